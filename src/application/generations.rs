@@ -0,0 +1,285 @@
+//! Generations action - records and restores snapshots of the whole toolset.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use log::warn;
+
+use crate::domain::model::{GenerationPackage, Generations};
+use crate::domain::service::PackageRepository;
+use crate::provider::RepoId;
+use crate::runtime::Runtime;
+
+use super::switch::{SwitchAction, SwitchResult};
+
+/// Result of switching to a generation
+#[derive(Debug, Default)]
+pub struct GenerationSwitch {
+    /// Packages whose version or links changed
+    pub switched: Vec<(RepoId, SwitchResult)>,
+    /// Installed packages the generation does not know about; left untouched
+    pub not_in_generation: Vec<String>,
+}
+
+/// Generations action - snapshots package versions and link rules
+pub struct GenerationAction<'a, R: Runtime> {
+    runtime: &'a R,
+    package_repo: PackageRepository<'a, R>,
+    switch: SwitchAction<'a, R>,
+    path: PathBuf,
+}
+
+impl<'a, R: Runtime> GenerationAction<'a, R> {
+    /// Create a new generations action
+    pub fn new(runtime: &'a R, install_root: PathBuf) -> Self {
+        Self {
+            runtime,
+            package_repo: PackageRepository::new(runtime, install_root.clone()),
+            switch: SwitchAction::new(runtime, install_root.clone()),
+            path: Generations::path(&install_root),
+        }
+    }
+
+    /// Active version and link rules of every installed package
+    pub fn snapshot(&self) -> Result<BTreeMap<String, GenerationPackage>> {
+        Ok(self
+            .package_repo
+            .find_all_with_meta()?
+            .into_iter()
+            .filter(|(_, meta)| !meta.current_version.is_empty())
+            .map(|(_, meta)| {
+                let package = GenerationPackage {
+                    version: meta.current_version.clone(),
+                    links: meta.link_rules(),
+                };
+                (meta.name, package)
+            })
+            .collect())
+    }
+
+    /// Record the current toolset as a new generation
+    ///
+    /// Returns the new generation id, or None if nothing changed.
+    pub fn record(&self, created_at: u64, command: &str) -> Result<Option<u32>> {
+        let mut generations = self.load()?;
+        let snapshot = self.snapshot()?;
+        if snapshot.is_empty() && generations.generations.is_empty() {
+            return Ok(None);
+        }
+        let id = generations.record(created_at, command, snapshot);
+        if id.is_some() {
+            generations.save(self.runtime, &self.path)?;
+        }
+        Ok(id)
+    }
+
+    /// Load all kept generations
+    pub fn load(&self) -> Result<Generations> {
+        Generations::load(self.runtime, &self.path)
+    }
+
+    /// Id of the newest generation matching the current toolset, if any
+    pub fn current(&self, generations: &Generations) -> Result<Option<u32>> {
+        Ok(generations.matching(&self.snapshot()?))
+    }
+
+    /// Restore every package of a generation
+    ///
+    /// Fails without changing anything if a version of the generation is no
+    /// longer on disk.
+    pub fn switch(&self, id: u32) -> Result<GenerationSwitch> {
+        let generations = self.load()?;
+        let Some(generation) = generations.get(id) else {
+            anyhow::bail!("Generation {} does not exist", id);
+        };
+
+        // --- Phase 1: Every version must still be installed ---
+        let mut targets = Vec::new();
+        let mut missing = Vec::new();
+        for (name, package) in &generation.packages {
+            let repo = name.parse::<RepoId>()?;
+            let version_dir =
+                self.package_repo
+                    .version_dir(&repo.owner, &repo.repo, &package.version);
+            if self.runtime.exists(&version_dir) {
+                targets.push((repo, package));
+            } else {
+                missing.push(format!("{} {}", name, package.version));
+            }
+        }
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "Cannot switch to generation {}, these versions are no longer installed: {}",
+                id,
+                missing.join(", ")
+            );
+        }
+
+        // --- Phase 2: Switch packages that differ ---
+        let snapshot = self.snapshot()?;
+        let mut result = GenerationSwitch::default();
+        for (repo, package) in targets {
+            if snapshot.get(&repo.to_string()) == Some(package) {
+                continue;
+            }
+            let switched = self
+                .switch
+                .restore(&repo, &package.version, &package.links)?;
+            result.switched.push((repo, switched));
+        }
+        result.not_in_generation = snapshot
+            .into_keys()
+            .filter(|name| !generation.packages.contains_key(name))
+            .collect();
+        if !result.not_in_generation.is_empty() {
+            warn!(
+                "{} package(s) are not part of generation {}",
+                result.not_in_generation.len(),
+                id
+            );
+        }
+        Ok(result)
+    }
+
+    /// Delete generations, so that prune may remove the versions only they used
+    pub fn delete(&self, ids: &[u32]) -> Result<()> {
+        let mut generations = self.load()?;
+        let missing = generations.remove(ids);
+        if let Some(id) = missing.first() {
+            anyhow::bail!("Generation {} does not exist", id);
+        }
+        generations.save(self.runtime, &self.path)
+    }
+
+    /// Delete the generations that need removed versions of a package
+    ///
+    /// Returns the ids of the deleted generations.
+    pub fn forget_versions(&self, name: &str, versions: &[String]) -> Result<Vec<u32>> {
+        let mut generations = self.load()?;
+        let ids = generations.referencing(name, versions);
+        if !ids.is_empty() {
+            generations.remove(&ids);
+            generations.save(self.runtime, &self.path)?;
+        }
+        Ok(ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::{Generation, Meta};
+    use crate::runtime::MockRuntime;
+    use mockall::predicate::eq;
+    use std::path::Path;
+
+    const ROOT: &str = "/test/root";
+
+    /// Runtime with one installed package, owner/tool at `current`
+    fn runtime_with_package(current: &'static str) -> MockRuntime {
+        let root = PathBuf::from(ROOT);
+        let meta = Meta {
+            name: "owner/tool".into(),
+            current_version: current.into(),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&meta).unwrap();
+
+        let mut runtime = MockRuntime::new();
+        runtime
+            .expect_read_dir()
+            .with(eq(root.clone()))
+            .returning(|_| Ok(vec![PathBuf::from(ROOT).join("owner")]));
+        runtime
+            .expect_read_dir()
+            .with(eq(root.join("owner")))
+            .returning(|_| Ok(vec![PathBuf::from(ROOT).join("owner/tool")]));
+        runtime.expect_is_dir().returning(|_| true);
+        runtime
+            .expect_read_to_string()
+            .with(eq(root.join("owner/tool/meta.json")))
+            .returning(move |_| Ok(json.clone()));
+        runtime
+    }
+
+    fn generations_with(versions: &[&str]) -> Generations {
+        let mut generations = Generations::default();
+        for version in versions {
+            let packages = BTreeMap::from([(
+                "owner/tool".to_string(),
+                GenerationPackage {
+                    version: version.to_string(),
+                    links: vec![],
+                },
+            )]);
+            generations.record(0, "install owner/tool", packages);
+        }
+        generations
+    }
+
+    fn expect_generations_file(runtime: &mut MockRuntime, generations: Option<Generations>) {
+        let path = Generations::path(Path::new(ROOT));
+        let exists = generations.is_some();
+        runtime
+            .expect_exists()
+            .with(eq(path.clone()))
+            .returning(move |_| exists);
+        if let Some(generations) = generations {
+            let json = serde_json::to_string(&generations).unwrap();
+            runtime
+                .expect_read_to_string()
+                .with(eq(path))
+                .returning(move |_| Ok(json.clone()));
+        }
+    }
+
+    #[test]
+    fn test_record_first_generation() {
+        // --- Setup ---
+        let mut runtime = runtime_with_package("v1");
+        expect_generations_file(&mut runtime, None);
+        runtime.expect_exists().returning(|_| true);
+        runtime
+            .expect_write()
+            .withf(|path, content| {
+                let generations: Generations = serde_json::from_slice(content).unwrap();
+                let generation: &Generation = &generations.generations[0];
+                path == Generations::path(Path::new(ROOT))
+                    && generation.command == "install owner/tool"
+                    && generation.packages["owner/tool"].version == "v1"
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let action = GenerationAction::new(&runtime, PathBuf::from(ROOT));
+
+        // --- Execute & Verify ---
+        assert_eq!(action.record(10, "install owner/tool").unwrap(), Some(1));
+    }
+
+    #[test]
+    fn test_record_unchanged_toolset() {
+        let mut runtime = runtime_with_package("v1");
+        expect_generations_file(&mut runtime, Some(generations_with(&["v1"])));
+        runtime.expect_exists().returning(|_| true);
+        let action = GenerationAction::new(&runtime, PathBuf::from(ROOT));
+
+        // No write expected
+        assert_eq!(action.record(10, "upgrade").unwrap(), None);
+    }
+
+    #[test]
+    fn test_switch_to_pruned_generation() {
+        let mut runtime = runtime_with_package("v2");
+        expect_generations_file(&mut runtime, Some(generations_with(&["v1", "v2"])));
+        runtime
+            .expect_exists()
+            .with(eq(PathBuf::from(ROOT).join("owner/tool/v1")))
+            .returning(|_| false);
+        let action = GenerationAction::new(&runtime, PathBuf::from(ROOT));
+
+        let err = action.switch(1).unwrap_err();
+        assert!(err.to_string().contains("owner/tool v1"));
+        assert!(action.switch(7).unwrap_err().to_string().contains("7"));
+    }
+}
@@ -15,11 +15,13 @@ use async_trait::async_trait;
 use log::{info, warn};
 
 use crate::commands::InstallOptions;
-use crate::domain::model::{Meta, VersionResolver};
+use crate::domain::model::{Meta, UrlSource, VersionConstraint, VersionResolver};
 use crate::domain::service::{LinkManager, PackageRepository};
 use crate::provider::{Provider, ProviderFactory, Release, RepoId};
 use crate::runtime::Runtime;
 
+use super::switch::{ExecutableLinks, SwitchAction};
+
 /// Result of resolving a version to install
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -88,7 +90,15 @@ pub trait InstallOperations: Send + Sync {
     fn update_current_link(&self, repo: &RepoId, version: &str) -> Result<()>;
 
     /// Update external links based on metadata
-    fn update_external_links(&self, meta: &Meta, version_dir: &Path) -> Result<()>;
+    fn update_external_links(&self, meta: &mut Meta, version_dir: &Path) -> Result<()>;
+
+    /// Link the executables of a version into a bin directory, recording a link rule for each
+    fn link_executables(
+        &self,
+        meta: &mut Meta,
+        version_dir: &Path,
+        bin_dir: &Path,
+    ) -> Result<ExecutableLinks>;
 
     /// Save metadata after successful installation
     fn save_meta(&self, repo: &RepoId, meta: &Meta) -> Result<()>;
@@ -98,6 +108,9 @@ pub trait InstallOperations: Send + Sync {
 
     /// Resolve source from existing metadata (for update/upgrade)
     fn resolve_source_for_existing(&self, meta: &Meta) -> Result<Arc<dyn Provider>>;
+
+    /// Resolve the source for a package served from plain HTTPS
+    fn resolve_source_for_url(&self, source: &UrlSource) -> Result<Arc<dyn Provider>>;
 }
 
 /// Install action - platform-agnostic installation orchestration
@@ -106,6 +119,7 @@ pub struct InstallAction<'a, R: Runtime> {
     package_repo: PackageRepository<'a, R>,
     provider_factory: &'a ProviderFactory,
     link_manager: LinkManager<'a, R>,
+    switch: SwitchAction<'a, R>,
     install_root: PathBuf,
 }
 
@@ -121,6 +135,7 @@ impl<'a, R: Runtime> InstallAction<'a, R> {
             package_repo: PackageRepository::new(runtime, install_root.clone()),
             provider_factory,
             link_manager: LinkManager::new(runtime),
+            switch: SwitchAction::new(runtime, install_root.clone()),
             install_root,
         }
     }
@@ -214,21 +229,38 @@ impl<'a, R: Runtime> InstallAction<'a, R> {
         version: Option<&str>,
         pre: bool,
     ) -> Result<&'m Release> {
+        let available = || {
+            meta.channel_releases()
+                .take(5)
+                .map(|r| r.tag.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
         if let Some(ver) = version {
-            // Find specific version
-            VersionResolver::find_exact(&meta.releases, ver).ok_or_else(|| {
-                anyhow::anyhow!(
-                    "Version '{}' not found for {}. Available versions: {}",
-                    ver,
-                    meta.name,
-                    meta.releases
-                        .iter()
-                        .take(5)
-                        .map(|r| r.tag.as_str())
-                        .collect::<Vec<_>>()
-                        .join(", ")
-                )
-            })
+            match VersionConstraint::parse(ver)? {
+                // Highest release inside the range
+                VersionConstraint::Range(req) => {
+                    VersionResolver::find_in_range(meta.channel_releases(), &req, pre).ok_or_else(
+                        || {
+                            anyhow::anyhow!(
+                                "No release of {} matches '{}'. Available versions: {}",
+                                meta.name,
+                                ver,
+                                available()
+                            )
+                        },
+                    )
+                }
+                // Find specific version
+                _ => meta.find_release(ver).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Version '{}' not found for {}. Available versions: {}",
+                        ver,
+                        meta.name,
+                        available()
+                    )
+                }),
+            }
         } else if pre {
             // Latest including pre-releases
             meta.get_latest_release()
@@ -244,11 +276,17 @@ impl<'a, R: Runtime> InstallAction<'a, R> {
         }
     }
 
-    /// Get effective filters (user-provided or from saved meta)
+    /// Get effective filters (user-provided, saved in meta, or configured defaults)
     pub fn effective_filters(&self, options: &InstallOptions, meta: &Meta) -> Vec<String> {
         if options.filters.is_empty() && !meta.filters.is_empty() {
             info!("Using saved filters from meta: {:?}", meta.filters);
             meta.filters.clone()
+        } else if options.filters.is_empty() && !options.default_filters.is_empty() {
+            info!(
+                "Using default filters from config: {:?}",
+                options.default_filters
+            );
+            options.default_filters.clone()
         } else {
             options.filters.clone()
         }
@@ -283,102 +321,24 @@ impl<'a, R: Runtime> InstallAction<'a, R> {
     ///
     /// Uses atomic approach: first validate all links, then execute all updates
     #[tracing::instrument(skip(self, meta, version_dir))]
-    pub fn update_external_links(&self, meta: &Meta, version_dir: &Path) -> Result<()> {
-        use crate::domain::model::{LinkRule, LinkValidation};
-
-        // Collect all rules to process (including legacy linked_to)
-        let mut all_rules: Vec<LinkRule> = meta.links.clone();
-        if let Some(ref linked_to) = meta.linked_to {
-            all_rules.push(LinkRule {
-                dest: linked_to.clone(),
-                path: meta.linked_path.clone(),
-            });
-        }
-
-        if all_rules.is_empty() {
-            return Ok(());
-        }
-
-        let package_dir = version_dir
-            .parent()
-            .ok_or_else(|| anyhow::anyhow!("Version directory has no parent"))?;
-
-        // --- Phase 1: Validate all links ---
-        #[derive(Debug)]
-        struct ValidatedLink {
-            target: PathBuf,
-            dest: PathBuf,
-            needs_removal: bool,
-        }
-
-        let mut validated_links: Vec<ValidatedLink> = Vec::new();
-        let mut skipped: Vec<(PathBuf, String)> = Vec::new();
-        let mut errors: Vec<(PathBuf, String)> = Vec::new();
-
-        for rule in &all_rules {
-            match self
-                .link_manager
-                .validate_link(rule, version_dir, package_dir)
-            {
-                LinkValidation::Valid {
-                    target,
-                    dest,
-                    needs_removal,
-                } => {
-                    validated_links.push(ValidatedLink {
-                        target,
-                        dest,
-                        needs_removal,
-                    });
-                }
-                LinkValidation::Skip { dest, reason } => {
-                    log::debug!("Skipping link {:?}: {}", dest, reason);
-                    eprintln!("Warning: Skipping {:?} - {}", dest, reason);
-                    skipped.push((dest, reason));
-                }
-                LinkValidation::Error { dest, error } => {
-                    eprintln!("Error validating link {:?}: {}", dest, error);
-                    errors.push((dest, error));
-                }
-            }
-        }
-
-        // If there are validation errors, fail before making any changes
-        if !errors.is_empty() {
-            let error_msgs: Vec<String> = errors
-                .iter()
-                .map(|(dest, e)| format!("{:?}: {}", dest, e))
-                .collect();
-            anyhow::bail!(
-                "Link validation failed for {} link(s):\n  {}",
-                errors.len(),
-                error_msgs.join("\n  ")
-            );
-        }
-
-        // --- Phase 2: Execute all validated link updates ---
-        for validated in &validated_links {
-            // Remove existing symlink if needed
-            if validated.needs_removal {
-                self.link_manager.remove_link(&validated.dest)?;
-            }
-
-            // Create new symlink (create_link handles parent directory and relative path)
-            self.link_manager
-                .create_link(&validated.target, &validated.dest)?;
-            info!(
-                "Updated external link {:?} -> {:?}",
-                validated.dest, validated.target
-            );
-        }
-
-        if !skipped.is_empty() {
-            warn!("{} link(s) were skipped", skipped.len());
-        }
-
+    pub fn update_external_links(&self, meta: &mut Meta, version_dir: &Path) -> Result<()> {
+        let digests = self
+            .link_manager
+            .update_links(&meta.link_rules(), version_dir)?;
+        meta.record_link_digests(&digests);
         Ok(())
     }
 
+    /// Link the executables of a version into a bin directory
+    pub fn link_executables(
+        &self,
+        meta: &mut Meta,
+        version_dir: &Path,
+        bin_dir: &Path,
+    ) -> Result<ExecutableLinks> {
+        self.switch.link_executables(meta, version_dir, bin_dir)
+    }
+
     /// Save metadata after successful installation
     pub fn save_meta(&self, repo: &RepoId, meta: &Meta) -> Result<()> {
         self.package_repo.save(&repo.owner, &repo.repo, meta)
@@ -473,40 +433,19 @@ impl<'a, R: Runtime + 'static> InstallOperations for InstallAction<'a, R> {
     }
 
     fn resolve_version(&self, meta: &Meta, version: Option<String>, pre: bool) -> Result<Release> {
-        if let Some(ver) = version {
-            VersionResolver::find_exact(&meta.releases, &ver)
-                .cloned()
-                .ok_or_else(|| {
-                    anyhow::anyhow!(
-                        "Version '{}' not found for {}. Available versions: {}",
-                        ver,
-                        meta.name,
-                        meta.releases
-                            .iter()
-                            .take(5)
-                            .map(|r| r.tag.as_str())
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    )
-                })
-        } else if pre {
-            meta.get_latest_release()
-                .cloned()
-                .ok_or_else(|| anyhow::anyhow!("No release found for {}.", meta.name))
-        } else {
-            meta.get_latest_stable_release().cloned().ok_or_else(|| {
-                anyhow::anyhow!(
-                    "No stable release found for {}. Use --pre for pre-releases.",
-                    meta.name
-                )
-            })
-        }
+        InstallAction::resolve_version(self, meta, version.as_deref(), pre).cloned()
     }
 
     fn effective_filters(&self, options: &InstallOptions, meta: &Meta) -> Vec<String> {
         if options.filters.is_empty() && !meta.filters.is_empty() {
             info!("Using saved filters from meta: {:?}", meta.filters);
             meta.filters.clone()
+        } else if options.filters.is_empty() && !options.default_filters.is_empty() {
+            info!(
+                "Using default filters from config: {:?}",
+                options.default_filters
+            );
+            options.default_filters.clone()
         } else {
             options.filters.clone()
         }
@@ -537,10 +476,19 @@ impl<'a, R: Runtime + 'static> InstallOperations for InstallAction<'a, R> {
         self.link_manager.update_current_link(&package_dir, version)
     }
 
-    fn update_external_links(&self, meta: &Meta, version_dir: &Path) -> Result<()> {
+    fn update_external_links(&self, meta: &mut Meta, version_dir: &Path) -> Result<()> {
         self.update_external_links(meta, version_dir)
     }
 
+    fn link_executables(
+        &self,
+        meta: &mut Meta,
+        version_dir: &Path,
+        bin_dir: &Path,
+    ) -> Result<ExecutableLinks> {
+        InstallAction::link_executables(self, meta, version_dir, bin_dir)
+    }
+
     fn save_meta(&self, repo: &RepoId, meta: &Meta) -> Result<()> {
         self.package_repo.save(&repo.owner, &repo.repo, meta)
     }
@@ -552,6 +500,10 @@ impl<'a, R: Runtime + 'static> InstallOperations for InstallAction<'a, R> {
     fn resolve_source_for_existing(&self, meta: &Meta) -> Result<Arc<dyn Provider>> {
         Ok(self.provider_factory.provider_for_meta(meta))
     }
+
+    fn resolve_source_for_url(&self, source: &UrlSource) -> Result<Arc<dyn Provider>> {
+        Ok(self.provider_factory.url_provider(source))
+    }
 }
 
 #[cfg(test)]
@@ -562,7 +514,11 @@ mod tests {
 
     fn make_test_factory() -> ProviderFactory {
         let http_client = HttpClient::new(reqwest::Client::new());
-        ProviderFactory::new(http_client, "https://api.github.com")
+        ProviderFactory::new(
+            Arc::new(MockRuntime::new()),
+            http_client,
+            "https://api.github.com",
+        )
     }
 
     fn make_test_meta() -> Meta {
@@ -716,6 +672,29 @@ mod tests {
         assert_eq!(filters, vec!["*linux*"]);
     }
 
+    #[test]
+    fn test_effective_filters_from_config_defaults() {
+        let mut runtime = MockRuntime::new();
+        runtime
+            .expect_temp_dir()
+            .returning(|| std::path::PathBuf::from("/tmp"));
+
+        let factory = make_test_factory();
+        let action = InstallAction::new(&runtime, &factory, "/test".into());
+        let mut meta = make_test_meta();
+        let options = InstallOptions {
+            default_filters: vec!["*musl*".into()],
+            ..Default::default()
+        };
+
+        // Saved filters take precedence over configured defaults
+        assert_eq!(action.effective_filters(&options, &meta), vec!["*linux*"]);
+
+        // Without saved filters the configured defaults apply
+        meta.filters.clear();
+        assert_eq!(action.effective_filters(&options, &meta), vec!["*musl*"]);
+    }
+
     #[test]
     fn test_version_dir() {
         let mut runtime = MockRuntime::new();
@@ -762,7 +741,7 @@ mod tests {
         let factory = make_test_factory();
         let action = InstallAction::new(&runtime, &factory, "/root".into());
 
-        let meta = Meta {
+        let mut meta = Meta {
             name: "o/r".into(),
             current_version: "v1".into(),
             links: vec![],
@@ -770,7 +749,7 @@ mod tests {
         };
 
         let version_dir = PathBuf::from("/root/o/r/v1");
-        let result = action.update_external_links(&meta, &version_dir);
+        let result = action.update_external_links(&mut meta, &version_dir);
         assert!(result.is_ok());
     }
 
@@ -786,12 +765,14 @@ mod tests {
         let link_target = PathBuf::from("/root/o/r/v2/tool");
         let old_target = PathBuf::from("/root/o/r/v1/tool");
 
-        let meta = Meta {
+        let mut meta = Meta {
             name: "o/r".into(),
             current_version: "v2".into(),
             links: vec![crate::domain::model::LinkRule {
                 dest: linked_to.clone(),
                 path: None,
+                shim: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -847,7 +828,7 @@ mod tests {
 
         let factory = make_test_factory();
         let action = InstallAction::new(&runtime, &factory, "/root".into());
-        let result = action.update_external_links(&meta, &version_dir);
+        let result = action.update_external_links(&mut meta, &version_dir);
         assert!(result.is_ok());
     }
 
@@ -863,17 +844,21 @@ mod tests {
         let linked_to2 = PathBuf::from("/usr/local/bin/tool2");
         let link_target = PathBuf::from("/root/o/r/v1/tool");
 
-        let meta = Meta {
+        let mut meta = Meta {
             name: "o/r".into(),
             current_version: "v1".into(),
             links: vec![
                 crate::domain::model::LinkRule {
                     dest: linked_to1.clone(),
                     path: None,
+                    shim: None,
+                    ..Default::default()
                 },
                 crate::domain::model::LinkRule {
                     dest: linked_to2.clone(),
                     path: Some("nonexistent".to_string()),
+                    shim: None,
+                    ..Default::default()
                 },
             ],
             ..Default::default()
@@ -910,7 +895,7 @@ mod tests {
 
         let factory = make_test_factory();
         let action = InstallAction::new(&runtime, &factory, "/root".into());
-        let result = action.update_external_links(&meta, &version_dir);
+        let result = action.update_external_links(&mut meta, &version_dir);
         assert!(result.is_err());
         let err_msg = result.unwrap_err().to_string();
         assert!(err_msg.contains("validation failed") || err_msg.contains("does not exist"));
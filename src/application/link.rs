@@ -10,7 +10,9 @@ use std::path::{Path, PathBuf};
 use anyhow::Result;
 use log::{debug, info, warn};
 
-use crate::domain::model::{LinkRule, LinkStatus, PackageContext, RemoveLinkResult, VersionedLink};
+use crate::domain::model::{
+    LinkMode, LinkRule, LinkStatus, PackageContext, RemoveLinkResult, Shim, VersionedLink,
+};
 use crate::domain::service::{LinkManager, PackageRepository};
 use crate::runtime::{Runtime, resolve_relative_path};
 
@@ -94,6 +96,17 @@ impl<'a, R: Runtime> LinkAction<'a, R> {
         self.link_manager.check_link(dest, expected_prefix)
     }
 
+    /// Check link status of a rule, which may be a copy or hard link
+    pub fn check_rule_link(
+        &self,
+        dest: &Path,
+        digest: Option<&str>,
+        expected_prefix: &Path,
+    ) -> LinkStatus {
+        self.link_manager
+            .check_rule_link(dest, digest, expected_prefix)
+    }
+
     /// Check if a path exists
     pub fn exists(&self, path: &Path) -> bool {
         self.runtime.exists(path)
@@ -112,13 +125,15 @@ impl<'a, R: Runtime> LinkAction<'a, R> {
     /// - Resolving the destination path (relative to cwd if needed)
     /// - Handling trailing slash (forces directory behavior)
     /// - Detecting if destination is a directory or managed symlink
-    /// - Creating the actual symlink
+    /// - Creating the actual symlink, or a shim
     /// - Updating package metadata
     ///
     /// # Arguments
     /// * `ctx` - Package context (must have version resolved)
     /// * `dest` - Destination path (may be relative)
     /// * `source_path` - Optional path inside version directory (e.g., "bin/tool")
+    /// * `shim` - Write a launcher script with these settings instead of a symlink
+    /// * `mode` - Place a symlink, a copy or a hard link at the destination
     ///
     /// # Returns
     /// `LinkResult` with details about the created link
@@ -127,6 +142,8 @@ impl<'a, R: Runtime> LinkAction<'a, R> {
         ctx: &mut PackageContext,
         dest: PathBuf,
         source_path: Option<String>,
+        shim: Option<Shim>,
+        mode: LinkMode,
     ) -> Result<LinkResult> {
         let version_dir = ctx.version_dir();
         if !self.runtime.exists(version_dir) {
@@ -171,14 +188,41 @@ impl<'a, R: Runtime> LinkAction<'a, R> {
             self.resolve_link_destination(&dest, &link_target, ctx, has_trailing_slash)?;
 
         // Prepare destination (check conflicts, remove existing if safe)
-        self.prepare_link_destination(&final_dest, &ctx.package_dir)?;
-
-        // Create the symlink
-        self.create_link(&link_target, &final_dest)?;
+        let placed_digest = ctx
+            .meta
+            .links
+            .iter()
+            .map(|l| (&l.dest, &l.digest))
+            .chain(
+                ctx.meta
+                    .versioned_links
+                    .iter()
+                    .map(|l| (&l.dest, &l.digest)),
+            )
+            .find(|(d, _)| **d == final_dest)
+            .and_then(|(_, digest)| digest.clone());
+        self.link_manager.prepare_rule_destination(
+            &final_dest,
+            placed_digest.as_deref(),
+            &ctx.package_dir,
+        )?;
+
+        // Create the symlink, shim, copy or hard link
+        let digest =
+            self.link_manager
+                .create_rule_link(&link_target, &final_dest, shim.as_ref(), mode)?;
 
         // Update metadata
         let is_versioned = ctx.version_specified;
-        self.update_meta_after_link(ctx, &final_dest, source_path.clone(), is_versioned);
+        let rule = LinkRule {
+            dest: final_dest.clone(),
+            path: source_path,
+            shim,
+            mode,
+            digest,
+            auto: false,
+        };
+        self.update_meta_after_link(ctx, rule, is_versioned);
 
         // Save metadata
         self.package_repo.save(&ctx.owner, &ctx.repo, &ctx.meta)?;
@@ -267,49 +311,36 @@ impl<'a, R: Runtime> LinkAction<'a, R> {
     }
 
     /// Update package metadata after creating a link.
-    fn update_meta_after_link(
-        &self,
-        ctx: &mut PackageContext,
-        dest: &Path,
-        source_path: Option<String>,
-        is_versioned: bool,
-    ) {
+    fn update_meta_after_link(&self, ctx: &mut PackageContext, rule: LinkRule, is_versioned: bool) {
+        let dest = rule.dest.clone();
         if is_versioned {
             let new_link = VersionedLink {
                 version: ctx.version().to_string(),
-                dest: dest.to_path_buf(),
-                path: source_path,
+                dest: rule.dest,
+                path: rule.path,
+                shim: rule.shim,
+                mode: rule.mode,
+                digest: rule.digest,
             };
 
             // Remove any existing entry with same dest from default links
-            ctx.meta.links.retain(|l| l.dest != *dest);
+            ctx.meta.links.retain(|l| l.dest != dest);
 
             // Update or add versioned link
-            if let Some(existing) = ctx
-                .meta
-                .versioned_links
-                .iter_mut()
-                .find(|l| l.dest == *dest)
-            {
-                existing.version = new_link.version;
-                existing.path = new_link.path;
+            if let Some(existing) = ctx.meta.versioned_links.iter_mut().find(|l| l.dest == dest) {
+                *existing = new_link;
             } else {
                 ctx.meta.versioned_links.push(new_link);
             }
         } else {
-            let new_rule = LinkRule {
-                dest: dest.to_path_buf(),
-                path: source_path,
-            };
-
             // Remove any existing entry with same dest from versioned links
-            ctx.meta.versioned_links.retain(|l| l.dest != *dest);
+            ctx.meta.versioned_links.retain(|l| l.dest != dest);
 
             // Update or add default link
-            if let Some(existing) = ctx.meta.links.iter_mut().find(|l| l.dest == *dest) {
-                existing.path = new_rule.path;
+            if let Some(existing) = ctx.meta.links.iter_mut().find(|l| l.dest == dest) {
+                *existing = rule;
             } else {
-                ctx.meta.links.push(new_rule);
+                ctx.meta.links.push(rule);
             }
         }
 
@@ -386,10 +417,7 @@ impl<'a, R: Runtime> LinkAction<'a, R> {
                 .versioned_links
                 .iter()
                 .filter(|v| v.version == ctx.version().as_str())
-                .map(|v| LinkRule {
-                    dest: v.dest.clone(),
-                    path: v.path.clone(),
-                })
+                .map(|v| v.to_rule())
                 .collect()
         } else {
             debug!("No version specified, looking in standard links");
@@ -556,10 +584,11 @@ impl<'a, R: Runtime> LinkAction<'a, R> {
         for rule in rules_to_remove {
             debug!("Processing rule: {:?}", rule);
 
-            match self
-                .link_manager
-                .remove_link_safely(&rule.dest, &ctx.package_dir)?
-            {
+            match self.link_manager.remove_rule_link_safely(
+                &rule.dest,
+                rule.digest.as_deref(),
+                &ctx.package_dir,
+            )? {
                 RemoveLinkResult::Removed => {
                     info!("Removed symlink {:?}", rule.dest);
                     removed_count += 1;
@@ -576,6 +605,14 @@ impl<'a, R: Runtime> LinkAction<'a, R> {
                     error_count += 1;
                     continue; // Don't remove this rule from meta
                 }
+                RemoveLinkResult::Modified => {
+                    warn!(
+                        "File {:?} was modified since it was placed, keeping it",
+                        rule.dest
+                    );
+                    error_count += 1;
+                    continue; // Don't remove this rule from meta
+                }
                 RemoveLinkResult::ExternalTarget => {
                     if all {
                         warn!(
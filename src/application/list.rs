@@ -14,6 +14,10 @@ pub struct PackageInfo {
     pub name: String,
     /// Current installed version
     pub version: String,
+    /// Version the package is pinned to
+    pub pinned: Option<String>,
+    /// Repository the package is an alias of
+    pub source_repo: Option<String>,
 }
 
 /// List action - queries installed packages
@@ -36,12 +40,17 @@ impl<'a, R: Runtime> ListAction<'a, R> {
         Ok(packages
             .into_iter()
             .map(|(_path, meta)| PackageInfo {
-                name: meta.name,
                 version: if meta.current_version.is_empty() {
                     "(unknown)".to_string()
                 } else {
-                    meta.current_version
+                    meta.display_version(&meta.current_version).to_string()
                 },
+                pinned: meta
+                    .pinned
+                    .as_deref()
+                    .map(|p| meta.display_version(p).to_string()),
+                name: meta.name,
+                source_repo: meta.source_repo,
             })
             .collect())
     }
@@ -3,23 +3,31 @@
 //! This layer contains the application-specific business rules and orchestrates
 //! the flow of data between the CLI layer and domain services.
 
+mod generations;
 mod install;
 mod link;
 mod list;
+mod pin;
 mod prune;
 mod remove;
 mod show;
+mod switch;
 mod update;
 mod upgrade;
 
+pub use generations::{GenerationAction, GenerationSwitch};
 pub use install::{InstallAction, InstallOperations};
 pub use link::{LinkAction, LinkResult, UnlinkResult};
 pub use list::{ListAction, PackageInfo};
+pub use pin::PinAction;
 pub use prune::{PruneAction, PruneInfo};
 pub use remove::RemoveAction;
 pub use show::{PackageDetails, ShowAction};
+pub use switch::{ExecutableLinks, SwitchAction, SwitchResult};
 pub use update::{UpdateAction, UpdateResult};
-pub use upgrade::{UpdateCheck, UpgradeAction, UpgradeCandidate, UpgradeCheckResult};
+pub use upgrade::{
+    PolicyLimited, UpdateCheck, UpgradeAction, UpgradeCandidate, UpgradeCheckResult,
+};
 
 // Re-export options from commands layer
 pub use crate::commands::{InstallOptions, UpgradeOptions};
@@ -0,0 +1,237 @@
+//! Pin action - holds packages at a version or within an upgrade policy.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::domain::model::UpgradePolicy;
+use crate::domain::service::PackageRepository;
+use crate::provider::RepoId;
+use crate::runtime::Runtime;
+
+/// Pin action - records and clears version holds and upgrade policies in package metadata
+pub struct PinAction<'a, R: Runtime> {
+    package_repo: PackageRepository<'a, R>,
+}
+
+impl<'a, R: Runtime> PinAction<'a, R> {
+    /// Create a new pin action
+    pub fn new(runtime: &'a R, install_root: PathBuf) -> Self {
+        Self {
+            package_repo: PackageRepository::new(runtime, install_root),
+        }
+    }
+
+    /// Pin a package to a version (default: the current version)
+    ///
+    /// The version must be a known release or installed on disk.
+    /// Returns the pinned tag.
+    pub fn pin(&self, repo: &RepoId, version: Option<&str>) -> Result<String> {
+        let mut meta = self.package_repo.load_required(&repo.owner, &repo.repo)?;
+
+        let tag = match version {
+            Some(v) => match meta.find_release(v) {
+                Some(release) => release.tag.clone(),
+                None if self
+                    .package_repo
+                    .installed_versions(&repo.owner, &repo.repo)?
+                    .iter()
+                    .any(|installed| installed == v) =>
+                {
+                    v.to_string()
+                }
+                None => anyhow::bail!(
+                    "Version {} not found for {}. Run `ghri update` to refresh the release list.",
+                    v,
+                    repo
+                ),
+            },
+            None if meta.current_version.is_empty() => {
+                anyhow::bail!(
+                    "No current version set for {}. Specify a version to pin.",
+                    repo
+                )
+            }
+            None => meta.current_version.clone(),
+        };
+
+        meta.pinned = Some(tag.clone());
+        self.package_repo.save(&repo.owner, &repo.repo, &meta)?;
+        Ok(tag)
+    }
+
+    /// Remove the pin from a package
+    ///
+    /// Returns the version it was pinned to, if any.
+    pub fn unpin(&self, repo: &RepoId) -> Result<Option<String>> {
+        let mut meta = self.package_repo.load_required(&repo.owner, &repo.repo)?;
+        let pinned = meta.pinned.take();
+        if pinned.is_some() {
+            self.package_repo.save(&repo.owner, &repo.repo, &meta)?;
+        }
+        Ok(pinned)
+    }
+
+    /// Current default upgrade policy of a package
+    pub fn policy(&self, repo: &RepoId) -> Result<Option<UpgradePolicy>> {
+        let meta = self.package_repo.load_required(&repo.owner, &repo.repo)?;
+        Ok(meta.upgrade_policy)
+    }
+
+    /// Set or clear the default upgrade policy of a package
+    pub fn set_policy(&self, repo: &RepoId, policy: Option<UpgradePolicy>) -> Result<()> {
+        let mut meta = self.package_repo.load_required(&repo.owner, &repo.repo)?;
+        if meta.upgrade_policy != policy {
+            meta.upgrade_policy = policy;
+            self.package_repo.save(&repo.owner, &repo.repo, &meta)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::Meta;
+    use crate::provider::Release;
+    use crate::runtime::MockRuntime;
+    use mockall::predicate::eq;
+    use std::path::Path;
+
+    const META_PATH: &str = "/test/root/owner/repo/meta.json";
+
+    fn runtime_with_meta(meta: Meta, saved: Option<Option<&'static str>>) -> MockRuntime {
+        let mut runtime = MockRuntime::new();
+        let json = serde_json::to_string(&meta).unwrap();
+        // meta.json and its package directory exist
+        runtime.expect_exists().returning(|_| true);
+        runtime
+            .expect_read_to_string()
+            .with(eq(PathBuf::from(META_PATH)))
+            .returning(move |_| Ok(json.clone()));
+        if let Some(expected) = saved {
+            runtime
+                .expect_write()
+                .withf(move |path, content| {
+                    let meta: Meta = serde_json::from_slice(content).unwrap();
+                    path == Path::new(META_PATH) && meta.pinned.as_deref() == expected
+                })
+                .times(1)
+                .returning(|_, _| Ok(()));
+        }
+        runtime
+    }
+
+    fn meta(current: &str) -> Meta {
+        Meta {
+            name: "owner/repo".into(),
+            current_version: current.into(),
+            releases: vec![Release {
+                tag: "v1.2.0".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn repo() -> RepoId {
+        "owner/repo".parse().unwrap()
+    }
+
+    #[test]
+    fn test_pin_current_version() {
+        let runtime = runtime_with_meta(meta("v1.0.0"), Some(Some("v1.0.0")));
+        let action = PinAction::new(&runtime, PathBuf::from("/test/root"));
+
+        assert_eq!(action.pin(&repo(), None).unwrap(), "v1.0.0");
+    }
+
+    #[test]
+    fn test_pin_normalizes_version() {
+        let runtime = runtime_with_meta(meta("v1.0.0"), Some(Some("v1.2.0")));
+        let action = PinAction::new(&runtime, PathBuf::from("/test/root"));
+
+        assert_eq!(action.pin(&repo(), Some("1.2.0")).unwrap(), "v1.2.0");
+    }
+
+    #[test]
+    fn test_pin_installed_version_without_release() {
+        let mut runtime = runtime_with_meta(meta("v1.0.0"), Some(Some("v1.0.0")));
+        runtime
+            .expect_read_dir()
+            .returning(|dir| Ok(vec![dir.join("v1.0.0"), dir.join("meta.json")]));
+        runtime
+            .expect_is_dir()
+            .returning(|path| path.ends_with("v1.0.0"));
+        let action = PinAction::new(&runtime, PathBuf::from("/test/root"));
+
+        assert_eq!(action.pin(&repo(), Some("v1.0.0")).unwrap(), "v1.0.0");
+    }
+
+    #[test]
+    fn test_pin_unknown_version() {
+        // --- Setup ---
+        let mut runtime = runtime_with_meta(meta("v1.0.0"), None);
+        runtime
+            .expect_read_dir()
+            .returning(|dir| Ok(vec![dir.join("v1.0.0"), dir.join("meta.json")]));
+        runtime
+            .expect_is_dir()
+            .returning(|path| path.ends_with("v1.0.0"));
+        runtime.expect_write().never();
+        let action = PinAction::new(&runtime, PathBuf::from("/test/root"));
+
+        // --- Execute ---
+        let err = action.pin(&repo(), Some("v1.2.O")).unwrap_err();
+
+        // --- Verify ---
+        assert!(err.to_string().contains("Version v1.2.O not found"));
+    }
+
+    #[test]
+    fn test_pin_without_current_version() {
+        let mut runtime = runtime_with_meta(meta(""), None);
+        // No current link to fall back on either
+        runtime
+            .expect_read_link()
+            .returning(|_| Err(anyhow::anyhow!("not a link")));
+        let action = PinAction::new(&runtime, PathBuf::from("/test/root"));
+
+        let err = action.pin(&repo(), None).unwrap_err();
+        assert!(err.to_string().contains("Specify a version"));
+    }
+
+    #[test]
+    fn test_unpin() {
+        let mut pinned = meta("v1.0.0");
+        pinned.pinned = Some("v1.0.0".into());
+        let runtime = runtime_with_meta(pinned, Some(None));
+        let action = PinAction::new(&runtime, PathBuf::from("/test/root"));
+        assert_eq!(action.unpin(&repo()).unwrap(), Some("v1.0.0".into()));
+
+        // Not pinned: nothing is written
+        let runtime = runtime_with_meta(meta("v1.0.0"), None);
+        let action = PinAction::new(&runtime, PathBuf::from("/test/root"));
+        assert_eq!(action.unpin(&repo()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_set_policy() {
+        let mut runtime = runtime_with_meta(meta("v1.0.0"), None);
+        runtime
+            .expect_write()
+            .withf(|_, content| {
+                let meta: Meta = serde_json::from_slice(content).unwrap();
+                meta.upgrade_policy == Some(UpgradePolicy::Minor)
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let action = PinAction::new(&runtime, PathBuf::from("/test/root"));
+
+        action
+            .set_policy(&repo(), Some(UpgradePolicy::Minor))
+            .unwrap();
+        // Unchanged policy: nothing is written
+        action.set_policy(&repo(), None).unwrap();
+    }
+}
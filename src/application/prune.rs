@@ -4,6 +4,7 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 
+use crate::domain::model::Generations;
 use crate::domain::service::PackageRepository;
 use crate::runtime::Runtime;
 
@@ -20,6 +21,8 @@ pub struct PruneInfo {
     pub current_version: Option<String>,
     /// Versions to be pruned
     pub versions_to_prune: Vec<String>,
+    /// Old versions kept because a generation still references them
+    pub retained: Vec<String>,
 }
 
 /// Prune action - identifies versions to remove
@@ -86,14 +89,17 @@ impl<'a, R: Runtime> PruneAction<'a, R> {
 
         let current_version = self.package_repo.current_version(owner, repo);
 
-        let versions_to_prune = if let Some(ref current) = current_version {
+        let (retained, versions_to_prune) = if let Some(ref current) = current_version {
+            let generations =
+                Generations::load(self.runtime, &Generations::path(&self.install_root))?;
+            let referenced = generations.referenced_versions(name);
             self.package_repo
                 .installed_versions(owner, repo)?
                 .into_iter()
                 .filter(|v| v != current)
-                .collect()
+                .partition(|v| referenced.contains(v.as_str()))
         } else {
-            vec![]
+            (vec![], vec![])
         };
 
         Ok(PruneInfo {
@@ -102,6 +108,7 @@ impl<'a, R: Runtime> PruneAction<'a, R> {
             repo: repo.to_string(),
             current_version,
             versions_to_prune,
+            retained,
         })
     }
 }
@@ -109,8 +116,10 @@ impl<'a, R: Runtime> PruneAction<'a, R> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::model::GenerationPackage;
     use crate::runtime::MockRuntime;
     use mockall::predicate::eq;
+    use std::collections::BTreeMap;
 
     #[test]
     fn test_find_prunable_not_installed() {
@@ -156,6 +165,11 @@ mod tests {
     fn test_find_prunable_with_old_versions() {
         let mut runtime = MockRuntime::new();
         let root = PathBuf::from("/test/root");
+        // No generations recorded
+        runtime
+            .expect_exists()
+            .with(eq(root.join("generations.json")))
+            .returning(|_| false);
         let package_dir = root.join("owner/repo");
         let current_link = package_dir.join("current");
 
@@ -192,6 +206,51 @@ mod tests {
         assert_eq!(info.versions_to_prune, vec!["v1.0.0"]);
     }
 
+    #[test]
+    fn test_find_prunable_keeps_generation_versions() {
+        // --- Setup ---
+        let mut runtime = MockRuntime::new();
+        let root = PathBuf::from("/test/root");
+        let mut generations = Generations::default();
+        generations.record(
+            0,
+            "install owner/repo",
+            BTreeMap::from([(
+                "owner/repo".to_string(),
+                GenerationPackage {
+                    version: "v1.0.0".into(),
+                    links: vec![],
+                },
+            )]),
+        );
+        let json = serde_json::to_string(&generations).unwrap();
+
+        runtime.expect_exists().returning(|_| true);
+        runtime
+            .expect_read_to_string()
+            .with(eq(root.join("generations.json")))
+            .returning(move |_| Ok(json.clone()));
+        runtime
+            .expect_read_link()
+            .returning(|_| Ok(PathBuf::from("v3.0.0")));
+        runtime.expect_read_dir().returning(|_| {
+            Ok(vec![
+                PathBuf::from("/test/root/owner/repo/v1.0.0"),
+                PathBuf::from("/test/root/owner/repo/v2.0.0"),
+                PathBuf::from("/test/root/owner/repo/v3.0.0"),
+            ])
+        });
+        runtime.expect_is_dir().returning(|_| true);
+        let action = PruneAction::new(&runtime, root);
+
+        // --- Execute ---
+        let info = action.find_prunable("owner", "repo", "owner/repo").unwrap();
+
+        // --- Verify ---
+        assert_eq!(info.versions_to_prune, vec!["v2.0.0"]);
+        assert_eq!(info.retained, vec!["v1.0.0"]);
+    }
+
     #[test]
     fn test_find_all_prunable_empty() {
         let mut runtime = MockRuntime::new();
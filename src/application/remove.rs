@@ -36,28 +36,39 @@ impl<'a, R: Runtime> RemoveAction<'a, R> {
     /// that point inside the given directory.
     fn remove_links_under(&self, meta: &Meta, dir: &Path) {
         for rule in &meta.links {
-            let _ = self.link_manager.remove_link_if_under(&rule.dest, dir);
+            let _ = self.link_manager.remove_rule_link_if_under(
+                &rule.dest,
+                rule.digest.as_deref(),
+                dir,
+            );
         }
         for link in &meta.versioned_links {
-            let _ = self.link_manager.remove_link_if_under(&link.dest, dir);
+            let _ = self.link_manager.remove_rule_link_if_under(
+                &link.dest,
+                link.digest.as_deref(),
+                dir,
+            );
         }
     }
 
     /// Remove links for a specific version only.
     ///
     /// For versioned_links, only removes links that match the version.
-    /// For regular links, removes if pointing to the version directory.
+    /// For regular links, removes if pointing to the version directory; copies
+    /// and hard links do not point anywhere and are left alone.
     fn remove_version_links(&self, meta: &Meta, version: &str, version_dir: &Path) {
-        for rule in &meta.links {
+        for rule in meta.links.iter().filter(|rule| rule.digest.is_none()) {
             let _ = self
                 .link_manager
                 .remove_link_if_under(&rule.dest, version_dir);
         }
         for link in &meta.versioned_links {
             if link.version == version {
-                let _ = self
-                    .link_manager
-                    .remove_link_if_under(&link.dest, version_dir);
+                let _ = self.link_manager.remove_rule_link_if_under(
+                    &link.dest,
+                    link.digest.as_deref(),
+                    version_dir,
+                );
             }
         }
     }
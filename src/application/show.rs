@@ -4,7 +4,7 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 
-use crate::domain::model::LinkRule;
+use crate::domain::model::{LinkRule, Meta, UpgradePolicy};
 use crate::domain::service::PackageRepository;
 use crate::provider::{PackageSpec, Release};
 use crate::runtime::Runtime;
@@ -36,6 +36,14 @@ pub struct PackageDetails {
     pub versioned_links: Vec<crate::domain::model::VersionedLink>,
     /// Path to current symlink
     pub current_version_path: Option<PathBuf>,
+    /// Version the package is pinned to
+    pub pinned: Option<String>,
+    /// Default upgrade policy
+    pub upgrade_policy: Option<UpgradePolicy>,
+    /// Tag pattern selecting the package's releases
+    pub tag_pattern: Option<String>,
+    /// Repository the package is an alias of
+    pub source_repo: Option<String>,
 }
 
 /// Show action - retrieves package details
@@ -90,9 +98,14 @@ impl<'a, R: Runtime> ShowAction<'a, R> {
                 .as_ref()
                 .filter(|m| !m.updated_at.is_empty())
                 .map(|m| m.updated_at.clone()),
+            // Newest version first, whatever order the cache was written in
             releases: meta
                 .as_ref()
-                .map(|m| m.releases.clone())
+                .map(|m| {
+                    let mut releases: Vec<Release> = m.channel_releases().cloned().collect();
+                    Meta::sort_releases_internal(&mut releases);
+                    releases
+                })
                 .unwrap_or_default(),
             links: meta.as_ref().map(|m| m.links.clone()).unwrap_or_default(),
             versioned_links: meta
@@ -100,6 +113,10 @@ impl<'a, R: Runtime> ShowAction<'a, R> {
                 .map(|m| m.versioned_links.clone())
                 .unwrap_or_default(),
             current_version_path,
+            pinned: meta.as_ref().and_then(|m| m.pinned.clone()),
+            upgrade_policy: meta.as_ref().and_then(|m| m.upgrade_policy),
+            tag_pattern: meta.as_ref().and_then(|m| m.tag_pattern.clone()),
+            source_repo: meta.as_ref().and_then(|m| m.source_repo.clone()),
         })
     }
 
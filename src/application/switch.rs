@@ -0,0 +1,574 @@
+//! Switch action - changes the active version of a package without network access.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use crate::domain::model::{LinkRule, Meta, VersionResolver};
+use crate::domain::service::{LinkManager, PackageRepository};
+use crate::provider::{Release, RepoId};
+use crate::runtime::Runtime;
+
+/// Result of switching the active version of a package
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitchResult {
+    /// Version that was active before the switch
+    pub from: String,
+    /// Version that is active now
+    pub to: String,
+    /// Changes to the executables linked into the bin directory
+    pub executables: ExecutableLinks,
+}
+
+/// Result of linking a package's executables into a bin directory
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutableLinks {
+    /// Links created for newly found executables
+    pub linked: Vec<PathBuf>,
+    /// Links removed because their executable is gone from the version
+    pub removed: Vec<PathBuf>,
+    /// Destinations left alone, with the reason
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+/// Switch action - points `current` and the link rules at an installed version
+pub struct SwitchAction<'a, R: Runtime> {
+    runtime: &'a R,
+    package_repo: PackageRepository<'a, R>,
+    link_manager: LinkManager<'a, R>,
+}
+
+impl<'a, R: Runtime> SwitchAction<'a, R> {
+    /// Create a new switch action
+    pub fn new(runtime: &'a R, install_root: PathBuf) -> Self {
+        Self {
+            runtime,
+            package_repo: PackageRepository::new(runtime, install_root),
+            link_manager: LinkManager::new(runtime),
+        }
+    }
+
+    /// Versions of a package present on disk, newest first
+    pub fn installed_versions(&self, repo: &RepoId) -> Result<Vec<String>> {
+        let mut releases: Vec<Release> = self
+            .package_repo
+            .installed_versions(&repo.owner, &repo.repo)?
+            .into_iter()
+            .map(|tag| Release {
+                tag,
+                ..Default::default()
+            })
+            .collect();
+        releases.sort_by(|a, b| VersionResolver::compare_releases(b, a));
+        Ok(releases.into_iter().map(|r| r.tag).collect())
+    }
+
+    /// Make an installed version active
+    ///
+    /// Only versions on disk are considered; `version` may omit the `v` prefix.
+    pub fn use_version(&self, repo: &RepoId, version: &str) -> Result<SwitchResult> {
+        let mut meta = self.package_repo.load_required(&repo.owner, &repo.repo)?;
+        let installed = self.installed_versions(repo)?;
+        let Some(tag) = installed
+            .iter()
+            .find(|v| VersionResolver::versions_match(v, version))
+            .cloned()
+        else {
+            let available = if installed.is_empty() {
+                "none".to_string()
+            } else {
+                installed.join(", ")
+            };
+            anyhow::bail!(
+                "{} {} is not installed. Installed versions: {}",
+                repo,
+                version,
+                available
+            );
+        };
+
+        let executables = self.activate(repo, &mut meta, &tag)?;
+
+        let from = meta.current_version.clone();
+        meta.set_current_version(&tag);
+        self.package_repo.save(&repo.owner, &repo.repo, &meta)?;
+
+        Ok(SwitchResult {
+            from,
+            to: tag,
+            executables,
+        })
+    }
+
+    /// Switch back to the previously active version
+    pub fn rollback(&self, repo: &RepoId) -> Result<SwitchResult> {
+        let mut meta = self.package_repo.load_required(&repo.owner, &repo.repo)?;
+        let Some(previous) = meta.history.last().cloned() else {
+            anyhow::bail!("No previous version recorded for {}", repo);
+        };
+
+        let version_dir = self
+            .package_repo
+            .version_dir(&repo.owner, &repo.repo, &previous);
+        if !self.runtime.exists(&version_dir) {
+            anyhow::bail!(
+                "Previous version {} of {} is no longer installed (was it pruned?). \
+                 Install it again with: ghri install {}@{}",
+                previous,
+                repo,
+                repo,
+                previous
+            );
+        }
+
+        let executables = self.activate(repo, &mut meta, &previous)?;
+
+        // Going back consumes the history entry, so repeated rollbacks keep walking back
+        meta.history.pop();
+        let from = std::mem::replace(&mut meta.current_version, previous.clone());
+        self.package_repo.save(&repo.owner, &repo.repo, &meta)?;
+
+        Ok(SwitchResult {
+            from,
+            to: previous,
+            executables,
+        })
+    }
+
+    /// Restore a recorded state: make `version` active with exactly `links` as link rules
+    ///
+    /// Symlinks of current rules that are not in `links`, or that change kind, are removed.
+    pub fn restore(
+        &self,
+        repo: &RepoId,
+        version: &str,
+        links: &[LinkRule],
+    ) -> Result<SwitchResult> {
+        let mut meta = self.package_repo.load_required(&repo.owner, &repo.repo)?;
+        let version_dir = self
+            .package_repo
+            .version_dir(&repo.owner, &repo.repo, version);
+        if !self.runtime.exists(&version_dir) {
+            anyhow::bail!("{} {} is no longer installed", repo, version);
+        }
+
+        let package_dir = self.package_repo.package_dir(&repo.owner, &repo.repo);
+        let mut links = links.to_vec();
+        for rule in meta.link_rules() {
+            match links.iter_mut().find(|link| link.dest == rule.dest) {
+                // A placed file is replaced by what it holds now, not when recorded
+                Some(link)
+                    if link.mode == rule.mode && link.shim.is_some() == rule.shim.is_some() =>
+                {
+                    link.digest = rule.digest;
+                }
+                _ => {
+                    self.link_manager.remove_rule_link_if_under(
+                        &rule.dest,
+                        rule.digest.as_deref(),
+                        &package_dir,
+                    )?;
+                }
+            }
+        }
+        meta.links = links;
+        meta.linked_to = None;
+        meta.linked_path = None;
+
+        let executables = self.activate(repo, &mut meta, version)?;
+
+        let from = meta.current_version.clone();
+        meta.set_current_version(version);
+        self.package_repo.save(&repo.owner, &repo.repo, &meta)?;
+
+        Ok(SwitchResult {
+            from,
+            to: version.to_string(),
+            executables,
+        })
+    }
+
+    /// Link the executables of a version into a bin directory
+    ///
+    /// Executables without a link rule get one, unless another package already
+    /// links to the same destination or the destination is not ours to
+    /// replace. Rules into the bin directory whose executable is gone from the
+    /// version are dropped, so the remaining links can be updated.
+    pub fn link_executables(
+        &self,
+        meta: &mut Meta,
+        version_dir: &Path,
+        bin_dir: &Path,
+    ) -> Result<ExecutableLinks> {
+        let package_dir = version_dir
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Version directory has no parent"))?;
+        let mut result = ExecutableLinks::default();
+
+        // Executables that no longer exist would fail the link update; rules
+        // the user made with `ghri link` are left for them to fix
+        let (gone, kept): (Vec<LinkRule>, Vec<LinkRule>) = std::mem::take(&mut meta.links)
+            .into_iter()
+            .partition(|rule| {
+                rule.auto
+                    && rule.dest.parent() == Some(bin_dir)
+                    && rule
+                        .path
+                        .as_ref()
+                        .is_some_and(|path| !self.runtime.exists(&version_dir.join(path)))
+            });
+        meta.links = kept;
+        for rule in gone {
+            self.link_manager.remove_rule_link_if_under(
+                &rule.dest,
+                rule.digest.as_deref(),
+                package_dir,
+            )?;
+            result.removed.push(rule.dest);
+        }
+
+        // Destinations linked by other packages
+        let mut taken: HashMap<PathBuf, String> = HashMap::new();
+        for (_, other) in self.package_repo.find_all_with_meta()? {
+            if other.name == meta.name {
+                continue;
+            }
+            let dests = other.link_rules().into_iter().map(|rule| rule.dest);
+            let versioned = other.versioned_links.iter().map(|link| link.dest.clone());
+            for dest in dests.chain(versioned) {
+                taken.insert(dest, other.name.clone());
+            }
+        }
+
+        for target in self.link_manager.find_executables(version_dir)? {
+            let (Some(name), Ok(path)) = (target.file_name(), target.strip_prefix(version_dir))
+            else {
+                continue;
+            };
+            let dest = bin_dir.join(name);
+            let managed = meta.links.iter().any(|rule| rule.dest == dest)
+                || meta.versioned_links.iter().any(|link| link.dest == dest);
+            if managed {
+                continue;
+            }
+            if let Some(owner) = taken.get(&dest) {
+                result
+                    .skipped
+                    .push((dest, format!("already linked by {}", owner)));
+                continue;
+            }
+            if let Err(e) = self
+                .link_manager
+                .prepare_link_destination(&dest, package_dir)
+            {
+                result.skipped.push((dest, e.to_string()));
+                continue;
+            }
+            self.link_manager.create_link(&target, &dest)?;
+            meta.links.push(LinkRule {
+                dest: dest.clone(),
+                path: Some(path.to_string_lossy().into_owned()),
+                auto: true,
+                ..Default::default()
+            });
+            result.linked.push(dest);
+        }
+        Ok(result)
+    }
+
+    /// Point the link rules and `current` at an installed version
+    ///
+    /// Executables in the bin directory are linked first, dropping rules for
+    /// ones the version lacks. The remaining links are validated before they
+    /// change, so a failed switch leaves them as they were.
+    fn activate(&self, repo: &RepoId, meta: &mut Meta, version: &str) -> Result<ExecutableLinks> {
+        let version_dir = self
+            .package_repo
+            .version_dir(&repo.owner, &repo.repo, version);
+        let executables = match meta.bin_dir.clone() {
+            Some(bin_dir) => self.link_executables(meta, &version_dir, &bin_dir)?,
+            None => ExecutableLinks::default(),
+        };
+        let digests = self
+            .link_manager
+            .update_links(&meta.link_rules(), &version_dir)?;
+        meta.record_link_digests(&digests);
+
+        let package_dir = self.package_repo.package_dir(&repo.owner, &repo.repo);
+        self.link_manager
+            .update_current_link(&package_dir, version)?;
+        Ok(executables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::MockRuntime;
+    use mockall::predicate::eq;
+    use std::path::Path;
+
+    const PACKAGE_DIR: &str = "/test/root/owner/repo";
+
+    fn repo() -> RepoId {
+        "owner/repo".parse().unwrap()
+    }
+
+    fn meta(current: &str, history: &[&str]) -> Meta {
+        Meta {
+            name: "owner/repo".into(),
+            current_version: current.into(),
+            history: history.iter().map(|v| v.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Runtime serving meta.json and reporting which version directories exist
+    fn runtime_with_meta(meta: Meta, installed: &'static [&'static str]) -> MockRuntime {
+        let mut runtime = MockRuntime::new();
+        let json = serde_json::to_string(&meta).unwrap();
+        runtime
+            .expect_read_to_string()
+            .with(eq(PathBuf::from(PACKAGE_DIR).join("meta.json")))
+            .returning(move |_| Ok(json.clone()));
+        runtime.expect_exists().returning(move |path| {
+            let name = path.file_name().unwrap().to_str().unwrap();
+            path == Path::new(PACKAGE_DIR)
+                || path.parent() == Some(Path::new(PACKAGE_DIR))
+                    && (name == "meta.json" || name == "current" || installed.contains(&name))
+        });
+        runtime
+    }
+
+    #[test]
+    fn test_rollback() {
+        // --- Setup ---
+        let mut runtime = runtime_with_meta(meta("v2", &["v0", "v1"]), &["v1", "v2"]);
+        let current = PathBuf::from(PACKAGE_DIR).join("current");
+        runtime.expect_is_symlink().returning(|_| true);
+        runtime
+            .expect_read_link()
+            .returning(|_| Ok(PathBuf::from("v2")));
+        runtime
+            .expect_remove_symlink()
+            .with(eq(current.clone()))
+            .times(1)
+            .returning(|_| Ok(()));
+        runtime
+            .expect_symlink()
+            .with(eq(PathBuf::from("v1")), eq(current))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        runtime
+            .expect_write()
+            .withf(|_, content| {
+                let meta: Meta = serde_json::from_slice(content).unwrap();
+                meta.current_version == "v1" && meta.history == vec!["v0"]
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let action = SwitchAction::new(&runtime, PathBuf::from("/test/root"));
+
+        // --- Execute ---
+        let result = action.rollback(&repo()).unwrap();
+
+        // --- Verify ---
+        assert_eq!(
+            result,
+            SwitchResult {
+                from: "v2".into(),
+                to: "v1".into(),
+                executables: ExecutableLinks::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_rollback_pruned_version() {
+        let runtime = runtime_with_meta(meta("v2", &["v1"]), &["v2"]);
+        let action = SwitchAction::new(&runtime, PathBuf::from("/test/root"));
+
+        let err = action.rollback(&repo()).unwrap_err();
+        assert!(err.to_string().contains("no longer installed"));
+        assert!(err.to_string().contains("ghri install owner/repo@v1"));
+    }
+
+    #[test]
+    fn test_rollback_without_history() {
+        let runtime = runtime_with_meta(meta("v2", &[]), &["v2"]);
+        let action = SwitchAction::new(&runtime, PathBuf::from("/test/root"));
+
+        let err = action.rollback(&repo()).unwrap_err();
+        assert!(err.to_string().contains("No previous version"));
+    }
+
+    /// Expect the package directory listing to contain `versions`
+    fn expect_version_dirs(runtime: &mut MockRuntime, versions: &'static [&'static str]) {
+        runtime.expect_read_dir().returning(move |_| {
+            let mut entries: Vec<PathBuf> = versions
+                .iter()
+                .map(|v| PathBuf::from(PACKAGE_DIR).join(v))
+                .collect();
+            entries.push(PathBuf::from(PACKAGE_DIR).join("meta.json"));
+            Ok(entries)
+        });
+        runtime
+            .expect_is_dir()
+            .returning(|path| path.extension().is_none_or(|ext| ext != "json"));
+    }
+
+    #[test]
+    fn test_installed_versions_newest_first() {
+        let mut runtime = runtime_with_meta(meta("v1.2.0", &[]), &[]);
+        expect_version_dirs(&mut runtime, &["v1.10.0", "v1.2.0", "v1.9.0"]);
+        let action = SwitchAction::new(&runtime, PathBuf::from("/test/root"));
+
+        assert_eq!(
+            action.installed_versions(&repo()).unwrap(),
+            vec!["v1.10.0", "v1.9.0", "v1.2.0"]
+        );
+    }
+
+    #[test]
+    fn test_use_version() {
+        // --- Setup ---
+        let mut runtime = runtime_with_meta(meta("v2", &[]), &["v1", "v2"]);
+        expect_version_dirs(&mut runtime, &["v1", "v2"]);
+        let current = PathBuf::from(PACKAGE_DIR).join("current");
+        runtime.expect_is_symlink().returning(|_| true);
+        runtime
+            .expect_read_link()
+            .returning(|_| Ok(PathBuf::from("v2")));
+        runtime.expect_remove_symlink().returning(|_| Ok(()));
+        runtime
+            .expect_symlink()
+            .with(eq(PathBuf::from("v1")), eq(current))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        runtime
+            .expect_write()
+            .withf(|_, content| {
+                let meta: Meta = serde_json::from_slice(content).unwrap();
+                meta.current_version == "v1" && meta.history == vec!["v2"]
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let action = SwitchAction::new(&runtime, PathBuf::from("/test/root"));
+
+        // --- Execute ---
+        let result = action.use_version(&repo(), "1").unwrap();
+
+        // --- Verify ---
+        assert_eq!(result.from, "v2");
+        assert_eq!(result.to, "v1");
+    }
+
+    #[test]
+    fn test_use_version_not_installed() {
+        let mut runtime = runtime_with_meta(meta("v2", &[]), &["v1", "v2"]);
+        expect_version_dirs(&mut runtime, &["v1", "v2"]);
+        let action = SwitchAction::new(&runtime, PathBuf::from("/test/root"));
+
+        let err = action.use_version(&repo(), "v3").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "owner/repo v3 is not installed. Installed versions: v2, v1"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_link_executables() {
+        use crate::runtime::RealRuntime;
+
+        // --- Setup ---
+        let root = tempfile::tempdir().unwrap();
+        let install_root = root.path().join("root");
+        let bin_dir = root.path().join("bin");
+        let package_dir = install_root.join("owner/tool");
+        let version_dir = package_dir.join("v2.0.0");
+        for name in ["tool", "helper"] {
+            std::fs::create_dir_all(version_dir.join("bin")).unwrap();
+            std::fs::write(version_dir.join("bin").join(name), "#!/bin/sh\n").unwrap();
+        }
+        // `old` was an executable of v1.0.0 only
+        std::fs::create_dir_all(package_dir.join("v1.0.0/bin")).unwrap();
+        std::fs::write(package_dir.join("v1.0.0/bin/old"), "#!/bin/sh\n").unwrap();
+        std::fs::create_dir_all(&bin_dir).unwrap();
+        std::os::unix::fs::symlink(package_dir.join("v1.0.0/bin/old"), bin_dir.join("old"))
+            .unwrap();
+        // `mine` was linked by the user with `ghri link`
+        std::fs::write(package_dir.join("v1.0.0/bin/mine"), "#!/bin/sh\n").unwrap();
+        std::os::unix::fs::symlink(package_dir.join("v1.0.0/bin/mine"), bin_dir.join("mine"))
+            .unwrap();
+
+        let action = SwitchAction::new(&RealRuntime, install_root.clone());
+        let other = Meta {
+            name: "other/pkg".into(),
+            links: vec![LinkRule {
+                dest: bin_dir.join("helper"),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        PackageRepository::new(&RealRuntime, install_root)
+            .save("other", "pkg", &other)
+            .unwrap();
+        let mut meta = Meta {
+            name: "owner/tool".into(),
+            links: vec![
+                LinkRule {
+                    dest: bin_dir.join("old"),
+                    path: Some("bin/old".into()),
+                    auto: true,
+                    ..Default::default()
+                },
+                LinkRule {
+                    dest: bin_dir.join("mine"),
+                    path: Some("bin/mine".into()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        // --- Execute ---
+        let links = action
+            .link_executables(&mut meta, &version_dir, &bin_dir)
+            .unwrap();
+
+        // --- Verify ---
+        assert_eq!(
+            links,
+            ExecutableLinks {
+                linked: vec![bin_dir.join("tool")],
+                removed: vec![bin_dir.join("old")],
+                skipped: vec![(bin_dir.join("helper"), "already linked by other/pkg".into())],
+            }
+        );
+        assert_eq!(
+            meta.links,
+            vec![
+                LinkRule {
+                    dest: bin_dir.join("mine"),
+                    path: Some("bin/mine".into()),
+                    ..Default::default()
+                },
+                LinkRule {
+                    dest: bin_dir.join("tool"),
+                    path: Some("bin/tool".into()),
+                    auto: true,
+                    ..Default::default()
+                }
+            ]
+        );
+        assert_eq!(
+            std::fs::canonicalize(bin_dir.join("tool")).unwrap(),
+            std::fs::canonicalize(version_dir.join("bin/tool")).unwrap()
+        );
+        assert!(!bin_dir.join("old").is_symlink());
+        assert!(bin_dir.join("mine").is_symlink());
+        assert!(!bin_dir.join("helper").exists());
+    }
+}
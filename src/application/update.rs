@@ -6,6 +6,7 @@
 //! - Fetching fresh metadata from sources
 //! - Checking for available updates
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use anyhow::Result;
@@ -68,6 +69,8 @@ impl<'a, R: Runtime> UpdateAction<'a, R> {
             .collect();
 
         let mut results = Vec::new();
+        // Aliases of one repository share a single fetch
+        let mut fetched = HashMap::new();
 
         for (_meta_path, meta) in packages {
             let repo = match meta.name.parse::<RepoId>() {
@@ -84,7 +87,7 @@ impl<'a, R: Runtime> UpdateAction<'a, R> {
             }
 
             // Update this package
-            match self.update_package(&repo, &meta).await {
+            match self.update_package(&repo, &meta, &mut fetched).await {
                 Ok(result) => results.push(result),
                 Err(e) => {
                     warn!("Failed to update {}: {}", repo, e);
@@ -96,14 +99,43 @@ impl<'a, R: Runtime> UpdateAction<'a, R> {
     }
 
     /// Update metadata for a single package
-    async fn update_package(&self, repo: &RepoId, meta: &Meta) -> Result<UpdateResult> {
-        // Resolve source from package metadata
-        let source = self.provider_factory.provider_for_meta(meta);
+    ///
+    /// `fetched` caches fresh metadata by source repository and API URL.
+    async fn update_package(
+        &self,
+        repo: &RepoId,
+        meta: &Meta,
+        fetched: &mut HashMap<(RepoId, String), Meta>,
+    ) -> Result<UpdateResult> {
+        // Local artifacts have nothing to refresh
+        if meta.from_file {
+            return Ok(UpdateResult {
+                repo: repo.clone(),
+                current_version: meta.current_version.clone(),
+                latest_version: None,
+                has_update: false,
+            });
+        }
 
-        // Fetch new metadata using saved API URL
-        let new_meta = self
-            .fetch_meta(repo, source.as_ref(), &meta.api_url, &meta.current_version)
-            .await?;
+        // Fetch new metadata from the source repository using saved API URL
+        let key = (meta.source_repo()?, meta.api_url.clone());
+        let new_meta = match fetched.get(&key) {
+            Some(new_meta) => new_meta.clone(),
+            None => {
+                // Resolve source from package metadata
+                let source = self.provider_factory.provider_for_meta(meta);
+                let new_meta = self
+                    .fetch_meta(
+                        &key.0,
+                        source.as_ref(),
+                        &meta.api_url,
+                        &meta.current_version,
+                    )
+                    .await?;
+                fetched.insert(key, new_meta.clone());
+                new_meta
+            }
+        };
 
         // Merge with existing metadata
         let mut final_meta = meta.clone();
@@ -116,11 +148,10 @@ impl<'a, R: Runtime> UpdateAction<'a, R> {
             .save(&repo.owner, &repo.repo, &final_meta)?;
 
         // Check if update is available
-        let latest = VersionResolver::check_update(
-            &final_meta.releases,
-            &meta.current_version,
-            false, // don't include prereleases
-        );
+        // Latest stable release, inside the saved version range if any
+        let latest = final_meta
+            .upgrade_target(false)
+            .filter(|r| VersionResolver::is_newer(&final_meta.releases, r, &meta.current_version));
 
         Ok(UpdateResult {
             repo: repo.clone(),
@@ -164,10 +195,15 @@ mod tests {
     use super::*;
     use crate::http::HttpClient;
     use crate::runtime::MockRuntime;
+    use std::sync::Arc;
 
     fn make_test_factory() -> ProviderFactory {
         let http_client = HttpClient::new(reqwest::Client::new());
-        ProviderFactory::new(http_client, "https://api.github.com")
+        ProviderFactory::new(
+            Arc::new(MockRuntime::new()),
+            http_client,
+            "https://api.github.com",
+        )
     }
 
     #[test]
@@ -11,9 +11,9 @@ use std::sync::Arc;
 use anyhow::Result;
 use log::warn;
 
-use crate::domain::model::Meta;
+use crate::domain::model::{Meta, UpgradePolicy, VersionResolver};
 use crate::domain::service::PackageRepository;
-use crate::provider::{Provider, ProviderFactory, RepoId};
+use crate::provider::{Provider, ProviderFactory, Release, RepoId};
 use crate::runtime::Runtime;
 
 /// Result of checking for an update
@@ -21,10 +21,14 @@ use crate::runtime::Runtime;
 pub struct UpdateCheck<'a> {
     /// The package metadata
     pub meta: &'a Meta,
-    /// The latest available version (if update available)
+    /// The latest available version within the upgrade policy
     pub latest_version: Option<String>,
-    /// Whether an update is available
+    /// Whether an update is available within the upgrade policy
     pub has_update: bool,
+    /// The latest available version, ignoring the upgrade policy
+    pub latest_overall: Option<String>,
+    /// Whether the latest version overall is newer than the installed one
+    pub has_update_overall: bool,
 }
 
 /// Information about a package that needs upgrading
@@ -34,21 +38,42 @@ pub struct UpgradeCandidate {
     pub repo: RepoId,
     /// Current installed version
     pub current_version: String,
-    /// Latest available version
+    /// Latest available version within the upgrade policy
     pub latest_version: String,
+    /// Latest available version, ignoring the upgrade policy
+    pub latest_overall: String,
+    /// Upgrade policy that applied to this package
+    pub policy: Option<UpgradePolicy>,
     /// Package metadata
     pub meta: Meta,
 }
 
+/// A package whose newer releases are all outside its upgrade policy
+#[derive(Debug, Clone)]
+pub struct PolicyLimited {
+    /// Repository identifier
+    pub repo: RepoId,
+    /// Current installed version
+    pub current_version: String,
+    /// Latest available version, ignoring the upgrade policy
+    pub latest_overall: String,
+    /// Upgrade policy that held the package back
+    pub policy: UpgradePolicy,
+}
+
 /// Result of checking all packages for upgrades
 #[derive(Debug)]
 pub struct UpgradeCheckResult {
     /// Packages that have updates available
     pub upgradable: Vec<UpgradeCandidate>,
-    /// Packages that are already up to date
+    /// Packages that are already up to date, with versions as displayed
     pub up_to_date: Vec<(RepoId, String)>,
     /// Packages with no releases available
     pub no_releases: Vec<RepoId>,
+    /// Pinned packages and the version they are held at, as displayed
+    pub held: Vec<(RepoId, String)>,
+    /// Packages up to date within their policy, with newer releases outside it
+    pub limited: Vec<PolicyLimited>,
 }
 
 /// Upgrade action - checks and performs package upgrades
@@ -78,11 +103,13 @@ impl<'a, R: Runtime> UpgradeAction<'a, R> {
 
     /// Check all packages for available upgrades
     ///
-    /// Returns categorized results: upgradable, up-to-date, no-releases
+    /// Returns categorized results: upgradable, up-to-date, no-releases, held,
+    /// limited. `policy` overrides the upgrade policy saved for each package.
     pub fn check_all(
         &self,
         repo_filters: &[String],
         include_prerelease: bool,
+        policy: Option<UpgradePolicy>,
     ) -> Result<UpgradeCheckResult> {
         let packages = self.package_repo.find_all_with_meta()?;
 
@@ -96,6 +123,8 @@ impl<'a, R: Runtime> UpgradeAction<'a, R> {
             upgradable: Vec::new(),
             up_to_date: Vec::new(),
             no_releases: Vec::new(),
+            held: Vec::new(),
+            limited: Vec::new(),
         };
 
         for (_meta_path, meta) in packages {
@@ -112,22 +141,44 @@ impl<'a, R: Runtime> UpgradeAction<'a, R> {
                 continue;
             }
 
+            // Pinned packages are held, even when named explicitly
+            if let Some(pinned) = &meta.pinned {
+                let version = meta.display_version(pinned).to_string();
+                result.held.push((repo, version));
+                continue;
+            }
+
             // Check for available update
-            let check = self.check_update(&meta, include_prerelease);
+            let policy = policy.or(meta.upgrade_policy);
+            let check = self.check_update(&meta, include_prerelease, policy);
 
-            match check.latest_version {
-                Some(latest) if check.has_update => {
+            match (check.latest_version, check.latest_overall) {
+                (Some(latest), Some(latest_overall)) if check.has_update => {
                     result.upgradable.push(UpgradeCandidate {
                         repo,
                         current_version: meta.current_version.clone(),
                         latest_version: latest,
+                        latest_overall,
+                        policy,
                         meta,
                     });
                 }
-                Some(version) => {
+                (_, Some(latest_overall)) if check.has_update_overall => {
+                    if let Some(policy) = policy {
+                        result.limited.push(PolicyLimited {
+                            repo,
+                            current_version: meta.display_version(&meta.current_version).into(),
+                            latest_overall: meta.display_version(&latest_overall).into(),
+                            policy,
+                        });
+                    }
+                }
+                (latest, Some(latest_overall)) => {
+                    let latest = latest.unwrap_or(latest_overall);
+                    let version = meta.display_version(&latest).to_string();
                     result.up_to_date.push((repo, version));
                 }
-                None => {
+                (_, None) => {
                     result.no_releases.push(repo);
                 }
             }
@@ -136,28 +187,25 @@ impl<'a, R: Runtime> UpgradeAction<'a, R> {
         Ok(result)
     }
 
-    /// Check if a package has an available update
-    fn check_update<'m>(&self, meta: &'m Meta, include_prerelease: bool) -> UpdateCheck<'m> {
-        let latest = if include_prerelease {
-            meta.get_latest_release()
-        } else {
-            meta.get_latest_stable_release()
+    /// Check if a package has an available update, within a policy and overall
+    fn check_update<'m>(
+        &self,
+        meta: &'m Meta,
+        include_prerelease: bool,
+        policy: Option<UpgradePolicy>,
+    ) -> UpdateCheck<'m> {
+        // Only move forward: a release ranked below the current one is not an update
+        let is_newer = |release: &Release| {
+            VersionResolver::is_newer(&meta.releases, release, &meta.current_version)
         };
-
-        match latest {
-            Some(release) => {
-                let has_update = meta.current_version != release.tag;
-                UpdateCheck {
-                    meta,
-                    latest_version: Some(release.tag.clone()),
-                    has_update,
-                }
-            }
-            None => UpdateCheck {
-                meta,
-                latest_version: None,
-                has_update: false,
-            },
+        let target = meta.upgrade_target_within(include_prerelease, policy);
+        let overall = meta.upgrade_target(include_prerelease);
+        UpdateCheck {
+            meta,
+            latest_version: target.map(|r| r.tag.clone()),
+            has_update: target.is_some_and(is_newer),
+            latest_overall: overall.map(|r| r.tag.clone()),
+            has_update_overall: overall.is_some_and(is_newer),
         }
     }
 
@@ -171,12 +219,15 @@ impl<'a, R: Runtime> UpgradeAction<'a, R> {
 mod tests {
     use super::*;
     use crate::http::HttpClient;
-    use crate::provider::Release;
     use crate::runtime::MockRuntime;
 
     fn make_test_factory() -> ProviderFactory {
         let http_client = HttpClient::new(reqwest::Client::new());
-        ProviderFactory::new(http_client, "https://api.github.com")
+        ProviderFactory::new(
+            Arc::new(MockRuntime::new()),
+            http_client,
+            "https://api.github.com",
+        )
     }
 
     fn make_test_meta(current: &str, releases: Vec<(&str, bool)>) -> Meta {
@@ -207,7 +258,7 @@ mod tests {
         let action = UpgradeAction::new(&runtime, &factory, "/test".into());
 
         let meta = make_test_meta("v1.0.0", vec![("v2.0.0", false), ("v1.0.0", false)]);
-        let check = action.check_update(&meta, false);
+        let check = action.check_update(&meta, false, None);
 
         assert!(check.has_update);
         assert_eq!(check.latest_version, Some("v2.0.0".into()));
@@ -224,7 +275,7 @@ mod tests {
         let action = UpgradeAction::new(&runtime, &factory, "/test".into());
 
         let meta = make_test_meta("v2.0.0", vec![("v2.0.0", false), ("v1.0.0", false)]);
-        let check = action.check_update(&meta, false);
+        let check = action.check_update(&meta, false, None);
 
         assert!(!check.has_update);
         assert_eq!(check.latest_version, Some("v2.0.0".into()));
@@ -243,11 +294,11 @@ mod tests {
         let meta = make_test_meta("v1.0.0", vec![("v2.0.0-rc1", true), ("v1.0.0", false)]);
 
         // Without prerelease flag - no update (stable is v1.0.0)
-        let check = action.check_update(&meta, false);
+        let check = action.check_update(&meta, false, None);
         assert!(!check.has_update);
 
         // With prerelease flag - has update
-        let check = action.check_update(&meta, true);
+        let check = action.check_update(&meta, true, None);
         assert!(check.has_update);
         assert_eq!(check.latest_version, Some("v2.0.0-rc1".into()));
     }
@@ -263,9 +314,116 @@ mod tests {
         let action = UpgradeAction::new(&runtime, &factory, "/test".into());
 
         let meta = make_test_meta("v1.0.0", vec![]);
-        let check = action.check_update(&meta, false);
+        let check = action.check_update(&meta, false, None);
 
         assert!(!check.has_update);
         assert!(check.latest_version.is_none());
     }
+
+    #[test]
+    fn test_check_update_ignores_backport() {
+        let runtime = MockRuntime::new();
+        let factory = make_test_factory();
+        let action = UpgradeAction::new(&runtime, &factory, "/test".into());
+
+        // v1.9.8 is listed first (published last) but is older than v2.1.0
+        let meta = make_test_meta("v2.1.0", vec![("v1.9.8", false), ("v2.1.0", false)]);
+        let check = action.check_update(&meta, false, None);
+
+        assert!(!check.has_update);
+        assert_eq!(check.latest_version, Some("v2.1.0".into()));
+    }
+
+    #[test]
+    fn test_check_update_stays_in_range() {
+        let runtime = MockRuntime::new();
+        let factory = make_test_factory();
+        let action = UpgradeAction::new(&runtime, &factory, "/test".into());
+
+        let mut meta = make_test_meta(
+            "v1.4.0",
+            vec![("v2.0.0", false), ("v1.5.2", false), ("v1.4.0", false)],
+        );
+        meta.version_constraint = Some("^1.4".into());
+
+        let check = action.check_update(&meta, false, None);
+        assert!(check.has_update);
+        assert_eq!(check.latest_version, Some("v1.5.2".into()));
+    }
+
+    #[test]
+    fn test_check_all_holds_pinned_package() {
+        // --- Setup ---
+        let root = PathBuf::from("/test");
+        let package_dir = root.join("owner/repo");
+        let mut meta = make_test_meta("v1.0.0", vec![("v2.0.0", false), ("v1.0.0", false)]);
+        meta.pinned = Some("v1.0.0".into());
+        let json = serde_json::to_string(&meta).unwrap();
+
+        let mut runtime = MockRuntime::new();
+        runtime.expect_exists().returning(|_| true);
+        runtime.expect_is_dir().returning(|_| true);
+        runtime.expect_read_dir().returning(move |path| {
+            Ok(match path.to_str().unwrap() {
+                "/test" => vec![PathBuf::from("/test/owner")],
+                _ => vec![package_dir.clone()],
+            })
+        });
+        runtime
+            .expect_read_to_string()
+            .returning(move |_| Ok(json.clone()));
+        let factory = make_test_factory();
+        let action = UpgradeAction::new(&runtime, &factory, root);
+
+        // --- Execute ---
+        let result = action.check_all(&[], false, None).unwrap();
+
+        // --- Verify ---
+        assert!(result.upgradable.is_empty());
+        assert_eq!(result.held.len(), 1);
+        assert_eq!(result.held[0].1, "v1.0.0");
+    }
+
+    #[test]
+    fn test_check_update_within_policy() {
+        let runtime = MockRuntime::new();
+        let factory = make_test_factory();
+        let action = UpgradeAction::new(&runtime, &factory, "/test".into());
+
+        let meta = make_test_meta(
+            "v1.4.2",
+            vec![
+                ("v2.0.0", false),
+                ("v1.5.0", false),
+                ("v1.4.5", false),
+                ("v1.4.2", false),
+            ],
+        );
+
+        let check = action.check_update(&meta, false, Some(UpgradePolicy::Patch));
+        assert!(check.has_update);
+        assert_eq!(check.latest_version, Some("v1.4.5".into()));
+        assert_eq!(check.latest_overall, Some("v2.0.0".into()));
+
+        let check = action.check_update(&meta, false, Some(UpgradePolicy::Minor));
+        assert_eq!(check.latest_version, Some("v1.5.0".into()));
+
+        let check = action.check_update(&meta, false, Some(UpgradePolicy::Major));
+        assert_eq!(check.latest_version, Some("v2.0.0".into()));
+    }
+
+    #[test]
+    fn test_check_update_outside_policy() {
+        let runtime = MockRuntime::new();
+        let factory = make_test_factory();
+        let action = UpgradeAction::new(&runtime, &factory, "/test".into());
+
+        let meta = make_test_meta("v1.4.2", vec![("v2.0.0", false), ("v1.4.2", false)]);
+        let check = action.check_update(&meta, false, Some(UpgradePolicy::Minor));
+
+        assert!(!check.has_update);
+        assert_eq!(check.latest_version, Some("v1.4.2".into()));
+        assert!(check.has_update_overall);
+        assert_eq!(check.latest_overall, Some("v2.0.0".into()));
+    }
 }
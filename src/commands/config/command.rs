@@ -0,0 +1,252 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+use crate::runtime::Runtime;
+
+use super::Config;
+use super::file::{ConfigFile, ConfigLayers, system_config_path, user_config_path};
+
+/// Show all effective configuration values and where they came from
+#[tracing::instrument(skip(runtime, cli))]
+pub fn config_list<R: Runtime>(runtime: R, cli: ConfigFile) -> Result<()> {
+    let layers = ConfigLayers::load(&runtime, cli)?;
+    let effective = Config::from_layers(&runtime, &layers)?.to_file();
+
+    for key in effective.keys() {
+        if let Some(value) = effective.get(&key)? {
+            println!(
+                "{} = {}  ({})",
+                key,
+                display_value(&key, &value),
+                layers.source_of(&key)?
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Show the effective value of a single configuration key
+#[tracing::instrument(skip(runtime, cli))]
+pub fn config_get<R: Runtime>(
+    runtime: R,
+    cli: ConfigFile,
+    key: &str,
+    show_origin: bool,
+) -> Result<()> {
+    let layers = ConfigLayers::load(&runtime, cli)?;
+    let effective = Config::from_layers(&runtime, &layers)?.to_file();
+
+    let value = effective
+        .get(key)?
+        .with_context(|| format!("Config key '{}' is not set", key))?;
+
+    if show_origin {
+        println!("{}  ({})", value, layers.source_of(key)?);
+    } else {
+        println!("{}", value);
+    }
+    Ok(())
+}
+
+/// Set a configuration key in the user (or system) config file
+#[tracing::instrument(skip(runtime, value))]
+pub fn config_set<R: Runtime>(runtime: R, key: &str, value: &str, system: bool) -> Result<()> {
+    let path = config_file_path(&runtime, system)?;
+    let mut file = ConfigFile::load(&runtime, &path)?.unwrap_or_default();
+    file.set(key, value)?;
+    file.save(&runtime, &path)?;
+
+    println!(
+        "Set {} = {} in {}",
+        key,
+        display_value(key, value),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Remove a configuration key from the user (or system) config file
+#[tracing::instrument(skip(runtime))]
+pub fn config_unset<R: Runtime>(runtime: R, key: &str, system: bool) -> Result<()> {
+    let path = config_file_path(&runtime, system)?;
+    let Some(mut file) = ConfigFile::load(&runtime, &path)? else {
+        println!("{} is not set in {}", key, path.display());
+        return Ok(());
+    };
+
+    if file.get(key)?.is_none() {
+        println!("{} is not set in {}", key, path.display());
+        return Ok(());
+    }
+
+    file.unset(key)?;
+    file.save(&runtime, &path)?;
+    println!("Removed {} from {}", key, path.display());
+    Ok(())
+}
+
+fn config_file_path<R: Runtime>(runtime: &R, system: bool) -> Result<PathBuf> {
+    if system {
+        Ok(system_config_path())
+    } else {
+        user_config_path(runtime).context("Could not determine the user config directory")
+    }
+}
+
+/// Mask secrets when printing values
+fn display_value(key: &str, value: &str) -> String {
+    if key.ends_with(".token") {
+        let visible: String = value.chars().take(4).collect();
+        format!("{}********", visible)
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::MockRuntime;
+    use crate::test_utils::test_home;
+    use mockall::predicate::*;
+
+    fn user_file() -> PathBuf {
+        test_home().join(".config").join("ghri").join("config.toml")
+    }
+
+    fn configure_runtime(runtime: &mut MockRuntime) {
+        runtime
+            .expect_env_var()
+            .returning(|_| Err(std::env::VarError::NotPresent));
+        runtime
+            .expect_config_dir()
+            .returning(|| Some(test_home().join(".config")));
+    }
+
+    #[test]
+    fn test_config_set_creates_user_file() {
+        // --- Setup ---
+        let mut runtime = MockRuntime::new();
+        configure_runtime(&mut runtime);
+        let path = user_file();
+
+        runtime
+            .expect_exists()
+            .with(eq(path.clone()))
+            .returning(|_| false);
+        runtime
+            .expect_create_dir_all()
+            .with(eq(path.parent().unwrap().to_path_buf()))
+            .returning(|_| Ok(()));
+        runtime
+            .expect_write()
+            .withf(move |p, content| {
+                p == path && String::from_utf8_lossy(content).contains("prune = true")
+            })
+            .returning(|_, _| Ok(()));
+
+        // --- Execute & Verify ---
+        config_set(&runtime, "prune", "true", false).unwrap();
+    }
+
+    #[test]
+    fn test_config_set_keeps_existing_values() {
+        // --- Setup ---
+        let mut runtime = MockRuntime::new();
+        configure_runtime(&mut runtime);
+        let path = user_file();
+
+        runtime.expect_exists().returning(|_| true);
+        runtime
+            .expect_read_to_string()
+            .returning(|_| Ok("pre = true\n".into()));
+        runtime.expect_create_dir_all().returning(|_| Ok(()));
+        runtime
+            .expect_write()
+            .withf(move |p, content| {
+                let file = ConfigFile::parse(&String::from_utf8_lossy(content)).unwrap();
+                p == path
+                    && file.pre == Some(true)
+                    && file.http.proxy.as_deref() == Some("http://proxy:3128")
+            })
+            .returning(|_, _| Ok(()));
+
+        // --- Execute & Verify ---
+        config_set(&runtime, "http.proxy", "http://proxy:3128", false).unwrap();
+    }
+
+    #[test]
+    fn test_config_set_token_restricts_permissions() {
+        // --- Setup ---
+        let mut runtime = MockRuntime::new();
+        configure_runtime(&mut runtime);
+        let path = user_file();
+        let mut seq = mockall::Sequence::new();
+
+        runtime.expect_exists().returning(|_| false);
+        runtime.expect_create_dir_all().returning(|_| Ok(()));
+        runtime
+            .expect_write()
+            .withf(|_, content| content.is_empty())
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(()));
+        runtime
+            .expect_set_permissions()
+            .with(eq(path.clone()), eq(0o600))
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(()));
+        runtime
+            .expect_write()
+            .withf(|_, content| String::from_utf8_lossy(content).contains("ghp_secret"))
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(()));
+
+        // --- Execute & Verify ---
+        config_set(&runtime, "hosts.github.com.token", "ghp_secret", false).unwrap();
+    }
+
+    #[test]
+    fn test_config_set_invalid_key() {
+        let mut runtime = MockRuntime::new();
+        configure_runtime(&mut runtime);
+        runtime.expect_exists().returning(|_| false);
+
+        let err = config_set(&runtime, "colour", "blue", false).unwrap_err();
+        assert!(err.to_string().contains("Unknown config key"));
+    }
+
+    #[test]
+    fn test_config_unset_missing_file() {
+        let mut runtime = MockRuntime::new();
+        configure_runtime(&mut runtime);
+        runtime.expect_exists().returning(|_| false);
+        runtime.expect_write().never();
+
+        config_unset(&runtime, "pre", false).unwrap();
+    }
+
+    #[test]
+    fn test_config_get_unset_key() {
+        let mut runtime = MockRuntime::new();
+        configure_runtime(&mut runtime);
+        runtime.expect_exists().returning(|_| false);
+        runtime.expect_is_privileged().returning(|| false);
+        runtime.expect_home_dir().returning(|| Some(test_home()));
+
+        let err = config_get(&runtime, ConfigFile::default(), "link_dir", false).unwrap_err();
+        assert!(err.to_string().contains("is not set"));
+        assert!(config_get(&runtime, ConfigFile::default(), "api_url", true).is_ok());
+    }
+
+    #[test]
+    fn test_display_value_masks_tokens() {
+        assert_eq!(
+            display_value("hosts.github.com.token", "ghp_secret"),
+            "ghp_********"
+        );
+        assert_eq!(display_value("api_url", "https://x"), "https://x");
+    }
+}
@@ -0,0 +1,672 @@
+//! Configuration file format and layered resolution.
+//!
+//! Every configuration source (system file, user file, environment, command line)
+//! is represented as a [`ConfigFile`] layer. Layers are merged in precedence order
+//! and each effective value remembers which layer it came from.
+
+use anyhow::{Context, Result};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::download::UrlRewrite;
+use crate::runtime::Runtime;
+
+/// Environment variable pointing to an alternative user config file
+pub const CONFIG_ENV_VAR: &str = "GHRI_CONFIG";
+
+/// Scalar configuration keys, in display order
+pub const CONFIG_KEYS: &[&str] = &[
+    "install_root",
+    "api_url",
+    "link_dir",
+    "bin_dir",
+    "filters",
+    "pre",
+    "prune",
+    "credential_helper",
+    "http.proxy",
+    "http.no_proxy",
+    "http.ca_certs",
+    "http.client_cert",
+    "http.client_key",
+    "http.insecure",
+];
+
+/// Settings available in each `[hosts."<host>"]` table
+const HOST_KEYS: &[&str] = &["token", "credential_helper"];
+
+/// Environment variables for each key, in lookup order
+const ENV_KEYS: &[(&str, &[&str])] = &[
+    ("install_root", &["GHRI_ROOT"]),
+    ("api_url", &["GHRI_API_URL"]),
+    ("link_dir", &["GHRI_LINK_DIR"]),
+    ("bin_dir", &["GHRI_BIN_DIR"]),
+    (
+        "http.proxy",
+        &[
+            "GHRI_PROXY",
+            "HTTPS_PROXY",
+            "https_proxy",
+            "ALL_PROXY",
+            "all_proxy",
+        ],
+    ),
+    ("http.no_proxy", &["GHRI_NO_PROXY", "NO_PROXY", "no_proxy"]),
+    ("http.ca_certs", &["GHRI_CA_CERT"]),
+    ("http.client_cert", &["GHRI_CLIENT_CERT"]),
+    ("http.client_key", &["GHRI_CLIENT_KEY"]),
+    ("http.insecure", &["GHRI_INSECURE"]),
+];
+
+/// One layer of configuration, as stored in `config.toml`.
+///
+/// Every field is optional: unset fields fall through to lower-precedence layers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigFile {
+    /// Installation root directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install_root: Option<PathBuf>,
+    /// Default GitHub API URL
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    /// Default destination directory for `ghri link`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_dir: Option<PathBuf>,
+    /// Default directory `ghri install` links package executables into
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bin_dir: Option<PathBuf>,
+    /// Default asset filters for new installs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filters: Option<Vec<String>>,
+    /// Allow pre-release versions by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pre: Option<bool>,
+    /// Remove other versions after install/upgrade by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prune: Option<bool>,
+    /// Command that prints a token for a host (host name is passed as argument)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_helper: Option<String>,
+    /// Proxy and TLS settings
+    #[serde(default, skip_serializing_if = "HttpConfig::is_empty")]
+    pub http: HttpConfig,
+    /// Per-host settings, keyed by host name (e.g., "github.mycompany.com")
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hosts: BTreeMap<String, HostConfig>,
+    /// Download URL rewrite rules, in order (`[[rewrites]]` tables)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rewrites: Option<Vec<UrlRewrite>>,
+}
+
+/// The `[http]` table of the configuration file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HttpConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_certs: Option<Vec<PathBuf>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insecure: Option<bool>,
+}
+
+impl HttpConfig {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A `[hosts."<host>"]` table of the configuration file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HostConfig {
+    /// Access token used for this host
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Credential helper for this host (overrides the global one)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_helper: Option<String>,
+}
+
+impl HostConfig {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Mutable view of a single configuration value, used for key-based access.
+enum Field<'a> {
+    Str(&'a mut Option<String>),
+    Path(&'a mut Option<PathBuf>),
+    List(&'a mut Option<Vec<String>>),
+    PathList(&'a mut Option<Vec<PathBuf>>),
+    Bool(&'a mut Option<bool>),
+}
+
+impl ConfigFile {
+    /// Parse a configuration file from TOML text.
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Serialize this layer to TOML text.
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// Load a configuration file, returning `None` if it does not exist.
+    pub fn load<R: Runtime>(runtime: &R, path: &Path) -> Result<Option<Self>> {
+        if !runtime.exists(path) {
+            return Ok(None);
+        }
+        let content = runtime.read_to_string(path)?;
+        let file =
+            Self::parse(&content).with_context(|| format!("Invalid config file {:?}", path))?;
+        debug!("Loaded config file {:?}", path);
+        Ok(Some(file))
+    }
+
+    /// Write this layer to a configuration file, creating parent directories.
+    ///
+    /// A file holding a token is made readable by its owner only (on Unix)
+    /// before the token is written to it.
+    pub fn save<R: Runtime>(&self, runtime: &R, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            runtime.create_dir_all(parent)?;
+        }
+        if self.has_tokens() {
+            if !runtime.exists(path) {
+                runtime.write(path, b"")?;
+            }
+            runtime.set_permissions(path, 0o600)?;
+        }
+        runtime.write(path, self.to_toml()?.as_bytes())
+    }
+
+    /// Whether any host table stores a token
+    fn has_tokens(&self) -> bool {
+        self.hosts.values().any(|host| host.token.is_some())
+    }
+
+    /// Build a layer from environment variables.
+    ///
+    /// Returns the layer and the name of the variable that provided each key.
+    pub fn from_env<R: Runtime>(runtime: &R) -> Result<(Self, BTreeMap<String, String>)> {
+        let mut layer = Self::default();
+        let mut vars = BTreeMap::new();
+        for (key, names) in ENV_KEYS {
+            let found = names.iter().find_map(|name| {
+                runtime
+                    .env_var(name)
+                    .ok()
+                    .filter(|v| !v.trim().is_empty())
+                    .map(|v| (*name, v))
+            });
+            if let Some((name, value)) = found {
+                layer
+                    .set(key, &value)
+                    .with_context(|| format!("Invalid value in {}", name))?;
+                vars.insert(key.to_string(), name.to_string());
+            }
+        }
+        Ok((layer, vars))
+    }
+
+    /// All keys this layer knows about: the scalar keys plus the settings of each host.
+    pub fn keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = CONFIG_KEYS.iter().map(|k| k.to_string()).collect();
+        for host in self.hosts.keys() {
+            keys.extend(
+                HOST_KEYS
+                    .iter()
+                    .map(|name| format!("hosts.{}.{}", host, name)),
+            );
+        }
+        keys
+    }
+
+    /// Get the value of a key as a display string, or `None` if unset in this layer.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        let mut copy = self.clone();
+        let value = match copy.field(key)? {
+            Field::Str(v) => v.clone(),
+            Field::Path(v) => v.as_ref().map(|p| p.display().to_string()),
+            Field::List(v) => v.as_ref().map(|l| l.join(",")),
+            Field::PathList(v) => v.as_ref().map(|l| {
+                l.iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }),
+            Field::Bool(v) => v.map(|b| b.to_string()),
+        };
+        Ok(value)
+    }
+
+    /// Set a key from its string form. Lists are comma-separated.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let split = || {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        match self.field(key)? {
+            Field::Str(v) => *v = Some(value.to_string()),
+            Field::Path(v) => *v = Some(PathBuf::from(value)),
+            Field::List(v) => *v = Some(split().collect()),
+            Field::PathList(v) => *v = Some(split().map(PathBuf::from).collect()),
+            Field::Bool(v) => *v = Some(parse_bool(value)?),
+        }
+        Ok(())
+    }
+
+    /// Remove a key from this layer.
+    pub fn unset(&mut self, key: &str) -> Result<()> {
+        match self.field(key)? {
+            Field::Str(v) => *v = None,
+            Field::Path(v) => *v = None,
+            Field::List(v) => *v = None,
+            Field::PathList(v) => *v = None,
+            Field::Bool(v) => *v = None,
+        }
+        self.hosts.retain(|_, host| !host.is_empty());
+        Ok(())
+    }
+
+    /// Overlay a higher-precedence layer on top of this one.
+    pub fn overlay(&mut self, upper: &ConfigFile) {
+        fn pick<T: Clone>(lower: &mut Option<T>, upper: &Option<T>) {
+            if upper.is_some() {
+                lower.clone_from(upper);
+            }
+        }
+        pick(&mut self.install_root, &upper.install_root);
+        pick(&mut self.api_url, &upper.api_url);
+        pick(&mut self.link_dir, &upper.link_dir);
+        pick(&mut self.bin_dir, &upper.bin_dir);
+        pick(&mut self.filters, &upper.filters);
+        pick(&mut self.pre, &upper.pre);
+        pick(&mut self.prune, &upper.prune);
+        pick(&mut self.credential_helper, &upper.credential_helper);
+        pick(&mut self.http.proxy, &upper.http.proxy);
+        pick(&mut self.http.no_proxy, &upper.http.no_proxy);
+        pick(&mut self.http.ca_certs, &upper.http.ca_certs);
+        pick(&mut self.http.client_cert, &upper.http.client_cert);
+        pick(&mut self.http.client_key, &upper.http.client_key);
+        pick(&mut self.http.insecure, &upper.http.insecure);
+        pick(&mut self.rewrites, &upper.rewrites);
+        for (host, upper_host) in &upper.hosts {
+            let host = self.hosts.entry(host.clone()).or_default();
+            pick(&mut host.token, &upper_host.token);
+            pick(&mut host.credential_helper, &upper_host.credential_helper);
+        }
+    }
+
+    fn field(&mut self, key: &str) -> Result<Field<'_>> {
+        if let Some(rest) = key.strip_prefix("hosts.") {
+            let (host, name) = rest
+                .rsplit_once('.')
+                .filter(|(host, _)| !host.is_empty())
+                .with_context(|| {
+                    format!(
+                        "Invalid host key '{}'. Expected hosts.<host>.<setting>",
+                        key
+                    )
+                })?;
+            let host = self.hosts.entry(host.to_string()).or_default();
+            return match name {
+                "token" => Ok(Field::Str(&mut host.token)),
+                "credential_helper" => Ok(Field::Str(&mut host.credential_helper)),
+                _ => anyhow::bail!(
+                    "Unknown host setting '{}'. Expected one of: {}",
+                    name,
+                    HOST_KEYS.join(", ")
+                ),
+            };
+        }
+
+        Ok(match key {
+            "install_root" => Field::Path(&mut self.install_root),
+            "api_url" => Field::Str(&mut self.api_url),
+            "link_dir" => Field::Path(&mut self.link_dir),
+            "bin_dir" => Field::Path(&mut self.bin_dir),
+            "filters" => Field::List(&mut self.filters),
+            "pre" => Field::Bool(&mut self.pre),
+            "prune" => Field::Bool(&mut self.prune),
+            "credential_helper" => Field::Str(&mut self.credential_helper),
+            "http.proxy" => Field::Str(&mut self.http.proxy),
+            "http.no_proxy" => Field::Str(&mut self.http.no_proxy),
+            "http.ca_certs" => Field::PathList(&mut self.http.ca_certs),
+            "http.client_cert" => Field::Path(&mut self.http.client_cert),
+            "http.client_key" => Field::Path(&mut self.http.client_key),
+            "http.insecure" => Field::Bool(&mut self.http.insecure),
+            _ => anyhow::bail!(
+                "Unknown config key '{}'. Valid keys: {}, hosts.<host>.<setting>",
+                key,
+                CONFIG_KEYS.join(", ")
+            ),
+        })
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => anyhow::bail!("Invalid boolean '{}'. Expected true or false", value),
+    }
+}
+
+/// Where a configuration value came from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigSource {
+    /// Built-in default
+    Default,
+    /// System-wide configuration file
+    SystemFile(PathBuf),
+    /// Per-user configuration file
+    UserFile(PathBuf),
+    /// Environment variable (holds the variable name)
+    Env(String),
+    /// Command-line option
+    Cli,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::SystemFile(path) => write!(f, "system config {}", path.display()),
+            ConfigSource::UserFile(path) => write!(f, "user config {}", path.display()),
+            ConfigSource::Env(var) => write!(f, "environment {}", var),
+            ConfigSource::Cli => write!(f, "command line"),
+        }
+    }
+}
+
+/// All configuration layers, lowest precedence first.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLayers {
+    layers: Vec<(ConfigSource, ConfigFile)>,
+    /// Environment variable that provided each key of the environment layer
+    env_vars: BTreeMap<String, String>,
+}
+
+impl ConfigLayers {
+    /// Load all layers: system file < user file < environment < command line.
+    pub fn load<R: Runtime>(runtime: &R, cli: ConfigFile) -> Result<Self> {
+        let mut layers = Self::default();
+
+        let system_path = system_config_path();
+        if let Some(file) = ConfigFile::load(runtime, &system_path)? {
+            layers.push(ConfigSource::SystemFile(system_path), file);
+        }
+
+        if let Some(user_path) = user_config_path(runtime)
+            && let Some(file) = ConfigFile::load(runtime, &user_path)?
+        {
+            layers.push(ConfigSource::UserFile(user_path), file);
+        }
+
+        let (env, env_vars) = ConfigFile::from_env(runtime)?;
+        layers.env_vars = env_vars;
+        layers.push(ConfigSource::Env(String::new()), env);
+
+        layers.push(ConfigSource::Cli, cli);
+        Ok(layers)
+    }
+
+    /// Add a layer on top of the existing ones.
+    pub fn push(&mut self, source: ConfigSource, file: ConfigFile) {
+        self.layers.push((source, file));
+    }
+
+    /// Merge all layers into the effective configuration values.
+    pub fn merged(&self) -> ConfigFile {
+        let mut merged = ConfigFile::default();
+        for (_, layer) in &self.layers {
+            merged.overlay(layer);
+        }
+        merged
+    }
+
+    /// Find the layer that provides a key, or `Default` if no layer sets it.
+    pub fn source_of(&self, key: &str) -> Result<ConfigSource> {
+        for (source, layer) in self.layers.iter().rev() {
+            if layer.get(key)?.is_some() {
+                return Ok(match source {
+                    ConfigSource::Env(_) => {
+                        ConfigSource::Env(self.env_vars.get(key).cloned().unwrap_or_default())
+                    }
+                    other => other.clone(),
+                });
+            }
+        }
+        Ok(ConfigSource::Default)
+    }
+}
+
+/// Path of the system-wide configuration file
+#[cfg(not(windows))]
+pub fn system_config_path() -> PathBuf {
+    PathBuf::from("/etc/ghri/config.toml")
+}
+
+#[cfg(windows)]
+pub fn system_config_path() -> PathBuf {
+    PathBuf::from(r"C:\ProgramData\ghri\config.toml")
+}
+
+/// Path of the per-user configuration file (`GHRI_CONFIG` overrides the default)
+pub fn user_config_path<R: Runtime>(runtime: &R) -> Option<PathBuf> {
+    if let Ok(path) = runtime.env_var(CONFIG_ENV_VAR)
+        && !path.is_empty()
+    {
+        return Some(PathBuf::from(path));
+    }
+    runtime
+        .config_dir()
+        .map(|dir| dir.join("ghri").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::MockRuntime;
+
+    fn mock_env(vars: &'static [(&'static str, &'static str)]) -> MockRuntime {
+        let mut runtime = MockRuntime::new();
+        runtime.expect_env_var().returning(move |key| {
+            vars.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.to_string())
+                .ok_or(std::env::VarError::NotPresent)
+        });
+        runtime
+    }
+
+    #[test]
+    fn test_parse_config_file() {
+        let file = ConfigFile::parse(
+            r#"
+install_root = "/opt/tools"
+filters = ["*musl*"]
+pre = true
+
+[http]
+proxy = "http://proxy:3128"
+ca_certs = ["/etc/ca.pem"]
+
+[hosts."github.example.com"]
+token = "secret"
+
+[[rewrites]]
+match = "github.com"
+to = ["https://ghproxy.example/https://github.com"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(file.install_root, Some(PathBuf::from("/opt/tools")));
+        assert_eq!(file.filters, Some(vec!["*musl*".to_string()]));
+        assert_eq!(file.pre, Some(true));
+        assert_eq!(file.http.proxy.as_deref(), Some("http://proxy:3128"));
+        assert_eq!(file.http.ca_certs, Some(vec![PathBuf::from("/etc/ca.pem")]));
+        assert_eq!(
+            file.hosts["github.example.com"].token.as_deref(),
+            Some("secret")
+        );
+        let rewrites = file.rewrites.as_ref().unwrap();
+        assert_eq!(rewrites[0].pattern, "github.com");
+        assert_eq!(ConfigFile::parse(&file.to_toml().unwrap()).unwrap(), file);
+    }
+
+    #[test]
+    fn test_parse_invalid_config_file() {
+        assert!(ConfigFile::parse("pre = \"maybe\"").is_err());
+    }
+
+    #[test]
+    fn test_set_get_unset_roundtrip() {
+        let mut file = ConfigFile::default();
+        file.set("filters", "*linux*, *musl*").unwrap();
+        file.set("http.insecure", "yes").unwrap();
+        file.set("hosts.gitlab.com.token", "glpat").unwrap();
+
+        assert_eq!(
+            file.get("filters").unwrap().as_deref(),
+            Some("*linux*,*musl*")
+        );
+        assert_eq!(file.get("http.insecure").unwrap().as_deref(), Some("true"));
+        assert_eq!(
+            file.get("hosts.gitlab.com.token").unwrap().as_deref(),
+            Some("glpat")
+        );
+        assert_eq!(file.get("link_dir").unwrap(), None);
+
+        // Saved file round-trips through TOML
+        let parsed = ConfigFile::parse(&file.to_toml().unwrap()).unwrap();
+        assert_eq!(parsed, file);
+
+        file.unset("hosts.gitlab.com.token").unwrap();
+        assert!(file.hosts.is_empty());
+    }
+
+    #[test]
+    fn test_get_does_not_create_host_entries() {
+        let file = ConfigFile::default();
+        assert_eq!(file.get("hosts.example.com.token").unwrap(), None);
+        assert!(file.hosts.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_keys_and_values() {
+        let mut file = ConfigFile::default();
+        assert!(file.set("nonsense", "x").is_err());
+        assert!(file.set("hosts..token", "x").is_err());
+        assert!(file.set("hosts.example.com.password", "x").is_err());
+        assert!(file.set("pre", "maybe").is_err());
+    }
+
+    #[test]
+    fn test_from_env() {
+        let runtime = mock_env(&[
+            ("GHRI_ROOT", "/env/root"),
+            ("HTTPS_PROXY", "http://https:2"),
+            ("ALL_PROXY", "http://all:1"),
+            ("no_proxy", "localhost"),
+            ("GHRI_INSECURE", "1"),
+        ]);
+
+        let (layer, vars) = ConfigFile::from_env(&runtime).unwrap();
+
+        assert_eq!(layer.install_root, Some(PathBuf::from("/env/root")));
+        assert_eq!(layer.http.proxy.as_deref(), Some("http://https:2"));
+        assert_eq!(layer.http.no_proxy.as_deref(), Some("localhost"));
+        assert_eq!(layer.http.insecure, Some(true));
+        assert_eq!(vars["http.proxy"], "HTTPS_PROXY");
+        assert_eq!(vars["http.no_proxy"], "no_proxy");
+    }
+
+    #[test]
+    fn test_from_env_ghri_proxy_wins_and_blank_ignored() {
+        let runtime = mock_env(&[
+            ("GHRI_PROXY", "http://ghri:3"),
+            ("HTTPS_PROXY", "http://https:2"),
+            ("GHRI_API_URL", " "),
+        ]);
+
+        let (layer, _) = ConfigFile::from_env(&runtime).unwrap();
+
+        assert_eq!(layer.http.proxy.as_deref(), Some("http://ghri:3"));
+        assert_eq!(layer.api_url, None);
+    }
+
+    #[test]
+    fn test_layers_precedence_and_sources() {
+        let user_path = PathBuf::from("/home/u/.config/ghri/config.toml");
+        let mut layers = ConfigLayers::default();
+        layers.push(
+            ConfigSource::SystemFile(system_config_path()),
+            ConfigFile::parse("api_url = \"https://system\"\npre = true\nprune = true").unwrap(),
+        );
+        layers.push(
+            ConfigSource::UserFile(user_path.clone()),
+            ConfigFile::parse("api_url = \"https://user\"\npre = false").unwrap(),
+        );
+        layers
+            .env_vars
+            .insert("api_url".into(), "GHRI_API_URL".into());
+        layers.push(
+            ConfigSource::Env(String::new()),
+            ConfigFile::parse("api_url = \"https://env\"").unwrap(),
+        );
+        layers.push(ConfigSource::Cli, ConfigFile::default());
+
+        let merged = layers.merged();
+        assert_eq!(merged.api_url.as_deref(), Some("https://env"));
+        assert_eq!(merged.pre, Some(false));
+        assert_eq!(merged.prune, Some(true));
+
+        assert_eq!(
+            layers.source_of("api_url").unwrap(),
+            ConfigSource::Env("GHRI_API_URL".into())
+        );
+        assert_eq!(
+            layers.source_of("pre").unwrap(),
+            ConfigSource::UserFile(user_path)
+        );
+        assert_eq!(
+            layers.source_of("prune").unwrap(),
+            ConfigSource::SystemFile(system_config_path())
+        );
+        assert_eq!(layers.source_of("link_dir").unwrap(), ConfigSource::Default);
+    }
+
+    #[test]
+    fn test_user_config_path() {
+        let runtime = mock_env(&[("GHRI_CONFIG", "/custom/ghri.toml")]);
+        assert_eq!(
+            user_config_path(&runtime),
+            Some(PathBuf::from("/custom/ghri.toml"))
+        );
+
+        let mut runtime = mock_env(&[]);
+        runtime
+            .expect_config_dir()
+            .returning(|| Some(PathBuf::from("/home/u/.config")));
+        assert_eq!(
+            user_config_path(&runtime),
+            Some(PathBuf::from("/home/u/.config/ghri/config.toml"))
+        );
+    }
+}
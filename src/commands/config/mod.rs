@@ -0,0 +1,544 @@
+mod command;
+mod file;
+
+pub use command::{config_get, config_list, config_set, config_unset};
+pub use file::{
+    CONFIG_KEYS, ConfigFile, ConfigLayers, ConfigSource, HostConfig, HttpConfig,
+    system_config_path, user_config_path,
+};
+
+use anyhow::{Context, Result};
+use log::debug;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::domain::model::{ManifestFormat, UpgradePolicy, UrlSource};
+use crate::download::UrlRewrite;
+use crate::http::{HttpSettings, Origin};
+use crate::runtime::{Runtime, resolve_relative_path};
+
+/// Application configuration resolved from all configuration layers.
+/// This struct contains only configuration values, not service dependencies.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Installation root directory (e.g., ~/.ghri or /opt/ghri)
+    pub install_root: PathBuf,
+    /// GitHub API URL (e.g., https://api.github.com)
+    pub api_url: String,
+    /// GitHub authentication token (optional)
+    pub token: Option<String>,
+    /// Proxy and TLS settings for the HTTP client
+    pub http: HttpSettings,
+    /// Default destination directory for `ghri link`
+    pub link_dir: Option<PathBuf>,
+    /// Default directory `ghri install` links package executables into
+    pub bin_dir: Option<PathBuf>,
+    /// Default asset filters for packages without saved filters
+    pub filters: Vec<String>,
+    /// Allow pre-release versions by default
+    pub pre: bool,
+    /// Remove other versions after install/upgrade by default
+    pub prune: bool,
+    /// Command that prints a token for a host
+    pub credential_helper: Option<String>,
+    /// Per-host settings, keyed by host name
+    pub hosts: BTreeMap<String, HostConfig>,
+    /// Download URL rewrite rules, first match wins
+    pub rewrites: Vec<UrlRewrite>,
+}
+
+impl Config {
+    /// Default GitHub API URL
+    pub const DEFAULT_API_URL: &'static str = "https://api.github.com";
+
+    /// Load configuration with layered precedence:
+    /// command line > environment > user config file > system config file > defaults.
+    ///
+    /// # Arguments
+    /// * `runtime` - Runtime for environment and file access
+    /// * `cli` - Values given on the command line
+    pub fn load<R: Runtime>(runtime: &R, cli: ConfigFile) -> Result<Self> {
+        let layers = ConfigLayers::load(runtime, cli)?;
+        Self::from_layers(runtime, &layers)
+    }
+
+    /// Build the effective configuration from already loaded layers
+    pub fn from_layers<R: Runtime>(runtime: &R, layers: &ConfigLayers) -> Result<Self> {
+        let merged = layers.merged();
+
+        // Determine install root: configured value > default based on privilege
+        let install_root = match merged.install_root {
+            Some(path) => path,
+            None => Self::default_install_root(runtime)?,
+        };
+
+        // Convert relative install_root to absolute using current working directory
+        let install_root = if install_root.is_relative() {
+            let cwd = runtime.current_dir()?;
+            resolve_relative_path(&cwd, &install_root)
+        } else {
+            install_root
+        };
+
+        let api_url = merged
+            .api_url
+            .unwrap_or_else(|| Self::DEFAULT_API_URL.to_string());
+
+        // Load token from environment, falling back to the token configured for the API host
+        let token = runtime.env_var("GITHUB_TOKEN").ok().or_else(|| {
+            api_host(&api_url)
+                .and_then(|host| merged.hosts.get(&host))
+                .and_then(|host| host.token.clone())
+        });
+
+        if let Some(ref t) = token {
+            if t.len() >= 12 {
+                debug!(
+                    "Using token for authentication: {}*********{}",
+                    &t[..8],
+                    &t[t.len() - 4..]
+                );
+            } else {
+                debug!("Using token for authentication");
+            }
+        }
+
+        let http = HttpSettings {
+            proxy: merged.http.proxy,
+            no_proxy: merged.http.no_proxy,
+            ca_certs: merged.http.ca_certs.unwrap_or_default(),
+            client_cert: merged.http.client_cert,
+            client_key: merged.http.client_key,
+            insecure: merged.http.insecure.unwrap_or(false),
+        };
+
+        let rewrites = merged.rewrites.unwrap_or_default();
+        for rule in &rewrites {
+            rule.validate().context("Invalid [[rewrites]] rule")?;
+        }
+
+        Ok(Self {
+            install_root,
+            api_url,
+            token,
+            http,
+            link_dir: merged.link_dir,
+            bin_dir: merged.bin_dir,
+            filters: merged.filters.unwrap_or_default(),
+            pre: merged.pre.unwrap_or(false),
+            prune: merged.prune.unwrap_or(false),
+            credential_helper: merged.credential_helper,
+            hosts: merged.hosts,
+            rewrites,
+        })
+    }
+
+    /// Effective values as a configuration layer (used for display)
+    pub fn to_file(&self) -> ConfigFile {
+        ConfigFile {
+            install_root: Some(self.install_root.clone()),
+            api_url: Some(self.api_url.clone()),
+            link_dir: self.link_dir.clone(),
+            bin_dir: self.bin_dir.clone(),
+            filters: (!self.filters.is_empty()).then(|| self.filters.clone()),
+            pre: Some(self.pre),
+            prune: Some(self.prune),
+            credential_helper: self.credential_helper.clone(),
+            http: HttpConfig {
+                proxy: self.http.proxy.clone(),
+                no_proxy: self.http.no_proxy.clone(),
+                ca_certs: (!self.http.ca_certs.is_empty()).then(|| self.http.ca_certs.clone()),
+                client_cert: self.http.client_cert.clone(),
+                client_key: self.http.client_key.clone(),
+                insecure: Some(self.http.insecure),
+            },
+            hosts: self.hosts.clone(),
+            rewrites: (!self.rewrites.is_empty()).then(|| self.rewrites.clone()),
+        }
+    }
+
+    /// Get the default installation root directory based on user privilege
+    fn default_install_root<R: Runtime>(runtime: &R) -> Result<PathBuf> {
+        if runtime.is_privileged() {
+            Ok(Self::system_install_root())
+        } else {
+            let home_dir = runtime
+                .home_dir()
+                .context("Could not find home directory")?;
+            Ok(home_dir.join(".ghri"))
+        }
+    }
+
+    /// Get the system-wide installation root (for privileged users)
+    #[cfg(target_os = "macos")]
+    fn system_install_root() -> PathBuf {
+        PathBuf::from("/opt/ghri")
+    }
+
+    #[cfg(target_os = "windows")]
+    fn system_install_root() -> PathBuf {
+        PathBuf::from(r"C:\ProgramData\ghri")
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    fn system_install_root() -> PathBuf {
+        PathBuf::from("/usr/local/ghri")
+    }
+
+    /// Get the package directory for a given repo
+    pub fn package_dir(&self, owner: &str, repo: &str) -> PathBuf {
+        self.install_root.join(owner).join(repo)
+    }
+
+    /// Get the version directory for a given repo and version
+    pub fn version_dir(&self, owner: &str, repo: &str, version: &str) -> PathBuf {
+        self.package_dir(owner, repo).join(version)
+    }
+
+    /// Create a Config for testing with a specific install root
+    #[cfg(test)]
+    pub fn for_test(install_root: impl Into<PathBuf>) -> Self {
+        Self {
+            install_root: install_root.into(),
+            api_url: Self::DEFAULT_API_URL.to_string(),
+            token: None,
+            http: HttpSettings::default(),
+            link_dir: None,
+            bin_dir: None,
+            filters: vec![],
+            pre: false,
+            prune: false,
+            credential_helper: None,
+            hosts: BTreeMap::new(),
+            rewrites: vec![],
+        }
+    }
+}
+
+/// Host name used for per-host settings of an API URL.
+///
+/// `https://api.github.com` maps to `api.github.com` and GitHub Enterprise URLs
+/// like `https://github.example.com/api/v3` to their host; a non-default port is kept.
+pub fn api_host(api_url: &str) -> Option<String> {
+    Origin::parse(api_url).map(|origin| origin.authority())
+}
+
+/// Options for the install command (behavior parameters)
+#[derive(Debug, Default, Clone)]
+pub struct InstallOptions {
+    /// Asset name filters (e.g., ["*linux*", "*x86_64*"])
+    pub filters: Vec<String>,
+    /// Configured default filters, used when neither options nor saved metadata have filters
+    pub default_filters: Vec<String>,
+    /// Allow installing pre-release versions
+    pub pre: bool,
+    /// Skip confirmation prompts
+    pub yes: bool,
+    /// Prune old versions after installation
+    pub prune: bool,
+    /// Tag pattern to save for the package (e.g., "cli-v*")
+    pub tag_pattern: Option<String>,
+    /// Install under this name instead of the repository name (`owner/alias`)
+    pub alias: Option<String>,
+    /// Download URL template and version discovery, instead of a forge
+    pub url_source: Option<UrlSource>,
+    /// Directory to link the package's executables into, saved for upgrades
+    pub bin_dir: Option<PathBuf>,
+    /// Configured default bin directory, used when neither options nor saved metadata have one
+    pub default_bin_dir: Option<PathBuf>,
+    /// Original command line arguments (for building suggested commands in error messages)
+    pub original_args: Vec<String>,
+}
+
+/// Options for the upgrade command
+#[derive(Debug, Default, Clone)]
+pub struct UpgradeOptions {
+    /// Allow upgrading to pre-release versions
+    pub pre: bool,
+    /// Skip confirmation prompts
+    pub yes: bool,
+    /// Prune old versions after upgrading
+    pub prune: bool,
+    /// Bound for this run, overriding each package's saved upgrade policy
+    pub policy: Option<UpgradePolicy>,
+}
+
+/// Options for the sync command
+#[derive(Debug, Default, Clone)]
+pub struct SyncOptions {
+    /// Path of the manifest file
+    pub manifest: PathBuf,
+    /// Remove installed packages that are not listed in the manifest
+    pub remove_unlisted: bool,
+    /// Install exactly the releases and files recorded in the lockfile
+    pub locked: bool,
+    /// Skip confirmation prompts
+    pub yes: bool,
+}
+
+/// Options for the mirror create command
+#[derive(Debug, Default, Clone)]
+pub struct MirrorOptions {
+    /// Mirror directory
+    pub dir: PathBuf,
+    /// Repositories to mirror, "owner/repo" or "owner/repo@constraint"
+    pub packages: Vec<String>,
+    /// Asset filters (empty = all assets of every platform)
+    pub filters: Vec<String>,
+}
+
+/// Options for the export command
+#[derive(Debug, Default, Clone)]
+pub struct ExportOptions {
+    /// Output format (default: from the output file name, else TOML)
+    pub format: Option<ManifestFormat>,
+    /// File to write (default: standard output)
+    pub output: Option<PathBuf>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::MockRuntime;
+    use crate::test_utils::test_home;
+
+    /// Runtime with the given environment variables and config files (path, content)
+    fn mock_runtime(vars: &[(&str, &str)], files: &[(PathBuf, &str)]) -> MockRuntime {
+        let vars: Vec<(String, String)> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let files: Vec<(PathBuf, String)> = files
+            .iter()
+            .map(|(p, c)| (p.clone(), c.to_string()))
+            .collect();
+        let existing: Vec<PathBuf> = files.iter().map(|(p, _)| p.clone()).collect();
+
+        let mut runtime = MockRuntime::new();
+        runtime.expect_env_var().returning(move |key| {
+            vars.iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.clone())
+                .ok_or(std::env::VarError::NotPresent)
+        });
+        runtime
+            .expect_config_dir()
+            .returning(|| Some(test_home().join(".config")));
+        runtime
+            .expect_exists()
+            .returning(move |path| existing.iter().any(|p| p == path));
+        runtime.expect_read_to_string().returning(move |path| {
+            files
+                .iter()
+                .find(|(p, _)| p == path)
+                .map(|(_, content)| content.clone())
+                .ok_or_else(|| anyhow::anyhow!("not found"))
+        });
+        runtime
+    }
+
+    fn user_file() -> PathBuf {
+        test_home().join(".config").join("ghri").join("config.toml")
+    }
+
+    #[test]
+    fn test_config_load_defaults() {
+        // Test loading config with default values (no overrides)
+        let mut runtime = mock_runtime(&[], &[]);
+        runtime.expect_is_privileged().returning(|| false);
+        runtime.expect_home_dir().returning(|| Some(test_home()));
+
+        let config = Config::load(&runtime, ConfigFile::default()).unwrap();
+
+        assert_eq!(config.install_root, test_home().join(".ghri"));
+        assert_eq!(config.api_url, Config::DEFAULT_API_URL);
+        assert!(config.token.is_none());
+        assert!(config.filters.is_empty());
+        assert!(!config.pre);
+        assert!(!config.prune);
+        assert_eq!(config.link_dir, None);
+    }
+
+    #[test]
+    fn test_config_load_with_overrides() {
+        // Test loading config with CLI overrides
+        let runtime = mock_runtime(&[("GITHUB_TOKEN", "test_token")], &[]);
+
+        #[cfg(not(windows))]
+        let custom_root = PathBuf::from("/custom/root");
+        #[cfg(windows)]
+        let custom_root = PathBuf::from(r"C:\custom\root");
+
+        let cli = ConfigFile {
+            install_root: Some(custom_root.clone()),
+            api_url: Some("https://github.example.com/api/v3".to_string()),
+            ..Default::default()
+        };
+        let config = Config::load(&runtime, cli).unwrap();
+
+        assert_eq!(config.install_root, custom_root);
+        assert_eq!(config.api_url, "https://github.example.com/api/v3");
+        assert_eq!(config.token, Some("test_token".to_string()));
+    }
+
+    #[test]
+    fn test_config_load_layered_precedence() {
+        #[cfg(not(windows))]
+        let (env_root, user_root) = ("/env/root", "/user/root");
+        #[cfg(windows)]
+        let (env_root, user_root) = (r"C:\env\root", r"C:\user\root");
+
+        let system = "api_url = \"https://system/api\"\npre = true\nprune = true\n";
+        let user = format!(
+            "install_root = '{}'\napi_url = \"https://user/api\"\nprune = false\nlink_dir = '/bin'\nbin_dir = '/opt/bin'\n",
+            user_root
+        );
+        let runtime = mock_runtime(
+            &[("GHRI_ROOT", env_root)],
+            &[(system_config_path(), system), (user_file(), &user)],
+        );
+
+        let cli = ConfigFile {
+            api_url: Some("https://cli/api".into()),
+            ..Default::default()
+        };
+        let config = Config::load(&runtime, cli).unwrap();
+
+        // CLI > env > user file > system file
+        assert_eq!(config.api_url, "https://cli/api");
+        assert_eq!(config.install_root, PathBuf::from(env_root));
+        assert!(!config.prune);
+        assert!(config.pre);
+        assert_eq!(config.link_dir, Some(PathBuf::from("/bin")));
+        assert_eq!(config.bin_dir, Some(PathBuf::from("/opt/bin")));
+    }
+
+    #[test]
+    fn test_config_load_http_settings_from_file_and_env() {
+        let user =
+            "[http]\nproxy = \"http://file-proxy:1\"\nno_proxy = \"file.local\"\ninsecure = true\n";
+        let runtime = mock_runtime(
+            &[("HTTPS_PROXY", "http://env-proxy:2"), ("GHRI_ROOT", "/r")],
+            &[(user_file(), user)],
+        );
+
+        let config = Config::load(&runtime, ConfigFile::default()).unwrap();
+
+        assert_eq!(config.http.proxy.as_deref(), Some("http://env-proxy:2"));
+        assert_eq!(config.http.no_proxy.as_deref(), Some("file.local"));
+        assert!(config.http.insecure);
+    }
+
+    #[test]
+    fn test_config_load_rewrite_rules() {
+        let system = "[[rewrites]]\nmatch = \"github.com\"\nto = [\"https://system.mirror\"]\n";
+        let user = "[[rewrites]]\nmatch = \"https://github.com/\"\n\
+                    to = [\"https://ghproxy.example/https://github.com/\", \"https://art.corp/gh/\"]\n\n\
+                    [[rewrites]]\nmatch = \"*.githubusercontent.com\"\nto = [\"https://art.corp/raw\"]\n";
+        let runtime = mock_runtime(
+            &[("GHRI_ROOT", "/r")],
+            &[(system_config_path(), system), (user_file(), user)],
+        );
+
+        let config = Config::load(&runtime, ConfigFile::default()).unwrap();
+
+        // The user rules replace the system rules and keep their order
+        assert_eq!(config.rewrites.len(), 2);
+        assert_eq!(config.rewrites[0].pattern, "https://github.com/");
+        assert_eq!(config.rewrites[0].to.len(), 2);
+        assert_eq!(config.rewrites[1].pattern, "*.githubusercontent.com");
+        assert_eq!(config.to_file().rewrites, Some(config.rewrites.clone()));
+
+        let user = "[[rewrites]]\nmatch = \"github.com\"\nto = []\n";
+        let runtime = mock_runtime(&[("GHRI_ROOT", "/r")], &[(user_file(), user)]);
+        assert!(Config::load(&runtime, ConfigFile::default()).is_err());
+    }
+
+    #[test]
+    fn test_config_load_token_from_host_settings() {
+        let user = "[hosts.\"api.github.com\"]\ntoken = \"file_token\"\n";
+        let runtime = mock_runtime(&[("GHRI_ROOT", "/r")], &[(user_file(), user)]);
+
+        let config = Config::load(&runtime, ConfigFile::default()).unwrap();
+        assert_eq!(config.token.as_deref(), Some("file_token"));
+
+        // GITHUB_TOKEN takes precedence over the configured token
+        let runtime = mock_runtime(
+            &[("GHRI_ROOT", "/r"), ("GITHUB_TOKEN", "env_token")],
+            &[(user_file(), user)],
+        );
+        let config = Config::load(&runtime, ConfigFile::default()).unwrap();
+        assert_eq!(config.token.as_deref(), Some("env_token"));
+    }
+
+    #[test]
+    fn test_config_load_invalid_file() {
+        let runtime = mock_runtime(&[], &[(user_file(), "pre = [")]);
+
+        let err = Config::load(&runtime, ConfigFile::default()).unwrap_err();
+        assert!(err.to_string().contains("Invalid config file"));
+    }
+
+    #[test]
+    fn test_config_load_privileged_user() {
+        // Test that privileged users get system install root
+        let mut runtime = mock_runtime(&[], &[]);
+        runtime.expect_is_privileged().returning(|| true);
+
+        let config = Config::load(&runtime, ConfigFile::default()).unwrap();
+
+        #[cfg(target_os = "macos")]
+        assert_eq!(config.install_root, PathBuf::from("/opt/ghri"));
+        #[cfg(all(unix, not(target_os = "macos")))]
+        assert_eq!(config.install_root, PathBuf::from("/usr/local/ghri"));
+        #[cfg(target_os = "windows")]
+        assert_eq!(config.install_root, PathBuf::from("C:\\ProgramData\\ghri"));
+    }
+
+    #[test]
+    fn test_config_load_no_home_dir() {
+        // Test that config fails to load when home dir is unavailable for non-privileged user
+        let mut runtime = mock_runtime(&[], &[]);
+        runtime.expect_is_privileged().returning(|| false);
+        runtime.expect_home_dir().returning(|| None);
+
+        let result = Config::load(&runtime, ConfigFile::default());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_api_host() {
+        assert_eq!(
+            api_host("https://api.github.com").as_deref(),
+            Some("api.github.com")
+        );
+        assert_eq!(
+            api_host("https://github.example.com:8443/api/v3").as_deref(),
+            Some("github.example.com:8443")
+        );
+        assert_eq!(
+            api_host("https://GitHub.Example.com/api/v3").as_deref(),
+            Some("github.example.com")
+        );
+        assert_eq!(api_host("not a url"), None);
+    }
+
+    #[test]
+    fn test_config_path_helpers() {
+        #[cfg(not(windows))]
+        let root = PathBuf::from("/root");
+        #[cfg(windows)]
+        let root = PathBuf::from(r"C:\root");
+
+        let config = Config::for_test(root.clone());
+
+        assert_eq!(
+            config.package_dir("owner", "repo"),
+            root.join("owner").join("repo")
+        );
+        assert_eq!(
+            config.version_dir("owner", "repo", "v1.0.0"),
+            root.join("owner").join("repo").join("v1.0.0")
+        );
+    }
+}
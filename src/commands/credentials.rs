@@ -0,0 +1,510 @@
+//! Token resolution for API hosts.
+//!
+//! Tokens are looked up per host (with its port, when not the default), matched
+//! exactly: `api.github.com` and `github.com` are different hosts. In order:
+//! 1. `GHRI_TOKEN_<HOST>` environment variable (e.g., `GHRI_TOKEN_API_GITHUB_COM`)
+//! 2. `GITHUB_TOKEN` (or the config file token), only for the configured API origin
+//! 3. `hosts.<host>.token` in the config file
+//! 4. A credential helper command: `hosts.<host>.credential_helper`, or the global
+//!    `credential_helper` for the configured API origin and hosts listed under `[hosts]`
+//! 5. `~/.netrc`
+//! 6. The `gh` CLI's `hosts.yml`
+
+use log::{debug, warn};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::http::{Origin, TokenSource, normalize_host};
+use crate::runtime::Runtime;
+
+use super::config::{Config, HostConfig};
+
+/// Resolves tokens for hosts from the environment, config and external tools.
+pub struct CredentialResolver<R: Runtime> {
+    runtime: Arc<R>,
+    /// Origin of the configured API URL
+    default_origin: Option<Origin>,
+    /// Token for the default origin (GITHUB_TOKEN)
+    default_token: Option<String>,
+    /// Per-host settings, keyed by normalized host
+    hosts: BTreeMap<String, HostConfig>,
+    credential_helper: Option<String>,
+}
+
+impl<R: Runtime> CredentialResolver<R> {
+    pub fn new(runtime: Arc<R>, config: &Config) -> Self {
+        Self {
+            runtime,
+            default_origin: Origin::parse(&config.api_url),
+            default_token: config.token.clone(),
+            hosts: config
+                .hosts
+                .iter()
+                .map(|(host, settings)| (normalize_host(host), settings.clone()))
+                .collect(),
+            credential_helper: config.credential_helper.clone(),
+        }
+    }
+
+    fn env_token(&self, host: &str) -> Option<String> {
+        self.runtime
+            .env_var(&host_env_var(host))
+            .ok()
+            .filter(|t| !t.trim().is_empty())
+    }
+
+    fn config_token(&self, origin: &Origin) -> Option<String> {
+        if self.default_origin.as_ref() == Some(origin) && self.default_token.is_some() {
+            return self.default_token.clone();
+        }
+        self.hosts
+            .get(&origin.authority())
+            .and_then(|h| h.token.clone())
+    }
+
+    /// The global helper knows nothing about hosts, so whatever it prints is
+    /// only sent to the API origin and to hosts the user listed.
+    fn helper_token(&self, origin: &Origin, host: &str) -> Option<String> {
+        let helper = match self.hosts.get(host) {
+            Some(settings) => settings
+                .credential_helper
+                .as_ref()
+                .or(self.credential_helper.as_ref())?,
+            None if self.default_origin.as_ref() == Some(origin) => {
+                self.credential_helper.as_ref()?
+            }
+            None => return None,
+        };
+
+        debug!("Running credential helper for {}", host);
+        let (program, args) = shell_command(helper, host);
+        match self.runtime.run_command(program, &args) {
+            Ok(output) => output
+                .lines()
+                .next()
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string),
+            Err(e) => {
+                warn!("Credential helper failed for {}: {}", host, e);
+                None
+            }
+        }
+    }
+
+    fn netrc_token(&self, host: &str) -> Option<String> {
+        let path = match self.runtime.env_var("NETRC") {
+            Ok(path) if !path.is_empty() => PathBuf::from(path),
+            _ => self.runtime.home_dir()?.join(NETRC_FILE),
+        };
+        let content = self.read_optional(&path)?;
+        parse_netrc(&content, host)
+    }
+
+    /// gh keys its logins by GitHub instance; the `github.com` login is the
+    /// token for `api.github.com`, and is not sent to `github.com` itself.
+    fn gh_hosts_token(&self, host: &str) -> Option<String> {
+        let instance = match host {
+            "api.github.com" => "github.com",
+            "github.com" => return None,
+            host => host,
+        };
+        let content = self.read_optional(&self.gh_hosts_path()?)?;
+        parse_gh_hosts(&content, instance)
+    }
+
+    /// Location of the gh CLI's hosts.yml
+    fn gh_hosts_path(&self) -> Option<PathBuf> {
+        let env_dir = |key: &str| {
+            self.runtime
+                .env_var(key)
+                .ok()
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+        };
+        let dir = if let Some(dir) = env_dir("GH_CONFIG_DIR") {
+            dir
+        } else if let Some(dir) = env_dir("XDG_CONFIG_HOME") {
+            dir.join("gh")
+        } else if cfg!(windows) {
+            env_dir("AppData")?.join("GitHub CLI")
+        } else {
+            self.runtime.home_dir()?.join(".config").join("gh")
+        };
+        Some(dir.join("hosts.yml"))
+    }
+
+    fn read_optional(&self, path: &Path) -> Option<String> {
+        if !self.runtime.exists(path) {
+            return None;
+        }
+        match self.runtime.read_to_string(path) {
+            Ok(content) => Some(content),
+            Err(e) => {
+                warn!("Failed to read {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+}
+
+impl<R: Runtime> TokenSource for CredentialResolver<R> {
+    fn token(&self, origin: &Origin) -> Option<String> {
+        let host = &origin.authority();
+        self.env_token(host)
+            .or_else(|| self.config_token(origin))
+            .or_else(|| self.helper_token(origin, host))
+            .or_else(|| self.netrc_token(host))
+            .or_else(|| self.gh_hosts_token(host))
+    }
+}
+
+#[cfg(not(windows))]
+const NETRC_FILE: &str = ".netrc";
+#[cfg(windows)]
+const NETRC_FILE: &str = "_netrc";
+
+/// Environment variable holding the token for a host: `GHRI_TOKEN_` + upper-cased host
+/// with every non-alphanumeric character replaced by `_`.
+pub fn host_env_var(host: &str) -> String {
+    let suffix: String = host
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("GHRI_TOKEN_{}", suffix)
+}
+
+/// Build the shell invocation for a credential helper, passing the host as an argument.
+fn shell_command(helper: &str, host: &str) -> (&'static str, Vec<String>) {
+    if cfg!(windows) {
+        ("cmd", vec!["/C".into(), format!("{} {}", helper, host)])
+    } else {
+        (
+            "sh",
+            vec![
+                "-c".into(),
+                format!("{} \"$1\"", helper),
+                "ghri".into(),
+                host.into(),
+            ],
+        )
+    }
+}
+
+/// Find the password for a host in netrc content (`machine <host> ... password <token>`).
+/// Host names match exactly, ignoring case. `default` entries are ignored.
+pub fn parse_netrc(content: &str, host: &str) -> Option<String> {
+    let mut tokens = content.split_whitespace();
+    let mut in_match = false;
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "machine" => {
+                let machine = tokens.next()?;
+                in_match = normalize_host(machine) == host;
+            }
+            "default" => in_match = false,
+            "password" => {
+                let password = tokens.next()?;
+                if in_match {
+                    return Some(password.to_string());
+                }
+            }
+            // Skip the value of other keywords
+            "login" | "account" => {
+                tokens.next();
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Find the `oauth_token` for a host in the gh CLI's `hosts.yml`.
+///
+/// Only the simple layout written by gh is supported: top-level host keys
+/// with indented `key: value` pairs. Tokens nested deeper (per-user entries
+/// under `users:`) are ignored in favor of the host's active token.
+pub fn parse_gh_hosts(content: &str, host: &str) -> Option<String> {
+    let mut in_host = false;
+    let mut host_indent = None;
+    for line in content.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        if indent == 0 {
+            let key = line.trim().trim_end_matches(':').trim_matches(['"', '\'']);
+            in_host = normalize_host(key) == host;
+            host_indent = None;
+            continue;
+        }
+        // The first indented line sets the indentation of the host's own keys
+        let level = *host_indent.get_or_insert(indent);
+        if in_host
+            && indent == level
+            && let Some(value) = line.trim().strip_prefix("oauth_token:")
+        {
+            let value = value.trim().trim_matches(['"', '\'']);
+            if !value.is_empty() {
+                return Some(value.to_string());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::MockRuntime;
+    use crate::test_utils::test_home;
+    use mockall::predicate::*;
+
+    fn origin(host: &str) -> Origin {
+        Origin::parse(&format!("https://{}/", host)).unwrap()
+    }
+
+    fn config_with(api_url: &str, token: Option<&str>) -> Config {
+        let mut config = Config::for_test("/test");
+        config.api_url = api_url.to_string();
+        config.token = token.map(str::to_string);
+        config
+    }
+
+    /// Runtime with the given environment and no credential files
+    fn runtime_with_env(vars: &'static [(&'static str, &'static str)]) -> MockRuntime {
+        let mut runtime = MockRuntime::new();
+        runtime.expect_env_var().returning(move |key| {
+            vars.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.to_string())
+                .ok_or(std::env::VarError::NotPresent)
+        });
+        runtime.expect_home_dir().returning(|| Some(test_home()));
+        runtime.expect_exists().returning(|_| false);
+        runtime
+    }
+
+    #[test]
+    fn test_host_env_var() {
+        assert_eq!(host_env_var("github.com"), "GHRI_TOKEN_GITHUB_COM");
+        assert_eq!(
+            host_env_var("git-lab.example.com"),
+            "GHRI_TOKEN_GIT_LAB_EXAMPLE_COM"
+        );
+    }
+
+    #[test]
+    fn test_default_token_only_for_api_host() {
+        let runtime = Arc::new(runtime_with_env(&[]));
+        let resolver = CredentialResolver::new(
+            runtime,
+            &config_with("https://api.github.com", Some("gh_token")),
+        );
+
+        assert_eq!(
+            resolver.token(&origin("api.github.com")).as_deref(),
+            Some("gh_token")
+        );
+        assert_eq!(resolver.token(&origin("gitlab.com")), None);
+        // Same host, different port or scheme: not the API origin
+        assert_eq!(resolver.token(&origin("api.github.com:8443")), None);
+        assert_eq!(
+            resolver.token(&Origin::parse("http://api.github.com/").unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_host_env_var_wins() {
+        let runtime = Arc::new(runtime_with_env(&[(
+            "GHRI_TOKEN_API_GITHUB_COM",
+            "host_env_token",
+        )]));
+        let resolver = CredentialResolver::new(
+            runtime,
+            &config_with("https://api.github.com", Some("gh_token")),
+        );
+
+        assert_eq!(
+            resolver.token(&origin("api.github.com")).as_deref(),
+            Some("host_env_token")
+        );
+    }
+
+    #[test]
+    fn test_config_host_token() {
+        let runtime = Arc::new(runtime_with_env(&[]));
+        let mut config = config_with("https://api.github.com", None);
+        config.hosts.insert(
+            "GitLab.example.com".into(),
+            HostConfig {
+                token: Some("gl_token".into()),
+                ..Default::default()
+            },
+        );
+        let resolver = CredentialResolver::new(runtime, &config);
+
+        assert_eq!(
+            resolver.token(&origin("gitlab.example.com")).as_deref(),
+            Some("gl_token")
+        );
+    }
+
+    #[test]
+    fn test_credential_helper() {
+        let mut runtime = runtime_with_env(&[]);
+        runtime
+            .expect_run_command()
+            .withf(|program, args| {
+                (program == "sh" || program == "cmd")
+                    && args.iter().any(|a| a.contains("pass show ghri"))
+                    && args.iter().any(|a| a.contains("github.example.com"))
+            })
+            .times(1)
+            .returning(|_, _| Ok("helper_token\nextra\n".into()));
+
+        let mut config = config_with("https://github.example.com/api/v3", None);
+        config.credential_helper = Some("pass show ghri".into());
+        let resolver = CredentialResolver::new(Arc::new(runtime), &config);
+
+        assert_eq!(
+            resolver.token(&origin("github.example.com")).as_deref(),
+            Some("helper_token")
+        );
+    }
+
+    #[test]
+    fn test_credential_helper_not_run_for_download_hosts() {
+        // --- Setup ---
+        let mut runtime = runtime_with_env(&[]);
+        runtime
+            .expect_run_command()
+            .withf(|_, args| args.iter().any(|a| a == "mirror.example.com"))
+            .times(1)
+            .returning(|_, _| Ok("helper_token\n".into()));
+
+        let mut config = config_with("https://api.github.com", None);
+        config.credential_helper = Some("pass show ghri".into());
+        config
+            .hosts
+            .insert("mirror.example.com".into(), HostConfig::default());
+        let resolver = CredentialResolver::new(Arc::new(runtime), &config);
+
+        // --- Execute & Verify ---
+        // Release downloads and vendor hosts never reach the helper
+        assert_eq!(resolver.token(&origin("github.com")), None);
+        assert_eq!(resolver.token(&origin("dl.example.com")), None);
+        // Hosts listed under [hosts] do
+        assert_eq!(
+            resolver.token(&origin("mirror.example.com")).as_deref(),
+            Some("helper_token")
+        );
+    }
+
+    #[test]
+    fn test_credential_helper_failure_falls_through() {
+        let mut runtime = runtime_with_env(&[]);
+        runtime
+            .expect_run_command()
+            .returning(|_, _| Err(anyhow::anyhow!("exit 1")));
+
+        let mut config = config_with("https://github.example.com/api/v3", None);
+        config.credential_helper = Some("false".into());
+        let resolver = CredentialResolver::new(Arc::new(runtime), &config);
+
+        assert_eq!(resolver.token(&origin("github.example.com")), None);
+    }
+
+    #[test]
+    fn test_netrc_and_gh_hosts_files() {
+        let netrc = test_home().join(NETRC_FILE);
+        let gh_hosts = test_home().join(".config").join("gh").join("hosts.yml");
+
+        let mut runtime = MockRuntime::new();
+        runtime
+            .expect_env_var()
+            .returning(|_| Err(std::env::VarError::NotPresent));
+        runtime.expect_home_dir().returning(|| Some(test_home()));
+        runtime.expect_exists().returning(|_| true);
+        runtime
+            .expect_read_to_string()
+            .with(eq(netrc))
+            .returning(|_| Ok("machine gitlab.com login me password netrc_token\n".into()));
+        runtime
+            .expect_read_to_string()
+            .with(eq(gh_hosts))
+            .returning(|_| Ok("github.com:\n    user: me\n    oauth_token: gho_abc\n".into()));
+
+        let resolver = CredentialResolver::new(
+            Arc::new(runtime),
+            &config_with("https://api.github.com", None),
+        );
+
+        assert_eq!(
+            resolver.token(&origin("gitlab.com")).as_deref(),
+            Some("netrc_token")
+        );
+        assert_eq!(
+            resolver.token(&origin("api.github.com")).as_deref(),
+            Some("gho_abc")
+        );
+        // gh's github.com login is for the API only, not release downloads
+        assert_eq!(resolver.token(&origin("github.com")), None);
+    }
+
+    #[test]
+    fn test_parse_netrc() {
+        let content = "\
+machine api.github.com
+  login me
+  password gh_secret
+machine gitlab.com login me password gl_secret
+default login anon password anon_secret
+";
+        assert_eq!(
+            parse_netrc(content, "api.github.com").as_deref(),
+            Some("gh_secret")
+        );
+        // The API host's entry is not used for github.com
+        assert_eq!(parse_netrc(content, "github.com"), None);
+        assert_eq!(
+            parse_netrc(content, "gitlab.com").as_deref(),
+            Some("gl_secret")
+        );
+        assert_eq!(parse_netrc(content, "example.com"), None);
+    }
+
+    #[test]
+    fn test_parse_gh_hosts() {
+        let content = "\
+github.com:
+    user: me
+    oauth_token: gho_public
+    git_protocol: https
+\"github.example.com\":
+    users:
+        me:
+            oauth_token: gho_nested
+    oauth_token: gho_enterprise
+";
+        assert_eq!(
+            parse_gh_hosts(content, "github.com").as_deref(),
+            Some("gho_public")
+        );
+        // Per-user tokens are skipped in favor of the host's active token
+        assert_eq!(
+            parse_gh_hosts(content, "github.example.com").as_deref(),
+            Some("gho_enterprise")
+        );
+        assert_eq!(parse_gh_hosts(content, "gitlab.com"), None);
+    }
+}
@@ -0,0 +1,251 @@
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use std::path::{Path, PathBuf};
+
+use crate::domain::model::{LinkRule, Manifest, ManifestFormat, ManifestPackage, VersionedLink};
+use crate::domain::service::PackageRepository;
+use crate::runtime::Runtime;
+
+use super::config::{Config, ExportOptions};
+
+/// Write the installed packages as a manifest that `ghri sync` can replay
+#[tracing::instrument(skip(runtime, config, options))]
+pub fn export<R: Runtime>(runtime: R, config: Config, options: ExportOptions) -> Result<()> {
+    let format = options
+        .format
+        .or_else(|| options.output.as_deref().map(ManifestFormat::from_path))
+        .unwrap_or_default();
+
+    let pkg_repo = PackageRepository::new(&runtime, config.install_root);
+    let manifest = build_manifest(&runtime, &pkg_repo)?;
+    let content = manifest.to_string_as(format)?;
+
+    match options.output {
+        Some(path) => {
+            runtime
+                .write(&path, content.as_bytes())
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!(
+                "   exported {} package(s) to {}",
+                manifest.packages.len(),
+                path.display()
+            );
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+/// Collect version, filters and links of every installed package
+fn build_manifest<R: Runtime>(
+    runtime: &R,
+    pkg_repo: &PackageRepository<'_, R>,
+) -> Result<Manifest> {
+    let home = runtime.home_dir();
+    let portable = |path: &Path| match &home {
+        Some(home) => home_relative(home, path),
+        None => path.to_path_buf(),
+    };
+
+    let mut manifest = Manifest::default();
+    for (_, meta) in pkg_repo.find_all_with_meta()? {
+        if meta.current_version.is_empty() {
+            debug!("Skipping {}: no current version", meta.name);
+            continue;
+        }
+        if meta.from_file {
+            warn!(
+                "Skipping {}: installed from a local file, which a manifest cannot describe",
+                meta.name
+            );
+            continue;
+        }
+
+        let mut links: Vec<LinkRule> = meta
+            .links
+            .iter()
+            .map(|rule| LinkRule {
+                dest: portable(&rule.dest),
+                digest: None,
+                ..rule.clone()
+            })
+            .collect();
+        if let Some(linked_to) = &meta.linked_to
+            && links.is_empty()
+        {
+            links.push(LinkRule {
+                dest: portable(linked_to),
+                path: meta.linked_path.clone(),
+                ..Default::default()
+            });
+        }
+        let versioned_links = meta
+            .versioned_links
+            .iter()
+            .map(|link| VersionedLink {
+                dest: portable(&link.dest),
+                digest: None,
+                ..link.clone()
+            })
+            .collect();
+
+        manifest.packages.insert(
+            meta.name.clone(),
+            ManifestPackage {
+                repo: meta.source_repo.clone(),
+                url: meta.url_source.clone(),
+                version: Some(meta.current_version.clone()),
+                filters: meta.filters.clone(),
+                pre: None,
+                tag_pattern: meta.tag_pattern.clone(),
+                links,
+                versioned_links,
+            },
+        );
+    }
+    Ok(manifest)
+}
+
+/// Rewrite a path inside the home directory as `~/...`
+fn home_relative(home: &Path, path: &Path) -> PathBuf {
+    match path.strip_prefix(home) {
+        Ok(rest) if rest.as_os_str().is_empty() => PathBuf::from("~"),
+        Ok(rest) => Path::new("~").join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::Meta;
+    use crate::runtime::MockRuntime;
+    use crate::test_utils::{test_home, test_root};
+    use mockall::predicate::*;
+
+    /// Runtime with one installed package, owner/tool
+    fn runtime_with_package(meta: Meta) -> MockRuntime {
+        let root = test_root();
+        let owner_dir = root.join("owner");
+        let package_dir = owner_dir.join("tool");
+        let meta_path = package_dir.join("meta.json");
+
+        let mut runtime = MockRuntime::new();
+        runtime.expect_home_dir().returning(|| Some(test_home()));
+        runtime
+            .expect_exists()
+            .with(eq(root.clone()))
+            .returning(|_| true);
+        runtime
+            .expect_read_dir()
+            .with(eq(root.clone()))
+            .returning(move |_| Ok(vec![owner_dir.clone()]));
+        runtime
+            .expect_is_dir()
+            .with(eq(root.join("owner")))
+            .returning(|_| true);
+        runtime
+            .expect_read_dir()
+            .with(eq(root.join("owner")))
+            .returning(move |_| Ok(vec![package_dir.clone()]));
+        runtime
+            .expect_is_dir()
+            .with(eq(root.join("owner/tool")))
+            .returning(|_| true);
+        runtime
+            .expect_exists()
+            .with(eq(meta_path.clone()))
+            .returning(|_| true);
+        runtime
+            .expect_read_to_string()
+            .with(eq(meta_path))
+            .returning(move |_| Ok(serde_json::to_string(&meta).unwrap()));
+        runtime
+    }
+
+    #[test]
+    fn test_build_manifest() {
+        // --- Setup ---
+        let meta = Meta {
+            name: "owner/tool".into(),
+            current_version: "v2.0.0".into(),
+            filters: vec!["*linux*".into()],
+            tag_pattern: Some("v*".into()),
+            links: vec![LinkRule {
+                dest: test_home().join(".local/bin/tool"),
+                path: Some("bin/tool".into()),
+                shim: None,
+                ..Default::default()
+            }],
+            versioned_links: vec![VersionedLink {
+                dest: PathBuf::from("/opt/bin/tool-1"),
+                version: "v1.0.0".into(),
+                path: None,
+                shim: None,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let runtime = runtime_with_package(meta);
+        let pkg_repo = PackageRepository::new(&runtime, test_root());
+
+        // --- Execute ---
+        let manifest = build_manifest(&runtime, &pkg_repo).unwrap();
+
+        // --- Verify ---
+        let package = &manifest.packages["owner/tool"];
+        assert_eq!(package.version.as_deref(), Some("v2.0.0"));
+        assert_eq!(package.filters, vec!["*linux*"]);
+        assert_eq!(package.tag_pattern.as_deref(), Some("v*"));
+        assert_eq!(package.links[0].dest, PathBuf::from("~/.local/bin/tool"));
+        assert_eq!(package.links[0].path.as_deref(), Some("bin/tool"));
+        assert_eq!(
+            package.versioned_links[0].dest,
+            PathBuf::from("/opt/bin/tool-1")
+        );
+        assert_eq!(package.versioned_links[0].version, "v1.0.0");
+    }
+
+    #[test]
+    fn test_export_to_json_file() {
+        // --- Setup ---
+        let meta = Meta {
+            name: "owner/tool".into(),
+            current_version: "v2.0.0".into(),
+            ..Default::default()
+        };
+        let mut runtime = runtime_with_package(meta);
+        runtime
+            .expect_write()
+            .withf(|path, content| {
+                let json = std::str::from_utf8(content).unwrap();
+                path == Path::new("/work/tools.json")
+                    && Manifest::parse_as(json, ManifestFormat::Json).is_ok()
+                    && json.contains("\"v2.0.0\"")
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let options = ExportOptions {
+            format: None,
+            output: Some(PathBuf::from("/work/tools.json")),
+        };
+
+        // --- Execute & Verify ---
+        export(runtime, Config::for_test(test_root()), options).unwrap();
+    }
+
+    #[test]
+    fn test_home_relative() {
+        let home = test_home();
+        assert_eq!(home_relative(&home, &home), PathBuf::from("~"));
+        assert_eq!(
+            home_relative(&home, &home.join("bin/tool")),
+            PathBuf::from("~/bin/tool")
+        );
+        assert_eq!(
+            home_relative(&home, Path::new("/usr/local/bin/tool")),
+            PathBuf::from("/usr/local/bin/tool")
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use log::{debug, warn};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::application::GenerationAction;
+use crate::domain::model::format_timestamp;
+use crate::runtime::Runtime;
+
+use super::config::Config;
+use super::print_executable_links;
+
+/// List the recorded generations, marking the one matching the current toolset
+#[tracing::instrument(skip(runtime, config))]
+pub fn generations_list<R: Runtime>(runtime: R, config: Config) -> Result<()> {
+    let action = GenerationAction::new(&runtime, config.install_root);
+    let generations = action.load()?;
+    if generations.generations.is_empty() {
+        println!("No generations recorded.");
+        return Ok(());
+    }
+
+    let current = action.current(&generations)?;
+    for generation in &generations.generations {
+        let marker = if Some(generation.id) == current {
+            "*"
+        } else {
+            " "
+        };
+        println!(
+            "{} {:>4}  {}  {} ({} package(s))",
+            marker,
+            generation.id,
+            format_timestamp(generation.created_at),
+            generation.command,
+            generation.packages.len()
+        );
+    }
+    Ok(())
+}
+
+/// Restore all packages to the versions and links of a generation
+#[tracing::instrument(skip(runtime, config))]
+pub fn generations_switch<R: Runtime>(runtime: R, id: u32, config: Config) -> Result<()> {
+    let action = GenerationAction::new(&runtime, config.install_root);
+    let result = action.switch(id)?;
+
+    for (repo, switched) in &result.switched {
+        if switched.from == switched.to {
+            println!("   relinked {} {}", repo, switched.to);
+        } else {
+            println!("   switched {} {} -> {}", repo, switched.from, switched.to);
+        }
+        print_executable_links(&switched.executables);
+    }
+    for name in &result.not_in_generation {
+        println!("   {} is not part of generation {}, left as is", name, id);
+    }
+    if result.switched.is_empty() {
+        println!("Already at generation {}.", id);
+    } else {
+        println!("Switched to generation {}.", id);
+    }
+    Ok(())
+}
+
+/// Delete generations so prune can remove the versions only they reference
+#[tracing::instrument(skip(runtime, config))]
+pub fn generations_delete<R: Runtime>(runtime: R, ids: &[u32], config: Config) -> Result<()> {
+    let action = GenerationAction::new(&runtime, config.install_root);
+    action.delete(ids)?;
+    println!("Deleted {} generation(s).", ids.len());
+    Ok(())
+}
+
+/// Record the toolset as a new generation after a mutating command
+///
+/// Failures only produce a warning: the command itself already succeeded.
+pub fn record_generation<R: Runtime>(runtime: &R, install_root: &Path, command: &str) {
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let action = GenerationAction::new(runtime, install_root.to_path_buf());
+    match action.record(created_at, command) {
+        Ok(Some(id)) => debug!("Recorded generation {}", id),
+        Ok(None) => debug!("Toolset unchanged, no generation recorded"),
+        Err(e) => warn!("Failed to record generation: {}", e),
+    }
+}
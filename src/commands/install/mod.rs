@@ -1,17 +1,22 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use log::warn;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use crate::application::{InstallAction, InstallOperations};
 use crate::cleanup::CleanupContext;
-use crate::provider::PackageSpec;
-use crate::runtime::Runtime;
+use crate::domain::model::{Meta, TagPattern, UrlSource, VersionConstraint, VersionResolver};
+use crate::download::local_file_path;
+use crate::provider::{PackageSpec, Provider, Release, ReleaseAsset, RepoId};
+use crate::runtime::{Runtime, resolve_relative_path};
 
 use super::config::{Config, InstallOptions};
+use super::generations::record_generation;
+use super::print_executable_links;
 use super::prune::prune_package_dir;
 use super::services::Services;
 
-mod ui;
+pub(super) mod ui;
 
 pub use crate::domain::service::release_installer::{
     DefaultReleaseInstaller, ReleaseInstaller, get_download_plan,
@@ -32,7 +37,7 @@ pub async fn install<R: Runtime + 'static>(
     let runtime = Arc::new(runtime);
 
     // Build services from config
-    let services = Services::from_config(&config)?;
+    let services = Services::from_config(Arc::clone(&runtime), &config)?;
 
     // Create action (borrows from Arc)
     let action = InstallAction::new(
@@ -58,7 +63,14 @@ pub async fn install<R: Runtime + 'static>(
         repo_str,
         options,
     )
-    .await
+    .await?;
+
+    record_generation(
+        runtime.as_ref(),
+        &config.install_root,
+        &format!("install {}", repo_str),
+    );
+    Ok(())
 }
 
 /// Core installation logic - separated for testability
@@ -73,22 +85,62 @@ pub async fn run_install<R: Runtime + 'static>(
     action: &dyn InstallOperations,
     release_installer: &dyn ReleaseInstaller,
     repo_str: &str,
-    options: InstallOptions,
+    mut options: InstallOptions,
 ) -> Result<()> {
-    let spec = repo_str.parse::<PackageSpec>()?;
-    let repo = &spec.repo;
+    // `ghri install ./tool.tar.gz --as owner/tool@1.2.3` installs a local artifact
+    let local_file = local_artifact_path(runtime.as_ref(), repo_str)?;
+    let spec = match &local_file {
+        Some(path) => artifact_spec(path, options.alias.take(), &options)?,
+        None => repo_str.parse::<PackageSpec>()?,
+    };
+    let package = match &options.alias {
+        Some(alias) => spec.repo.alias(alias)?,
+        None => spec.repo.clone(),
+    };
+    let repo = &package;
 
     println!("   resolving {}", repo);
 
     // Get or fetch metadata
-    let source = action.resolve_source_for_new()?;
-    let (mut meta, is_new) = action.get_or_fetch_meta(repo, source.as_ref()).await?;
+    let (mut meta, is_new) = match (&local_file, &options.url_source) {
+        (Some(path), _) => {
+            let version = spec.version.as_deref().unwrap_or_default();
+            local_file_package_meta(action, runtime.as_ref(), repo, path, version)?
+        }
+        (None, Some(url_source)) => fetch_url_package_meta(action, repo, url_source).await?,
+        (None, None) => {
+            let source = action.resolve_source_for_new()?;
+            get_or_fetch_package_meta(action, repo, &spec.repo, source.as_ref()).await?
+        }
+    };
 
-    // Get effective filters
-    let effective_filters = action.effective_filters(&options, &meta);
+    // A new tag pattern replaces the saved one and applies to this resolution
+    if let Some(pattern) = &options.tag_pattern {
+        TagPattern::parse(pattern)?;
+        meta.tag_pattern = Some(pattern.clone());
+    }
 
-    // Resolve version
-    let release = action.resolve_version(&meta, spec.version.clone(), options.pre)?;
+    // Get effective filters; a local artifact is installed as given
+    let effective_filters = action.effective_filters(&options, &meta);
+    let download_filters = match &local_file {
+        Some(_) => vec![],
+        None => effective_filters.clone(),
+    };
+
+    // Resolve version; without a version a pinned package stays at its pin,
+    // and one installed with a range stays inside it
+    let version = match (&spec.version, &meta.pinned, &meta.version_constraint) {
+        (None, Some(pinned), _) => {
+            println!("   {} is pinned to {}", repo, meta.display_version(pinned));
+            Some(pinned.clone())
+        }
+        (None, None, Some(range)) => {
+            println!("   {} stays within {}", repo, range);
+            Some(range.clone())
+        }
+        _ => spec.version.clone(),
+    };
+    let release = action.resolve_version(&meta, version, options.pre)?;
 
     let target_dir = action.version_dir(repo, &release.tag);
     let meta_path = action.meta_path(repo);
@@ -98,7 +150,7 @@ pub async fn run_install<R: Runtime + 'static>(
         println!("   {} {} is already installed", repo, release.tag);
     } else {
         // Get download plan and show confirmation
-        let plan = get_download_plan(&release, &effective_filters)?;
+        let plan = get_download_plan(&release, &download_filters)?;
 
         if !options.yes {
             ui::show_install_plan(
@@ -122,7 +174,7 @@ pub async fn run_install<R: Runtime + 'static>(
                 repo,
                 &release,
                 &target_dir,
-                &effective_filters,
+                &download_filters,
                 &options.original_args,
             )
             .await?;
@@ -131,14 +183,48 @@ pub async fn run_install<R: Runtime + 'static>(
     // Update 'current' symlink
     action.update_current_link(repo, &release.tag)?;
 
+    // Link executables into the bin directory; this drops rules for executables
+    // that are gone, so do it before updating the links
+    let bin_dir = options
+        .bin_dir
+        .clone()
+        .or_else(|| meta.bin_dir.clone())
+        .or_else(|| options.default_bin_dir.clone());
+    if let Some(bin_dir) = bin_dir {
+        let bin_dir = if bin_dir.is_relative() {
+            resolve_relative_path(&runtime.current_dir()?, &bin_dir)
+        } else {
+            bin_dir
+        };
+        match action.link_executables(&mut meta, &target_dir, &bin_dir) {
+            Ok(links) => print_executable_links(&links),
+            Err(e) => warn!("Failed to link executables: {}. Continuing.", e),
+        }
+        meta.bin_dir = Some(bin_dir);
+    }
+
     // Update external links
-    if let Err(e) = action.update_external_links(&meta, &target_dir) {
+    if let Err(e) = action.update_external_links(&mut meta, &target_dir) {
         warn!("Failed to update external links: {}. Continuing.", e);
     }
 
     // Save metadata
-    meta.current_version = release.tag.clone();
+    meta.set_current_version(&release.tag);
     meta.filters = effective_filters;
+    // Remember a version range so upgrades stay inside it; installing a
+    // version outside the saved range drops it
+    match spec.version.as_deref() {
+        Some(range) if VersionConstraint::is_range(range) => {
+            meta.version_constraint = Some(range.to_string());
+        }
+        _ => {
+            if let Some(saved) = &meta.version_constraint
+                && !VersionResolver::tag_in_range(&release.tag, saved)
+            {
+                meta.version_constraint = None;
+            }
+        }
+    }
     if let Err(e) = action.save_meta(repo, &meta) {
         warn!("Failed to save package metadata: {}. Continuing.", e);
     }
@@ -164,6 +250,165 @@ pub async fn run_install<R: Runtime + 'static>(
     Ok(())
 }
 
+/// Get or fetch the metadata of a package that may be an alias of `source_repo`
+///
+/// A new alias starts from the source repository's metadata, so the release
+/// data of an installed source package is reused instead of fetched again.
+pub(super) async fn get_or_fetch_package_meta(
+    action: &dyn InstallOperations,
+    package: &RepoId,
+    source_repo: &RepoId,
+    source: &dyn Provider,
+) -> Result<(Meta, bool)> {
+    if package == source_repo {
+        let (meta, is_new) = action.get_or_fetch_meta(package, source).await?;
+        // An alias takes an `owner/<name>` that may also be a real repository
+        let installed_from = meta.source_repo()?;
+        if &installed_from != package {
+            anyhow::bail!(
+                "{} is an alias installed from {}. Install it with: ghri install {} --as {}",
+                package,
+                installed_from,
+                installed_from,
+                package.repo
+            );
+        }
+        return Ok((meta, is_new));
+    }
+    if let Some(meta) = action.get_cached_meta(package)? {
+        let installed_from = meta.source_repo()?;
+        if &installed_from != source_repo {
+            anyhow::bail!(
+                "{} is already installed from {}. Choose another alias.",
+                package,
+                installed_from
+            );
+        }
+        return Ok((meta, false));
+    }
+    let (source_meta, _) = action.get_or_fetch_meta(source_repo, source).await?;
+    Ok((Meta::for_alias(source_meta, package), true))
+}
+
+/// Metadata of a package served from plain HTTPS, with freshly discovered versions
+///
+/// Versions are discovered on every install, so a changed URL template or
+/// discovery method applies right away.
+pub(super) async fn fetch_url_package_meta(
+    action: &dyn InstallOperations,
+    package: &RepoId,
+    url_source: &UrlSource,
+) -> Result<(Meta, bool)> {
+    url_source.validate()?;
+    let source = action.resolve_source_for_url(url_source)?;
+    let fresh = action.fetch_meta(package, source.as_ref(), "").await?;
+    let (mut meta, is_new) = match action.get_cached_meta(package)? {
+        Some(mut meta) => {
+            meta.api_url = fresh.api_url;
+            meta.releases = fresh.releases;
+            (meta, false)
+        }
+        None => (fresh, true),
+    };
+    meta.url_source = Some(url_source.clone());
+    Ok((meta, is_new))
+}
+
+/// Local release artifact named by the install argument, if it is one
+///
+/// Paths (`./tool.tar.gz`, `/srv/tool.tar.gz`) and `file://` URLs always are;
+/// any other argument is one only when it is not a package spec but an existing file.
+fn local_artifact_path<R: Runtime>(runtime: &R, arg: &str) -> Result<Option<PathBuf>> {
+    if let Some(path) = local_file_path(arg)? {
+        return Ok(Some(path));
+    }
+    let path = Path::new(arg);
+    let is_path = arg.starts_with('.') || path.is_absolute();
+    if is_path || (arg.parse::<PackageSpec>().is_err() && runtime.exists(path)) {
+        let path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            runtime.current_dir()?.join(path)
+        };
+        return Ok(Some(path));
+    }
+    Ok(None)
+}
+
+/// Package and version a local artifact is installed as, given by `--as`
+fn artifact_spec(
+    path: &Path,
+    name: Option<String>,
+    options: &InstallOptions,
+) -> Result<PackageSpec> {
+    if options.url_source.is_some() {
+        anyhow::bail!("--url cannot be used when installing from a file");
+    }
+    let usage = || {
+        anyhow::anyhow!(
+            "Installing {} needs the package and version: --as OWNER/REPO@VERSION",
+            path.display()
+        )
+    };
+    let spec = name.ok_or_else(usage)?.parse::<PackageSpec>()?;
+    if spec.version.is_none() {
+        return Err(usage());
+    }
+    Ok(spec)
+}
+
+/// Metadata of a package with a release made of one local artifact
+///
+/// The release is added to the installed package's metadata, replacing a
+/// release with the same tag. A new package is marked as installed from files,
+/// so `update` does not look for it on a forge.
+pub(super) fn local_file_package_meta<R: Runtime>(
+    action: &dyn InstallOperations,
+    runtime: &R,
+    package: &RepoId,
+    path: &Path,
+    version: &str,
+) -> Result<(Meta, bool)> {
+    if !runtime.exists(path) || runtime.is_dir(path) {
+        anyhow::bail!("{} is not a file", path.display());
+    }
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .with_context(|| format!("{} has no file name", path.display()))?;
+    let download_url = reqwest::Url::from_file_path(path)
+        .map_err(|_| anyhow::anyhow!("Cannot build a file URL for {}", path.display()))?
+        .to_string();
+    let release = Release {
+        tag: version.to_string(),
+        prerelease: VersionResolver::parse_tag(version).is_some_and(|v| !v.pre.is_empty()),
+        assets: vec![ReleaseAsset {
+            name,
+            size: 0,
+            download_url,
+            api_url: None,
+            local: true,
+        }],
+        ..Default::default()
+    };
+
+    let (mut meta, is_new) = match action.get_cached_meta(package)? {
+        Some(meta) => (meta, false),
+        None => (
+            Meta {
+                name: package.to_string(),
+                from_file: true,
+                ..Default::default()
+            },
+            true,
+        ),
+    };
+    meta.releases.retain(|r| r.tag != release.tag);
+    meta.releases.push(release);
+    meta.sort_releases();
+    Ok((meta, is_new))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -177,19 +422,21 @@ mod tests {
     fn default_install_options() -> InstallOptions {
         InstallOptions {
             filters: vec![],
+            default_filters: vec![],
             pre: false,
             yes: true, // Skip confirmation in tests
             prune: false,
+            tag_pattern: None,
+            alias: None,
+            url_source: None,
+            bin_dir: None,
+            default_bin_dir: None,
             original_args: vec![],
         }
     }
 
     fn test_config() -> Config {
-        Config {
-            install_root: PathBuf::from("/home/user/.ghri"),
-            api_url: "https://api.github.com".into(),
-            token: None,
-        }
+        Config::for_test("/home/user/.ghri")
     }
 
     fn test_meta() -> Meta {
@@ -209,6 +456,232 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_get_or_fetch_package_meta_new_alias() {
+        // --- Setup ---
+        let mut action = MockInstallOperations::new();
+        let package: RepoId = "owner/tool-musl".parse().unwrap();
+        let source_repo: RepoId = "owner/repo".parse().unwrap();
+        action
+            .expect_get_cached_meta()
+            .with(eq(package.clone()))
+            .returning(|_| Ok(None));
+        // Release data comes from the source repository's package
+        action
+            .expect_get_or_fetch_meta()
+            .with(eq(source_repo.clone()), always())
+            .times(1)
+            .returning(|_, _| {
+                let mut meta = test_meta();
+                meta.filters = vec!["*gnu*".into()];
+                Box::pin(async move { Ok((meta, false)) })
+            });
+
+        // --- Execute ---
+        let (meta, is_new) =
+            get_or_fetch_package_meta(&action, &package, &source_repo, &MockProvider::new())
+                .await
+                .unwrap();
+
+        // --- Verify ---
+        assert!(is_new);
+        assert_eq!(meta.name, "owner/tool-musl");
+        assert_eq!(meta.source_repo.as_deref(), Some("owner/repo"));
+        assert_eq!(meta.releases, test_meta().releases);
+        assert!(meta.filters.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_url_package_meta_refreshes_cached_releases() {
+        // --- Setup ---
+        let mut action = MockInstallOperations::new();
+        let url_source = UrlSource {
+            url: "https://dl.example.com/{version}/tool".into(),
+            versions: crate::domain::model::VersionDiscovery::List {
+                versions: vec!["2.0.0".into()],
+            },
+            os: Default::default(),
+            arch: Default::default(),
+        };
+        action
+            .expect_resolve_source_for_url()
+            .returning(|_| Ok(Arc::new(MockProvider::new())));
+        let fresh = url_source.release("2.0.0", "linux", "x86_64");
+        action.expect_fetch_meta().returning(move |_, _, _| {
+            let meta = Meta {
+                api_url: "https://dl.example.com/{version}/tool".into(),
+                releases: vec![fresh.clone()],
+                ..Default::default()
+            };
+            Box::pin(async move { Ok(meta) })
+        });
+        action.expect_get_cached_meta().returning(|_| {
+            let mut meta = test_meta();
+            meta.current_version = "v1.0.0".into();
+            Ok(Some(meta))
+        });
+
+        // --- Execute ---
+        let (meta, is_new) =
+            fetch_url_package_meta(&action, &"owner/repo".parse().unwrap(), &url_source)
+                .await
+                .unwrap();
+
+        // --- Verify ---
+        assert!(!is_new);
+        assert_eq!(meta.current_version, "v1.0.0");
+        assert_eq!(meta.releases.len(), 1);
+        assert_eq!(meta.releases[0].tag, "2.0.0");
+        assert_eq!(meta.url_source, Some(url_source));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_local_file_package_meta() {
+        // --- Setup ---
+        let mut runtime = MockRuntime::new();
+        runtime.expect_exists().returning(|_| true);
+        runtime.expect_is_dir().returning(|_| false);
+        let mut action = MockInstallOperations::new();
+        action.expect_get_cached_meta().returning(|_| Ok(None));
+        let path = Path::new("/srv/builds/tool-1.2.3-linux-x86_64.tar.gz");
+
+        // --- Execute ---
+        let (meta, is_new) = local_file_package_meta(
+            &action,
+            &runtime,
+            &"owner/tool".parse().unwrap(),
+            path,
+            "1.2.3",
+        )
+        .unwrap();
+
+        // --- Verify ---
+        assert!(is_new);
+        assert!(meta.from_file);
+        assert_eq!(meta.name, "owner/tool");
+        assert_eq!(meta.releases.len(), 1);
+        assert_eq!(meta.releases[0].tag, "1.2.3");
+        let asset = &meta.releases[0].assets[0];
+        assert_eq!(asset.name, "tool-1.2.3-linux-x86_64.tar.gz");
+        assert_eq!(
+            asset.download_url,
+            "file:///srv/builds/tool-1.2.3-linux-x86_64.tar.gz"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_local_file_package_meta_adds_release_to_installed_package() {
+        let mut runtime = MockRuntime::new();
+        runtime.expect_exists().returning(|_| true);
+        runtime.expect_is_dir().returning(|_| false);
+        let mut action = MockInstallOperations::new();
+        action
+            .expect_get_cached_meta()
+            .returning(|_| Ok(Some(test_meta())));
+
+        let (meta, is_new) = local_file_package_meta(
+            &action,
+            &runtime,
+            &"owner/repo".parse().unwrap(),
+            Path::new("/tmp/repo.tar.gz"),
+            "v1.1.0",
+        )
+        .unwrap();
+
+        // The forge package keeps its releases and stays updatable
+        assert!(!is_new);
+        assert!(!meta.from_file);
+        let tags: Vec<_> = meta.releases.iter().map(|r| r.tag.as_str()).collect();
+        assert_eq!(tags, vec!["v1.1.0", "v1.0.0"]);
+    }
+
+    #[test]
+    fn test_artifact_spec_needs_version() {
+        let path = Path::new("tool.tar.gz");
+        let options = default_install_options();
+
+        let spec = artifact_spec(path, Some("owner/tool@1.2.3".into()), &options).unwrap();
+        assert_eq!(spec.repo.to_string(), "owner/tool");
+        assert_eq!(spec.version.as_deref(), Some("1.2.3"));
+
+        for name in [None, Some("owner/tool".to_string())] {
+            let err = artifact_spec(path, name, &options).unwrap_err();
+            assert!(err.to_string().contains("--as OWNER/REPO@VERSION"));
+        }
+    }
+
+    #[test]
+    fn test_local_artifact_path() {
+        // Package specs never touch the filesystem
+        let runtime = MockRuntime::new();
+        assert_eq!(
+            local_artifact_path(&runtime, "owner/repo@v1").unwrap(),
+            None
+        );
+
+        let mut runtime = MockRuntime::new();
+        runtime
+            .expect_current_dir()
+            .returning(|| Ok(PathBuf::from("/work")));
+        assert_eq!(
+            local_artifact_path(&runtime, "./tool.tar.gz").unwrap(),
+            Some(PathBuf::from("/work/./tool.tar.gz"))
+        );
+
+        let mut runtime = MockRuntime::new();
+        runtime.expect_exists().returning(|_| false);
+        assert_eq!(local_artifact_path(&runtime, "tool.tar.gz").unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_or_fetch_package_meta_alias_taken() {
+        let mut action = MockInstallOperations::new();
+        let package: RepoId = "owner/other".parse().unwrap();
+        action.expect_get_cached_meta().returning(|_| {
+            Ok(Some(Meta {
+                name: "owner/other".into(),
+                ..Default::default()
+            }))
+        });
+
+        let err = get_or_fetch_package_meta(
+            &action,
+            &package,
+            &"owner/repo".parse().unwrap(),
+            &MockProvider::new(),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("already installed from owner/other")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_or_fetch_package_meta_name_taken_by_alias() {
+        let mut action = MockInstallOperations::new();
+        let package: RepoId = "owner/repo-musl".parse().unwrap();
+        action.expect_get_or_fetch_meta().returning(|_, _| {
+            let meta = Meta {
+                name: "owner/repo-musl".into(),
+                source_repo: Some("owner/repo".into()),
+                ..Default::default()
+            };
+            Box::pin(async move { Ok((meta, false)) })
+        });
+
+        let err = get_or_fetch_package_meta(&action, &package, &package, &MockProvider::new())
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("owner/repo-musl is an alias installed from owner/repo")
+        );
+    }
+
     #[tokio::test]
     async fn test_run_install_happy_path() {
         // Test successful installation with mocked traits
@@ -446,7 +919,9 @@ mod tests {
     #[tokio::test]
     async fn test_run_install_invalid_repo_spec() {
         // Test that invalid repo spec returns error early
-        let runtime = MockRuntime::new();
+        let mut runtime = MockRuntime::new();
+        // Not a local artifact either
+        runtime.expect_exists().returning(|_| false);
         let action = MockInstallOperations::new(); // No calls expected
         let release_installer = MockReleaseInstaller::new(); // No calls expected
 
@@ -651,4 +1126,80 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    /// Action mock that installs v1.0.0 of test_meta() with the given saved range
+    /// and expects save_meta to store `expected_range`
+    fn range_install_action(
+        saved_range: Option<&str>,
+        requested: &'static str,
+        expected_range: Option<&'static str>,
+    ) -> MockInstallOperations {
+        let mut action = MockInstallOperations::new();
+        let mut meta = test_meta();
+        meta.version_constraint = saved_range.map(String::from);
+        let release = meta.releases[0].clone();
+
+        action
+            .expect_resolve_source_for_new()
+            .returning(|| Ok(Arc::new(MockProvider::new())));
+        action.expect_get_or_fetch_meta().returning(move |_, _| {
+            let m = meta.clone();
+            Box::pin(async move { Ok((m, false)) })
+        });
+        action.expect_effective_filters().returning(|_, _| vec![]);
+        action
+            .expect_resolve_version()
+            .withf(move |_, version, _| version.as_deref() == Some(requested))
+            .returning(move |_, _, _| Ok(release.clone()));
+        action.expect_is_installed().returning(|_, _| true);
+        action
+            .expect_version_dir()
+            .returning(|_, _| PathBuf::from("/home/user/.ghri/owner/repo/v1.0.0"));
+        action
+            .expect_meta_path()
+            .returning(|_| PathBuf::from("/home/user/.ghri/owner/repo/meta.json"));
+        action.expect_update_current_link().returning(|_, _| Ok(()));
+        action
+            .expect_update_external_links()
+            .returning(|_, _| Ok(()));
+        action
+            .expect_save_meta()
+            .withf(move |_, meta| meta.version_constraint.as_deref() == expected_range)
+            .times(1)
+            .returning(|_, _| Ok(()));
+        action
+    }
+
+    #[tokio::test]
+    async fn test_run_install_saves_version_range() {
+        // --- Setup ---
+        let config = test_config();
+        let release_installer = MockReleaseInstaller::new();
+        let cases = [
+            // A range spec is saved
+            ("owner/repo@^1.0", None, "^1.0", Some("^1.0")),
+            // An exact version inside the saved range keeps it
+            ("owner/repo@v1.0.0", Some("^1.0"), "v1.0.0", Some("^1.0")),
+            // A version outside the saved range drops it
+            ("owner/repo@v1.0.0", Some("^2"), "v1.0.0", None),
+            // Without a version, the saved range is resolved and kept
+            ("owner/repo", Some("^1.0"), "^1.0", Some("^1.0")),
+        ];
+
+        for (spec, saved, requested, expected) in cases {
+            let action = range_install_action(saved, requested, expected);
+
+            // --- Execute & Verify ---
+            run_install(
+                &config,
+                Arc::new(MockRuntime::new()),
+                &action,
+                &release_installer,
+                spec,
+                default_install_options(),
+            )
+            .await
+            .unwrap();
+        }
+    }
 }
@@ -2,19 +2,29 @@ use anyhow::Result;
 use std::path::PathBuf;
 
 use crate::application::LinkAction;
+use crate::domain::model::{LinkMode, Shim};
 use crate::domain::service::PackageRepository;
 use crate::runtime::Runtime;
 
 use super::config::Config;
+use super::generations::record_generation;
 use super::link_spec::LinkSpec;
 
-/// Link a package's current version to a destination directory
+/// Link a package's current version to a destination directory, with a
+/// symlink, a shim, a copy or a hard link
 #[tracing::instrument(skip(runtime, config))]
-pub fn link<R: Runtime>(runtime: R, repo_str: &str, dest: PathBuf, config: Config) -> Result<()> {
+pub fn link<R: Runtime>(
+    runtime: R,
+    repo_str: &str,
+    dest: PathBuf,
+    shim: Option<Shim>,
+    mode: LinkMode,
+    config: Config,
+) -> Result<()> {
     let spec = repo_str.parse::<LinkSpec>()?;
 
     let pkg_repo = PackageRepository::new(&runtime, config.install_root.clone());
-    let action = LinkAction::new(&runtime, config.install_root);
+    let action = LinkAction::new(&runtime, config.install_root.clone());
 
     // Load package context - this handles version normalization
     let mut ctx =
@@ -34,7 +44,7 @@ pub fn link<R: Runtime>(runtime: R, repo_str: &str, dest: PathBuf, config: Confi
     }
 
     // Delegate to LinkAction for the actual work
-    let result = action.create_package_link(&mut ctx, dest, spec.path)?;
+    let result = action.create_package_link(&mut ctx, dest, spec.path, shim, mode)?;
 
     // Display result
     println!(
@@ -44,6 +54,11 @@ pub fn link<R: Runtime>(runtime: R, repo_str: &str, dest: PathBuf, config: Confi
         result.dest
     );
 
+    record_generation(
+        &runtime,
+        &config.install_root,
+        &format!("link {}", repo_str),
+    );
     Ok(())
 }
 
@@ -91,7 +106,14 @@ mod tests {
             .with(eq(v2_dir))
             .returning(|_| false);
 
-        let result = link(runtime, "owner/repo@v2", dest, Config::for_test(root));
+        let result = link(
+            runtime,
+            "owner/repo@v2",
+            dest,
+            None,
+            LinkMode::Symlink,
+            Config::for_test(root),
+        );
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not installed"));
     }
@@ -128,7 +150,14 @@ mod tests {
                 Err(std::io::Error::new(std::io::ErrorKind::NotFound, "not found").into())
             });
 
-        let result = link(runtime, "owner/repo", dest, Config::for_test(root));
+        let result = link(
+            runtime,
+            "owner/repo",
+            dest,
+            None,
+            LinkMode::Symlink,
+            Config::for_test(root),
+        );
         assert!(result.is_err());
         assert!(
             result
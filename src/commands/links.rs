@@ -2,8 +2,8 @@ use anyhow::Result;
 use log::debug;
 use std::path::Path;
 
-use crate::application::LinkAction;
-use crate::domain::model::{LinkRule, LinkStatus, VersionedLink};
+use crate::application::{ExecutableLinks, LinkAction};
+use crate::domain::model::{LinkMode, LinkRule, LinkStatus, Shim, VersionedLink};
 use crate::domain::service::PackageRepository;
 use crate::provider::PackageSpec;
 use crate::runtime::Runtime;
@@ -18,6 +18,30 @@ fn format_link_status(status: &LinkStatus) -> String {
         LinkStatus::NotSymlink => " [not a symlink]".to_string(),
         LinkStatus::WrongTarget => " [wrong target]".to_string(),
         LinkStatus::Unresolvable => " [unresolvable]".to_string(),
+        LinkStatus::Modified => " [modified]".to_string(),
+    }
+}
+
+/// Marker for links that are shims, copies or hard links rather than symlinks
+fn format_link_kind(shim: &Option<Shim>, mode: LinkMode) -> &'static str {
+    match mode {
+        _ if shim.is_some() => " (shim)",
+        LinkMode::Symlink => "",
+        LinkMode::Copy => " (copy)",
+        LinkMode::Hardlink => " (hardlink)",
+    }
+}
+
+/// Print the changes to the executables linked into a bin directory
+pub(crate) fn print_executable_links(links: &ExecutableLinks) {
+    for dest in &links.removed {
+        println!("   unlinked {}", dest.display());
+    }
+    for dest in &links.linked {
+        println!("   linked {}", dest.display());
+    }
+    for (dest, reason) in &links.skipped {
+        eprintln!("Warning: Skipping {:?} - {}", dest, reason);
     }
 }
 
@@ -38,12 +62,13 @@ pub(crate) fn print_links<R: Runtime>(
 
     let action = LinkAction::new(runtime, std::path::PathBuf::new());
     for rule in links {
-        let status = action.check_link(&rule.dest, expected_prefix);
+        let status = action.check_rule_link(&rule.dest, rule.digest.as_deref(), expected_prefix);
         let source = rule.path.as_deref().unwrap_or("(default)");
         println!(
-            "  {} -> {:?}{}",
+            "  {} -> {:?}{}{}",
             source,
             rule.dest,
+            format_link_kind(&rule.shim, rule.mode),
             format_link_status(&status)
         );
     }
@@ -67,13 +92,14 @@ pub(crate) fn print_versioned_links<R: Runtime>(
     let action = LinkAction::new(runtime, std::path::PathBuf::new());
     for link in links {
         let version_dir = package_dir.join(&link.version);
-        let status = action.check_link(&link.dest, &version_dir);
+        let status = action.check_rule_link(&link.dest, link.digest.as_deref(), &version_dir);
         let source = link.path.as_deref().unwrap_or("(default)");
         println!(
-            "  @{} {} -> {:?}{}",
+            "  @{} {} -> {:?}{}{}",
             link.version,
             source,
             link.dest,
+            format_link_kind(&link.shim, link.mode),
             format_link_status(&status)
         );
     }
@@ -313,6 +339,8 @@ mod tests {
             links: vec![LinkRule {
                 dest: link_dest.clone(),
                 path: Some("bin/tool".into()),
+                shim: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
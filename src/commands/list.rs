@@ -22,7 +22,20 @@ pub fn list<R: Runtime>(runtime: R, config: Config) -> Result<()> {
     debug!("Found {} package(s)", packages.len());
 
     for pkg in packages {
-        println!("{} {}", pkg.name, pkg.version);
+        let mut notes = Vec::new();
+        if let Some(source) = &pkg.source_repo {
+            notes.push(format!("alias of {}", source));
+        }
+        match pkg.pinned {
+            Some(pinned) if pinned == pkg.version => notes.push("pinned".to_string()),
+            Some(pinned) => notes.push(format!("pinned to {}", pinned)),
+            None => {}
+        }
+        if notes.is_empty() {
+            println!("{} {}", pkg.name, pkg.version);
+        } else {
+            println!("{} {} ({})", pkg.name, pkg.version, notes.join(", "));
+        }
     }
 
     Ok(())
@@ -0,0 +1,423 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::application::InstallAction;
+use crate::domain::model::{
+    ANY_PLATFORM, LockedFile, LockedPackage, LockedPlatform, Lockfile, Manifest,
+};
+use crate::domain::service::PackageRepository;
+use crate::download::{Downloader, sha256_file};
+use crate::platform::Platform;
+use crate::runtime::Runtime;
+
+use super::config::Config;
+use super::install::get_download_plan;
+use super::services::Services;
+use super::sync::{
+    PlannedPackage, absolute_manifest_path, installed_packages, plan_sync, refresh_listed_packages,
+};
+use crate::domain::service::release_installer::DownloadPlan;
+
+/// Resolve every package in a manifest to an exact release and write the lockfile
+#[tracing::instrument(skip(runtime, config))]
+pub async fn lock<R: Runtime + 'static>(
+    runtime: R,
+    config: Config,
+    manifest: PathBuf,
+) -> Result<()> {
+    let runtime = Arc::new(runtime);
+
+    let manifest_path = absolute_manifest_path(runtime.as_ref(), &manifest)?;
+    let manifest = Manifest::load(runtime.as_ref(), &manifest_path)?;
+    let manifest_dir = manifest_path.parent().unwrap_or(Path::new("/"));
+
+    let lock_path = Lockfile::path_for(&manifest_path);
+    let previous = if runtime.exists(&lock_path) {
+        Some(Lockfile::load(runtime.as_ref(), &lock_path)?)
+    } else {
+        None
+    };
+
+    let services = Services::from_config(Arc::clone(&runtime), &config)?;
+    let pkg_repo = PackageRepository::new(runtime.as_ref(), config.install_root.clone());
+    let installed = installed_packages(&pkg_repo)?;
+    refresh_listed_packages(runtime.as_ref(), &services, &config, &manifest, &installed).await?;
+
+    let action = InstallAction::new(
+        runtime.as_ref(),
+        &services.provider_factory,
+        config.install_root.clone(),
+    );
+    let plan = plan_sync(
+        runtime.as_ref(),
+        &action,
+        &manifest,
+        manifest_dir,
+        None,
+        &installed,
+        &config,
+        false,
+    )
+    .await?;
+
+    let platform = Platform::detect();
+    let mut lockfile = Lockfile::default();
+    for package in &plan.packages {
+        let locked = lock_package(
+            runtime.as_ref(),
+            &services.downloader,
+            package,
+            &platform,
+            previous.as_ref(),
+        )
+        .await
+        .with_context(|| format!("Failed to lock {}", package.repo))?;
+        let platforms: Vec<String> = locked.platforms.iter().map(|p| p.label()).collect();
+        println!(
+            "   locked {} {} ({})",
+            locked.name,
+            locked.tag,
+            platforms.join(", ")
+        );
+        if let Some(dropped) = previous
+            .as_ref()
+            .and_then(|previous| previous.get(&package.repo))
+            .filter(|old| old.tag != locked.tag)
+        {
+            let stale: Vec<String> = dropped
+                .platforms
+                .iter()
+                .map(|p| p.label())
+                .filter(|label| !platforms.contains(label))
+                .collect();
+            if !stale.is_empty() {
+                eprintln!(
+                    "Warning: {} moved from {} to {}, run `ghri lock` again on {}",
+                    locked.name,
+                    dropped.tag,
+                    locked.tag,
+                    stale.join(", ")
+                );
+            }
+        }
+        lockfile.packages.push(locked);
+    }
+
+    lockfile.save(runtime.as_ref(), &lock_path)?;
+    println!("   wrote {}", lock_path.display());
+    Ok(())
+}
+
+/// Record the files of a planned package with their SHA-256 digests.
+///
+/// The files are recorded for `platform`, or for every platform when the
+/// release is installed from its source tarball. What other platforms locked
+/// for the same tag is kept. Digests from the previous lockfile are kept for
+/// unchanged URLs of the same tag; other files are downloaded to a temporary
+/// file and hashed.
+async fn lock_package<R: Runtime, D: Downloader>(
+    runtime: &R,
+    downloader: &D,
+    package: &PlannedPackage,
+    platform: &Platform,
+    previous: Option<&Lockfile>,
+) -> Result<LockedPackage> {
+    let release = &package.release;
+    let previous = previous
+        .and_then(|lockfile| lockfile.get(&package.repo))
+        .filter(|locked| locked.tag == release.tag);
+    let known_digest = |url: &str| {
+        previous
+            .and_then(|locked| {
+                locked
+                    .platforms
+                    .iter()
+                    .flat_map(|p| &p.files)
+                    .find(|f| f.url == url)
+            })
+            .map(|f| f.sha256.clone())
+    };
+
+    let mut files = vec![];
+    let (os, arch) = match get_download_plan(release, &package.effective_filters)? {
+        DownloadPlan::Tarball { url } => {
+            let name = format!("{}-{}.tar.gz", package.repo.repo, release.tag);
+            let sha256 = match known_digest(&url) {
+                Some(sha256) => sha256,
+                None => {
+                    let temp_path = runtime.temp_dir().join(format!("ghri-lock-{}", name));
+                    let result = downloader.download(runtime, &url, &temp_path).await;
+                    hash_download(runtime, result, &temp_path)?
+                }
+            };
+            files.push(LockedFile {
+                name,
+                url,
+                api_url: None,
+                sha256,
+            });
+            (ANY_PLATFORM.to_string(), ANY_PLATFORM.to_string())
+        }
+        DownloadPlan::Assets { assets } => {
+            for asset in assets {
+                let sha256 = match known_digest(&asset.download_url) {
+                    Some(sha256) => sha256,
+                    None => {
+                        let temp_path =
+                            runtime.temp_dir().join(format!("ghri-lock-{}", asset.name));
+                        let result = downloader.download_asset(runtime, &asset, &temp_path).await;
+                        hash_download(runtime, result, &temp_path)?
+                    }
+                };
+                files.push(LockedFile {
+                    name: asset.name,
+                    url: asset.download_url,
+                    api_url: asset.api_url,
+                    sha256,
+                });
+            }
+            (platform.os.clone(), platform.arch.clone())
+        }
+    };
+
+    let mut platforms: Vec<LockedPlatform> = previous
+        .map(|locked| {
+            locked
+                .platforms
+                .iter()
+                .filter(|p| p.os != os || p.arch != arch)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    platforms.push(LockedPlatform {
+        os,
+        arch,
+        filters: package.effective_filters.clone(),
+        files,
+    });
+    platforms.sort_by(|a, b| (&a.os, &a.arch).cmp(&(&b.os, &b.arch)));
+
+    Ok(LockedPackage {
+        name: package.repo.to_string(),
+        tag: release.tag.clone(),
+        platforms,
+    })
+}
+
+/// Hash a downloaded temporary file and remove it
+fn hash_download<R: Runtime>(runtime: &R, result: Result<()>, temp_path: &Path) -> Result<String> {
+    let digest = result.and_then(|()| sha256_file(runtime, temp_path));
+    let _ = runtime.remove_file(temp_path);
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::sync::SyncChange;
+    use crate::domain::model::Meta;
+    use crate::download::mock::MockDownloader;
+    use crate::provider::{Release, ReleaseAsset};
+    use crate::runtime::MockRuntime;
+
+    // SHA-256 of "data"
+    const DATA_SHA256: &str = "3a6eb0790f39ac87c94f3856b2dd2c5d110e6811602261a9a923d3bb23adc8b7";
+
+    fn planned(assets: Vec<ReleaseAsset>, filters: Vec<String>) -> PlannedPackage {
+        PlannedPackage {
+            repo: "owner/tool".parse().unwrap(),
+            source_repo: "owner/tool".parse().unwrap(),
+            release: Release {
+                tag: "v1.0.0".into(),
+                tarball_url: "https://example.com/v1.0.0.tar.gz".into(),
+                assets,
+                ..Default::default()
+            },
+            change: SyncChange::Install,
+            filters: filters.clone(),
+            effective_filters: filters,
+            pre: false,
+            links: vec![],
+            versioned_links: vec![],
+            download: None,
+            meta: Meta::default(),
+            is_new: true,
+        }
+    }
+
+    fn asset(name: &str) -> ReleaseAsset {
+        ReleaseAsset {
+            name: name.into(),
+            size: 4,
+            download_url: format!("https://example.com/{}", name),
+            api_url: None,
+            local: false,
+        }
+    }
+
+    fn runtime_with_downloads() -> MockRuntime {
+        let mut runtime = MockRuntime::new();
+        runtime
+            .expect_temp_dir()
+            .returning(|| PathBuf::from("/tmp"));
+        runtime
+            .expect_open()
+            .returning(|_| Ok(Box::new(std::io::Cursor::new(b"data".to_vec()))));
+        runtime.expect_remove_file().returning(|_| Ok(()));
+        runtime
+    }
+
+    fn linux() -> Platform {
+        Platform {
+            os: "linux".into(),
+            arch: "x86_64".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lock_package_hashes_filtered_assets() {
+        // --- Setup ---
+        let runtime = runtime_with_downloads();
+        let package = planned(
+            vec![asset("tool-linux.tar.gz"), asset("tool-macos.tar.gz")],
+            vec!["*linux*".into()],
+        );
+
+        // --- Execute ---
+        let locked = lock_package(&runtime, &MockDownloader::new(), &package, &linux(), None)
+            .await
+            .unwrap();
+
+        // --- Verify ---
+        assert_eq!(locked.name, "owner/tool");
+        assert_eq!(locked.tag, "v1.0.0");
+        assert_eq!(
+            locked.platforms,
+            vec![LockedPlatform {
+                os: "linux".into(),
+                arch: "x86_64".into(),
+                filters: vec!["*linux*".into()],
+                files: vec![LockedFile {
+                    name: "tool-linux.tar.gz".into(),
+                    url: "https://example.com/tool-linux.tar.gz".into(),
+                    api_url: None,
+                    sha256: DATA_SHA256.into(),
+                }],
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lock_package_reuses_previous_digest() {
+        // --- Setup ---
+        // No runtime expectations: nothing may be downloaded
+        let runtime = MockRuntime::new();
+        let package = planned(vec![], vec![]);
+        let previous = Lockfile {
+            packages: vec![LockedPackage {
+                name: "owner/tool".into(),
+                tag: "v1.0.0".into(),
+                platforms: vec![LockedPlatform {
+                    os: ANY_PLATFORM.into(),
+                    arch: ANY_PLATFORM.into(),
+                    filters: vec![],
+                    files: vec![LockedFile {
+                        name: "tool-v1.0.0.tar.gz".into(),
+                        url: "https://example.com/v1.0.0.tar.gz".into(),
+                        api_url: None,
+                        sha256: "cafe".into(),
+                    }],
+                }],
+            }],
+            ..Default::default()
+        };
+
+        // --- Execute ---
+        let locked = lock_package(
+            &runtime,
+            &MockDownloader::new(),
+            &package,
+            &linux(),
+            Some(&previous),
+        )
+        .await
+        .unwrap();
+
+        // --- Verify ---
+        assert_eq!(locked, previous.packages[0]);
+    }
+
+    #[tokio::test]
+    async fn test_lock_package_keeps_other_platforms() {
+        // --- Setup ---
+        let runtime = runtime_with_downloads();
+        let package = planned(
+            vec![asset("tool-linux.tar.gz"), asset("tool-macos.tar.gz")],
+            vec!["*linux*".into()],
+        );
+        let macos = LockedPlatform {
+            os: "macos".into(),
+            arch: "aarch64".into(),
+            filters: vec!["*macos*".into()],
+            files: vec![LockedFile {
+                name: "tool-macos.tar.gz".into(),
+                url: "https://example.com/tool-macos.tar.gz".into(),
+                api_url: None,
+                sha256: "cafe".into(),
+            }],
+        };
+        let mut previous = Lockfile {
+            packages: vec![LockedPackage {
+                name: "owner/tool".into(),
+                tag: "v1.0.0".into(),
+                platforms: vec![macos.clone()],
+            }],
+            ..Default::default()
+        };
+
+        // --- Execute ---
+        let locked = lock_package(
+            &runtime,
+            &MockDownloader::new(),
+            &package,
+            &linux(),
+            Some(&previous),
+        )
+        .await
+        .unwrap();
+
+        // --- Verify ---
+        let labels: Vec<String> = locked.platforms.iter().map(|p| p.label()).collect();
+        assert_eq!(labels, vec!["linux-x86_64", "macos-aarch64"]);
+        assert_eq!(locked.platforms[1], macos);
+
+        // Files locked for another tag are dropped
+        previous.packages[0].tag = "v0.9.0".into();
+        let locked = lock_package(
+            &runtime,
+            &MockDownloader::new(),
+            &package,
+            &linux(),
+            Some(&previous),
+        )
+        .await
+        .unwrap();
+        let labels: Vec<String> = locked.platforms.iter().map(|p| p.label()).collect();
+        assert_eq!(labels, vec!["linux-x86_64"]);
+    }
+
+    #[tokio::test]
+    async fn test_lock_package_download_failure() {
+        let runtime = runtime_with_downloads();
+        let downloader = MockDownloader::new();
+        downloader.set_should_fail(true);
+        let package = planned(vec![], vec![]);
+
+        let err = lock_package(&runtime, &downloader, &package, &linux(), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Mock download failed"));
+    }
+}
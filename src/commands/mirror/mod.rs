@@ -0,0 +1,515 @@
+use anyhow::{Context, Result};
+use log::warn;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use crate::domain::model::{
+    MIRROR_CHECKSUMS, MIRROR_INDEX, MirrorIndex, MirroredFile, MirroredRelease, Release,
+};
+use crate::domain::service::release_installer::DownloadPlan;
+use crate::download::{Downloader, sha256_file};
+use crate::provider::{PackageSpec, Provider, ProviderFactory, RepoId};
+use crate::runtime::Runtime;
+
+use super::config::{Config, MirrorOptions};
+use super::install::get_download_plan;
+use super::services::Services;
+
+mod serve;
+
+pub use serve::mirror_serve;
+
+/// Download releases of repositories into a mirror directory
+#[tracing::instrument(skip(runtime, config, options))]
+pub async fn mirror_create<R: Runtime + 'static>(
+    runtime: R,
+    config: Config,
+    options: MirrorOptions,
+) -> Result<()> {
+    let runtime = Arc::new(runtime);
+    let mirror = absolute_dir(runtime.as_ref(), &options.dir)?;
+    let services = Services::from_config(Arc::clone(&runtime), &config)?;
+    let provider = services.provider_factory.default_provider();
+
+    for package in &options.packages {
+        let spec = package.parse::<PackageSpec>()?;
+        let index_path = MirrorIndex::path_for(&mirror, &spec.repo);
+        // Files mirrored before are kept; the new selection replaces the old one
+        let mut index = if runtime.exists(&index_path) {
+            MirrorIndex::load(runtime.as_ref(), &index_path)?
+        } else {
+            MirrorIndex {
+                name: spec.repo.to_string(),
+                ..Default::default()
+            }
+        };
+        index.source = provider.api_url().to_string();
+        index.constraint = spec.version.clone();
+        index.filters = options.filters.clone();
+        index.pre = config.pre;
+
+        println!("   mirroring {}", spec.repo);
+        mirror_package(
+            runtime.as_ref(),
+            &services.downloader,
+            provider.as_ref(),
+            &mirror,
+            &spec.repo,
+            &mut index,
+        )
+        .await
+        .with_context(|| format!("Failed to mirror {}", spec.repo))?;
+    }
+    Ok(())
+}
+
+/// Add new releases to the repositories of a mirror directory
+#[tracing::instrument(skip(runtime, config, repos))]
+pub async fn mirror_update<R: Runtime + 'static>(
+    runtime: R,
+    config: Config,
+    dir: PathBuf,
+    repos: Vec<String>,
+) -> Result<()> {
+    let runtime = Arc::new(runtime);
+    let mirror = absolute_dir(runtime.as_ref(), &dir)?;
+    let services = Services::from_config(Arc::clone(&runtime), &config)?;
+
+    let filter_repos = repos
+        .iter()
+        .map(|r| r.parse::<RepoId>())
+        .collect::<Result<Vec<_>>>()?;
+    let mirrored = mirrored_repos(runtime.as_ref(), &mirror)?;
+    if mirrored.is_empty() {
+        anyhow::bail!("No mirrored repositories in {}", mirror.display());
+    }
+
+    for repo in mirrored {
+        if !filter_repos.is_empty() && !filter_repos.contains(&repo) {
+            continue;
+        }
+        let index_path = MirrorIndex::path_for(&mirror, &repo);
+        let mut index = MirrorIndex::load(runtime.as_ref(), &index_path)?;
+        let kind = ProviderFactory::infer_provider_kind(&index.source);
+        let provider = services.provider_factory.create(kind, &index.source);
+
+        println!("   mirroring {}", repo);
+        if let Err(e) = mirror_package(
+            runtime.as_ref(),
+            &services.downloader,
+            provider.as_ref(),
+            &mirror,
+            &repo,
+            &mut index,
+        )
+        .await
+        {
+            warn!("Failed to update the mirror of {}: {:#}", repo, e);
+        }
+    }
+    Ok(())
+}
+
+/// Directory relative to the current directory
+fn absolute_dir<R: Runtime>(runtime: &R, dir: &Path) -> Result<PathBuf> {
+    if dir.is_relative() {
+        Ok(runtime.current_dir()?.join(dir))
+    } else {
+        Ok(dir.to_path_buf())
+    }
+}
+
+/// Repositories with an index in a mirror directory
+fn mirrored_repos<R: Runtime>(runtime: &R, mirror: &Path) -> Result<Vec<RepoId>> {
+    let mut repos = vec![];
+    if !runtime.is_dir(mirror) {
+        return Ok(repos);
+    }
+    for owner_dir in runtime.read_dir(mirror)? {
+        if !runtime.is_dir(&owner_dir) {
+            continue;
+        }
+        for repo_dir in runtime.read_dir(&owner_dir)? {
+            if !runtime.exists(&repo_dir.join(MIRROR_INDEX)) {
+                continue;
+            }
+            let (Some(owner), Some(repo)) = (owner_dir.file_name(), repo_dir.file_name()) else {
+                continue;
+            };
+            repos.push(RepoId {
+                owner: owner.to_string_lossy().into_owned(),
+                repo: repo.to_string_lossy().into_owned(),
+            });
+        }
+    }
+    repos.sort_by_key(|r| r.to_string());
+    Ok(repos)
+}
+
+/// Download the selected releases of a repository that are not mirrored yet
+///
+/// The index is saved after every release, so an interrupted run keeps the
+/// releases it completed. Returns the number of releases downloaded.
+async fn mirror_package<R: Runtime, D: Downloader>(
+    runtime: &R,
+    downloader: &D,
+    provider: &dyn Provider,
+    mirror: &Path,
+    repo: &RepoId,
+    index: &mut MirrorIndex,
+) -> Result<usize> {
+    let info = provider
+        .get_repo_metadata_at(repo, &index.source)
+        .await
+        .context("Failed to fetch repository metadata")?;
+    let releases = provider
+        .get_releases_at(repo, &index.source)
+        .await
+        .context("Failed to fetch releases")?;
+    index.description = info.description;
+    index.homepage = info.homepage;
+    index.license = info.license;
+    index.updated_at = info.updated_at;
+
+    let selected = index.select(&releases)?;
+    if selected.is_empty() {
+        anyhow::bail!(
+            "No release of {} matches {}",
+            repo,
+            index.constraint.as_deref().unwrap_or("latest")
+        );
+    }
+
+    let repo_dir = MirrorIndex::repo_dir(mirror, repo);
+    let index_path = MirrorIndex::path_for(mirror, repo);
+    runtime.create_dir_all(&repo_dir)?;
+
+    let mut downloaded = 0;
+    for release in selected {
+        let plan = match get_download_plan(release, &index.filters) {
+            Ok(plan) => plan,
+            Err(e) => {
+                warn!("Skipping {} {}: {}", repo, release.tag, e);
+                continue;
+            }
+        };
+        let Some(mirrored) =
+            mirror_release(runtime, downloader, &repo_dir, repo, release, plan, index)
+                .await
+                .with_context(|| format!("Failed to mirror {} {}", repo, release.tag))?
+        else {
+            continue;
+        };
+        println!(
+            "    mirrored {} {} ({} files)",
+            repo,
+            release.tag,
+            mirrored.files.len()
+        );
+        index.insert(mirrored);
+        index.save(runtime, &index_path)?;
+        downloaded += 1;
+    }
+
+    index.save(runtime, &index_path)?;
+    if downloaded == 0 {
+        println!("  up to date {}", repo);
+    }
+    Ok(downloaded)
+}
+
+/// Download the planned files of a release that are missing from the mirror
+///
+/// Returns None when every file is already mirrored.
+async fn mirror_release<R: Runtime, D: Downloader>(
+    runtime: &R,
+    downloader: &D,
+    repo_dir: &Path,
+    repo: &RepoId,
+    release: &Release,
+    plan: DownloadPlan,
+    index: &MirrorIndex,
+) -> Result<Option<MirroredRelease>> {
+    check_file_name(&release.tag)?;
+    let tag_dir = repo_dir.join(&release.tag);
+    let previous = index.get(&release.tag);
+    let mirrored_file = |name: &str| {
+        previous
+            .and_then(|r| r.files.iter().find(|f| f.name == name))
+            .filter(|_| runtime.exists(&tag_dir.join(name)))
+            .cloned()
+    };
+
+    let mut files = vec![];
+    let mut changed = previous.is_none();
+    match plan {
+        DownloadPlan::Tarball { url } => {
+            let name = format!("{}-{}.tar.gz", repo.repo, release.tag.replace('/', "-"));
+            let file = match mirrored_file(&name) {
+                Some(file) => file,
+                None => {
+                    changed = true;
+                    runtime.create_dir_all(&tag_dir)?;
+                    let part = tag_dir.join(format!("{}.part", name));
+                    let result = downloader.download(runtime, &url, &part).await;
+                    store_download(runtime, result, &part, &tag_dir, &name, 0)?
+                }
+            };
+            files.push(file);
+        }
+        DownloadPlan::Assets { assets } => {
+            for asset in assets {
+                check_file_name(&asset.name)?;
+                let file = match mirrored_file(&asset.name) {
+                    Some(file) => file,
+                    None => {
+                        changed = true;
+                        runtime.create_dir_all(&tag_dir)?;
+                        let part = tag_dir.join(format!("{}.part", asset.name));
+                        let result = downloader.download_asset(runtime, &asset, &part).await;
+                        store_download(runtime, result, &part, &tag_dir, &asset.name, asset.size)?
+                    }
+                };
+                files.push(file);
+            }
+        }
+    }
+    if !changed && previous.is_some_and(|r| r.files == files) {
+        return Ok(None);
+    }
+
+    let mirrored = MirroredRelease {
+        tag: release.tag.clone(),
+        name: release.name.clone(),
+        published_at: release.published_at.clone(),
+        prerelease: release.prerelease,
+        files,
+    };
+    runtime.write(
+        &tag_dir.join(MIRROR_CHECKSUMS),
+        mirrored.checksums().as_bytes(),
+    )?;
+    Ok(Some(mirrored))
+}
+
+/// Hash a finished download and move it into place
+fn store_download<R: Runtime>(
+    runtime: &R,
+    result: Result<()>,
+    part: &Path,
+    tag_dir: &Path,
+    name: &str,
+    size: u64,
+) -> Result<MirroredFile> {
+    let stored = result
+        .and_then(|()| sha256_file(runtime, part))
+        .and_then(|sha256| {
+            runtime.rename(part, &tag_dir.join(name))?;
+            Ok(sha256)
+        });
+    match stored {
+        Ok(sha256) => Ok(MirroredFile {
+            name: name.to_string(),
+            size,
+            sha256,
+        }),
+        Err(e) => {
+            let _ = runtime.remove_file(part);
+            Err(e)
+        }
+    }
+}
+
+/// Reject tags and asset names that would leave their directory
+fn check_file_name(name: &str) -> Result<()> {
+    let path = Path::new(name);
+    if name.is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        anyhow::bail!("Refusing to mirror '{}': not a plain file name", name);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::download::mock::MockDownloader;
+    use crate::provider::{MockProvider, ReleaseAsset, RepoMetadata};
+    use crate::runtime::MockRuntime;
+
+    // SHA-256 of "data"
+    const DATA_SHA256: &str = "3a6eb0790f39ac87c94f3856b2dd2c5d110e6811602261a9a923d3bb23adc8b7";
+
+    fn release(tag: &str, assets: &[&str]) -> Release {
+        Release {
+            tag: tag.into(),
+            tarball_url: format!("https://example.com/{}.tar.gz", tag),
+            assets: assets
+                .iter()
+                .map(|name| ReleaseAsset {
+                    name: name.to_string(),
+                    size: 4,
+                    download_url: format!("https://example.com/{}/{}", tag, name),
+                    api_url: None,
+                    local: false,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn provider(releases: Vec<Release>) -> MockProvider {
+        let mut provider = MockProvider::new();
+        provider.expect_get_repo_metadata_at().returning(|_, _| {
+            Ok(RepoMetadata {
+                description: Some("A tool".into()),
+                ..Default::default()
+            })
+        });
+        provider
+            .expect_get_releases_at()
+            .returning(move |_, _| Ok(releases.clone()));
+        provider
+    }
+
+    #[tokio::test]
+    async fn test_mirror_package_downloads_filtered_assets() {
+        // --- Setup ---
+        let mut runtime = MockRuntime::new();
+        runtime.expect_create_dir_all().returning(|_| Ok(()));
+        runtime.expect_exists().returning(|_| false);
+        runtime
+            .expect_open()
+            .returning(|_| Ok(Box::new(std::io::Cursor::new(b"data".to_vec()))));
+        runtime
+            .expect_rename()
+            .withf(|from, to| {
+                from == Path::new("/mirror/owner/tool/v1.1.0/tool-linux.tar.gz.part")
+                    && to == Path::new("/mirror/owner/tool/v1.1.0/tool-linux.tar.gz")
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+        runtime
+            .expect_write()
+            .withf(|path, _| path == Path::new("/mirror/owner/tool/v1.1.0/SHA256SUMS"))
+            .times(1)
+            .returning(|_, _| Ok(()));
+        runtime
+            .expect_write()
+            .withf(|path, _| path == Path::new("/mirror/owner/tool/mirror.json"))
+            .returning(|_, _| Ok(()));
+        let provider = provider(vec![
+            release("v1.1.0", &["tool-linux.tar.gz", "tool-macos.tar.gz"]),
+            release("v1.0.0", &["tool-linux.tar.gz"]),
+        ]);
+        let mut index = MirrorIndex {
+            name: "owner/tool".into(),
+            filters: vec!["*linux*".into()],
+            ..Default::default()
+        };
+
+        // --- Execute ---
+        let downloaded = mirror_package(
+            &runtime,
+            &MockDownloader::new(),
+            &provider,
+            Path::new("/mirror"),
+            &"owner/tool".parse().unwrap(),
+            &mut index,
+        )
+        .await
+        .unwrap();
+
+        // --- Verify ---
+        assert_eq!(downloaded, 1);
+        assert_eq!(index.description.as_deref(), Some("A tool"));
+        assert_eq!(
+            index.releases,
+            vec![MirroredRelease {
+                tag: "v1.1.0".into(),
+                files: vec![MirroredFile {
+                    name: "tool-linux.tar.gz".into(),
+                    size: 4,
+                    sha256: DATA_SHA256.into(),
+                }],
+                ..Default::default()
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mirror_package_skips_mirrored_release() {
+        // --- Setup ---
+        let mut runtime = MockRuntime::new();
+        runtime.expect_create_dir_all().returning(|_| Ok(()));
+        // The file is on disk, so nothing is downloaded
+        runtime.expect_exists().returning(|_| true);
+        runtime.expect_write().times(1).returning(|_, _| Ok(()));
+        let provider = provider(vec![release("v1.0.0", &["tool.tar.gz"])]);
+        let file = MirroredFile {
+            name: "tool.tar.gz".into(),
+            size: 4,
+            sha256: "cafe".into(),
+        };
+        let mut index = MirrorIndex {
+            name: "owner/tool".into(),
+            releases: vec![MirroredRelease {
+                tag: "v1.0.0".into(),
+                files: vec![file.clone()],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        // --- Execute ---
+        let downloaded = mirror_package(
+            &runtime,
+            &MockDownloader::new(),
+            &provider,
+            Path::new("/mirror"),
+            &"owner/tool".parse().unwrap(),
+            &mut index,
+        )
+        .await
+        .unwrap();
+
+        // --- Verify ---
+        assert_eq!(downloaded, 0);
+        assert_eq!(index.releases[0].files, vec![file]);
+    }
+
+    #[tokio::test]
+    async fn test_mirror_package_download_failure_removes_part_file() {
+        let mut runtime = MockRuntime::new();
+        runtime.expect_create_dir_all().returning(|_| Ok(()));
+        runtime.expect_exists().returning(|_| false);
+        runtime
+            .expect_remove_file()
+            .withf(|path| path == Path::new("/mirror/owner/tool/v1.0.0/tool-v1.0.0.tar.gz.part"))
+            .times(1)
+            .returning(|_| Ok(()));
+        let downloader = MockDownloader::new();
+        downloader.set_should_fail(true);
+        let mut index = MirrorIndex::default();
+
+        let err = mirror_package(
+            &runtime,
+            &downloader,
+            &provider(vec![release("v1.0.0", &[])]),
+            Path::new("/mirror"),
+            &"owner/tool".parse().unwrap(),
+            &mut index,
+        )
+        .await
+        .unwrap_err();
+        assert!(format!("{:#}", err).contains("Mock download failed"));
+    }
+
+    #[test]
+    fn test_check_file_name() {
+        assert!(check_file_name("tool-linux.tar.gz").is_ok());
+        assert!(check_file_name("cli/v1.0.0").is_ok());
+        for name in ["", "..", "../etc", "/abs", "a/../b"] {
+            assert!(check_file_name(name).is_err(), "{}", name);
+        }
+    }
+}
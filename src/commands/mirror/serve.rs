@@ -0,0 +1,308 @@
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+use crate::domain::model::{MirrorIndex, Release, VersionResolver};
+use crate::download::local_file_path;
+use crate::http::server::{self, Request, Response};
+use crate::provider::{FileProvider, Provider, RepoId};
+use crate::runtime::Runtime;
+
+use super::absolute_dir;
+
+/// Releases per page when the client asks for none, as on GitHub
+const DEFAULT_PER_PAGE: usize = 30;
+const MAX_PER_PAGE: usize = 100;
+
+/// Serve a mirror directory over a GitHub-compatible API until interrupted
+#[tracing::instrument(skip(runtime))]
+pub async fn mirror_serve<R: Runtime + 'static>(
+    runtime: R,
+    dir: PathBuf,
+    listen: String,
+) -> Result<()> {
+    let mirror = absolute_dir(&runtime, &dir)?;
+    if !runtime.is_dir(&mirror) {
+        anyhow::bail!("Mirror directory {} does not exist", mirror.display());
+    }
+    let listener = TcpListener::bind(&listen)
+        .await
+        .with_context(|| format!("Failed to listen on {}", listen))?;
+    let addr = listener.local_addr()?;
+    let server = Arc::new(MirrorServer::new(Arc::new(runtime), mirror.clone(), addr)?);
+
+    println!("     serving {} on http://{}", mirror.display(), addr);
+    let serve = server::serve(listener, move |request| {
+        let server = Arc::clone(&server);
+        async move { server.handle(request).await }
+    });
+    tokio::select! {
+        result = serve => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+
+/// Answers API requests from the releases in a mirror directory
+struct MirrorServer {
+    mirror: PathBuf,
+    provider: FileProvider,
+    /// Bound address, for clients that send no `Host` header
+    addr: SocketAddr,
+}
+
+impl MirrorServer {
+    fn new(runtime: Arc<dyn Runtime>, mirror: PathBuf, addr: SocketAddr) -> Result<Self> {
+        let root = reqwest::Url::from_directory_path(&mirror)
+            .map_err(|_| anyhow::anyhow!("Cannot build a file URL for {:?}", mirror))?;
+        Ok(Self {
+            mirror,
+            provider: FileProvider::new(runtime, root.as_str()),
+            addr,
+        })
+    }
+
+    async fn handle(&self, request: Request) -> Response {
+        let segments: Vec<&str> = request.segments.iter().map(String::as_str).collect();
+        let result = match segments.as_slice() {
+            ["repos", owner, repo] => self.repo_info(owner, repo).await,
+            ["repos", owner, repo, "releases"] => self.releases(&request, owner, repo).await,
+            [owner, repo, "releases", "download", tag, name] => {
+                self.download(owner, repo, tag, name).await
+            }
+            _ => Ok(None),
+        };
+        match result {
+            Ok(Some(response)) => response,
+            Ok(None) => Response::error(404, "Not Found"),
+            Err(e) => Response::error(500, &format!("{:#}", e)),
+        }
+    }
+
+    /// Mirrored repository, or None when it is not in the mirror
+    fn repo(&self, owner: &str, repo: &str) -> Option<RepoId> {
+        let repo: RepoId = format!("{}/{}", owner, repo).parse().ok()?;
+        MirrorIndex::repo_dir(&self.mirror, &repo)
+            .is_dir()
+            .then_some(repo)
+    }
+
+    async fn repo_info(&self, owner: &str, repo: &str) -> Result<Option<Response>> {
+        let Some(repo) = self.repo(owner, repo) else {
+            return Ok(None);
+        };
+        let info = self.provider.get_repo_metadata(&repo).await?;
+        Ok(Some(Response::json(
+            200,
+            &json!({
+                "full_name": repo.to_string(),
+                "description": info.description,
+                "homepage": info.homepage,
+                "license": info.license.map(|name| json!({ "name": name })),
+                "updated_at": info.updated_at.unwrap_or_else(|| "1970-01-01T00:00:00Z".into()),
+            }),
+        )))
+    }
+
+    /// A page of releases, newest first
+    async fn releases(
+        &self,
+        request: &Request,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Option<Response>> {
+        let Some(repo) = self.repo(owner, repo) else {
+            return Ok(None);
+        };
+        let per_page = request
+            .query_param("per_page")
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE);
+        let page = request
+            .query_param("page")
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(1)
+            .max(1);
+
+        let mut releases = self.provider.get_releases(&repo).await?;
+        releases.sort_by(|a, b| VersionResolver::compare_releases(b, a));
+        let base = self.base_url(request)?;
+        let page: Vec<Value> = releases
+            .iter()
+            .skip((page - 1) * per_page)
+            .take(per_page)
+            .map(|release| release_json(&base, &repo, release))
+            .collect();
+        Ok(Some(Response::json(200, &Value::Array(page))))
+    }
+
+    /// Asset file of a mirrored release
+    async fn download(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        name: &str,
+    ) -> Result<Option<Response>> {
+        let Some(repo) = self.repo(owner, repo) else {
+            return Ok(None);
+        };
+        let releases = self.provider.get_releases(&repo).await?;
+        let asset = releases
+            .iter()
+            .find(|r| r.tag == tag)
+            .and_then(|r| r.assets.iter().find(|a| a.name == name));
+        let Some(asset) = asset else {
+            return Ok(None);
+        };
+        Ok(local_file_path(&asset.download_url)?.map(Response::file))
+    }
+
+    /// URL clients reach the server at
+    fn base_url(&self, request: &Request) -> Result<reqwest::Url> {
+        let host = request
+            .host
+            .clone()
+            .unwrap_or_else(|| self.addr.to_string());
+        reqwest::Url::parse(&format!("http://{}/", host))
+            .with_context(|| format!("Invalid Host header '{}'", host))
+    }
+}
+
+/// A release in the shape of the GitHub API
+fn release_json(base: &reqwest::Url, repo: &RepoId, release: &Release) -> Value {
+    let assets: Vec<Value> = release
+        .assets
+        .iter()
+        .map(|asset| {
+            let mut url = base.clone();
+            if let Ok(mut segments) = url.path_segments_mut() {
+                segments.pop_if_empty().extend([
+                    repo.owner.as_str(),
+                    repo.repo.as_str(),
+                    "releases",
+                    "download",
+                    release.tag.as_str(),
+                    asset.name.as_str(),
+                ]);
+            }
+            json!({
+                "name": asset.name,
+                "size": asset.size,
+                "browser_download_url": url.to_string(),
+            })
+        })
+        .collect();
+    json!({
+        "tag_name": release.tag,
+        "name": release.name,
+        "tarball_url": "",
+        "published_at": release.published_at,
+        "prerelease": release.prerelease,
+        "assets": assets,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::server::Body;
+    use crate::runtime::RealRuntime;
+    use tempfile::tempdir;
+
+    fn get(target: &str) -> Request {
+        Request::parse(&format!(
+            "GET {} HTTP/1.1\r\nHost: mirror.local:8080\r\n\r\n",
+            target
+        ))
+        .unwrap()
+    }
+
+    fn json_body(response: &Response) -> Value {
+        match &response.body {
+            Body::Bytes(bytes) => serde_json::from_slice(bytes).unwrap(),
+            Body::File(path) => panic!("Expected JSON, got file {:?}", path),
+        }
+    }
+
+    fn server(mirror: &std::path::Path) -> MirrorServer {
+        MirrorServer::new(
+            Arc::new(RealRuntime),
+            mirror.to_path_buf(),
+            "127.0.0.1:8080".parse().unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_serve_repo_and_releases() {
+        // --- Setup ---
+        let mirror = tempdir().unwrap();
+        let repo_dir = mirror.path().join("owner/tool");
+        for tag in ["v1.0.0", "nightly", "v1.10.0", "v1.2.0"] {
+            std::fs::create_dir_all(repo_dir.join(tag)).unwrap();
+            std::fs::write(repo_dir.join(tag).join("tool.tar.gz"), "data").unwrap();
+        }
+        let server = server(mirror.path());
+
+        // --- Execute ---
+        let info = server.handle(get("/repos/owner/tool")).await;
+        let first = server
+            .handle(get("/repos/owner/tool/releases?per_page=2&page=1"))
+            .await;
+        let second = server
+            .handle(get("/repos/owner/tool/releases?per_page=2&page=2"))
+            .await;
+        let past_end = server
+            .handle(get("/repos/owner/tool/releases?per_page=2&page=3"))
+            .await;
+
+        // --- Verify ---
+        assert_eq!(info.status, 200);
+        assert_eq!(json_body(&info)["updated_at"], "1970-01-01T00:00:00Z");
+        assert_eq!(json_body(&info)["license"], Value::Null);
+
+        let first = json_body(&first);
+        assert_eq!(first[0]["tag_name"], "v1.10.0");
+        assert_eq!(first[1]["tag_name"], "v1.2.0");
+        assert_eq!(
+            first[0]["assets"][0]["browser_download_url"],
+            "http://mirror.local:8080/owner/tool/releases/download/v1.10.0/tool.tar.gz"
+        );
+        // Tags that are not versions come after the versions
+        assert_eq!(json_body(&second)[0]["tag_name"], "v1.0.0");
+        assert_eq!(json_body(&second)[1]["tag_name"], "nightly");
+        assert_eq!(json_body(&past_end), json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_serve_downloads_only_release_assets() {
+        // --- Setup ---
+        let mirror = tempdir().unwrap();
+        let release_dir = mirror.path().join("owner/tool/v1.0.0");
+        std::fs::create_dir_all(&release_dir).unwrap();
+        std::fs::write(release_dir.join("tool.tar.gz"), "data").unwrap();
+        let server = server(mirror.path());
+
+        // --- Execute ---
+        let asset = server
+            .handle(get("/owner/tool/releases/download/v1.0.0/tool.tar.gz"))
+            .await;
+        let escape = server
+            .handle(get(
+                "/owner/tool/releases/download/v1.0.0/..%2F..%2Fmirror.json",
+            ))
+            .await;
+        let missing_repo = server.handle(get("/repos/owner/missing")).await;
+        let unknown = server.handle(get("/unknown")).await;
+
+        // --- Verify ---
+        assert_eq!(asset.body, Body::File(release_dir.join("tool.tar.gz")));
+        assert_eq!(escape.status, 404);
+        assert_eq!(missing_repo.status, 404);
+        assert_eq!(json_body(&unknown), json!({ "message": "Not Found" }));
+    }
+}
@@ -1,28 +1,48 @@
 pub mod config;
+mod credentials;
+mod export;
+mod generations;
 mod install;
 mod link;
 mod link_spec;
 mod links;
 mod list;
+mod lock;
+mod mirror;
+mod pin;
 mod prune;
 mod remove;
+mod rollback;
 pub mod services;
 mod show;
+mod sync;
 mod unlink;
 mod update;
 mod upgrade;
+mod use_version;
 
-pub use config::{Config, InstallOptions, UpgradeOptions};
+pub use config::{
+    Config, ConfigFile, ExportOptions, InstallOptions, MirrorOptions, SyncOptions, UpgradeOptions,
+    config_get, config_list, config_set, config_unset,
+};
+pub use export::export;
+pub use generations::{generations_delete, generations_list, generations_switch};
 pub use install::install;
 pub use link::link;
 pub use link_spec::LinkSpec;
 pub use links::links;
-pub(crate) use links::{print_links, print_versioned_links};
+pub(crate) use links::{print_executable_links, print_links, print_versioned_links};
 pub use list::list;
+pub use lock::lock;
+pub use mirror::{mirror_create, mirror_serve, mirror_update};
+pub use pin::{pin, policy, unpin};
 pub use prune::prune;
 pub use remove::remove;
+pub use rollback::rollback;
 pub use services::Services;
 pub use show::show;
+pub use sync::sync;
 pub use unlink::unlink;
 pub use update::update;
 pub use upgrade::upgrade;
+pub use use_version::use_version;
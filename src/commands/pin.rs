@@ -0,0 +1,66 @@
+use anyhow::Result;
+use log::debug;
+
+use crate::application::PinAction;
+use crate::domain::model::UpgradePolicy;
+use crate::provider::{PackageSpec, RepoId};
+use crate::runtime::Runtime;
+
+use super::config::Config;
+
+/// Pin a package so upgrades and installs without a version leave it alone
+#[tracing::instrument(skip(runtime, config))]
+pub fn pin<R: Runtime>(runtime: R, repo_str: &str, config: Config) -> Result<()> {
+    debug!("Using install root: {:?}", config.install_root);
+    let spec = repo_str.parse::<PackageSpec>()?;
+
+    let action = PinAction::new(&runtime, config.install_root);
+    let tag = action.pin(&spec.repo, spec.version.as_deref())?;
+    println!("   pinned {} at {}", spec.repo, tag);
+    Ok(())
+}
+
+/// Remove the pin from a package
+#[tracing::instrument(skip(runtime, config))]
+pub fn unpin<R: Runtime>(runtime: R, repo_str: &str, config: Config) -> Result<()> {
+    debug!("Using install root: {:?}", config.install_root);
+    let repo = repo_str.parse::<RepoId>()?;
+
+    let action = PinAction::new(&runtime, config.install_root);
+    match action.unpin(&repo)? {
+        Some(tag) => println!("   unpinned {} (was {})", repo, tag),
+        None => println!("   {} is not pinned", repo),
+    }
+    Ok(())
+}
+
+/// Show the default upgrade policy of a package, or set it when `policy` is given
+///
+/// `Some(None)` removes the saved policy.
+#[tracing::instrument(skip(runtime, config))]
+pub fn policy<R: Runtime>(
+    runtime: R,
+    repo_str: &str,
+    policy: Option<Option<UpgradePolicy>>,
+    config: Config,
+) -> Result<()> {
+    debug!("Using install root: {:?}", config.install_root);
+    let repo = repo_str.parse::<RepoId>()?;
+
+    let action = PinAction::new(&runtime, config.install_root);
+    match policy {
+        None => match action.policy(&repo)? {
+            Some(policy) => println!("{} {}", repo, policy),
+            None => println!("{} (no policy)", repo),
+        },
+        Some(Some(policy)) => {
+            action.set_policy(&repo, Some(policy))?;
+            println!("   {} upgrades limited to {} releases", repo, policy);
+        }
+        Some(None) => {
+            action.set_policy(&repo, None)?;
+            println!("   {} upgrade policy removed", repo);
+        }
+    }
+    Ok(())
+}
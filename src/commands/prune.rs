@@ -2,7 +2,7 @@ use anyhow::Result;
 use log::{debug, info};
 use std::path::Path;
 
-use crate::application::{PruneAction, RemoveAction};
+use crate::application::{GenerationAction, PruneAction, RemoveAction};
 use crate::provider::PackageSpec;
 use crate::runtime::Runtime;
 
@@ -90,6 +90,14 @@ fn do_prune<R: Runtime>(
         return Ok(0);
     };
 
+    if !info.retained.is_empty() {
+        println!(
+            "{}: keeping {} for generations (see `ghri generations list`)",
+            info.name,
+            info.retained.join(", ")
+        );
+    }
+
     if info.versions_to_prune.is_empty() {
         debug!("No versions to prune for {}", info.name);
         return Ok(0);
@@ -130,6 +138,9 @@ fn do_prune<R: Runtime>(
 }
 
 /// Prune old versions from a package directory (no confirmation, for use after install/upgrade)
+///
+/// An explicit `--prune` also removes versions that generations still
+/// reference, and deletes those generations.
 pub fn prune_package_dir<R: Runtime>(
     runtime: &R,
     install_root: &Path,
@@ -149,7 +160,13 @@ pub fn prune_package_dir<R: Runtime>(
         return Ok(()); // No current version, nothing to prune
     };
 
-    if info.versions_to_prune.is_empty() {
+    let versions: Vec<String> = info
+        .versions_to_prune
+        .iter()
+        .chain(&info.retained)
+        .cloned()
+        .collect();
+    if versions.is_empty() {
         return Ok(()); // Nothing to prune
     }
 
@@ -157,12 +174,8 @@ pub fn prune_package_dir<R: Runtime>(
     let meta = prune_action.package_repo().load(owner, repo)?;
 
     // Remove each version
-    println!(
-        "Pruning {} old version(s) from {}...",
-        info.versions_to_prune.len(),
-        name
-    );
-    for version in &info.versions_to_prune {
+    println!("Pruning {} old version(s) from {}...", versions.len(), name);
+    for version in &versions {
         if version == current_version {
             continue; // Safety check
         }
@@ -173,7 +186,19 @@ pub fn prune_package_dir<R: Runtime>(
     }
 
     // Update meta.json to remove pruned versioned_links
-    remove_action.update_meta_after_prune(owner, repo, &info.versions_to_prune)?;
+    remove_action.update_meta_after_prune(owner, repo, &versions)?;
+
+    // Generations that used the pruned versions can no longer be restored
+    if !info.retained.is_empty() {
+        let forgotten = GenerationAction::new(runtime, install_root.to_path_buf())
+            .forget_versions(name, &info.retained)?;
+        let ids: Vec<String> = forgotten.iter().map(u32::to_string).collect();
+        println!(
+            "Deleted generation(s) {} that used {}",
+            ids.join(", "),
+            info.retained.join(", ")
+        );
+    }
 
     Ok(())
 }
@@ -191,6 +216,11 @@ mod tests {
     fn test_find_prunable_identifies_old_versions() {
         let mut runtime = MockRuntime::new();
         let root = PathBuf::from("/root");
+        // No generations recorded
+        runtime
+            .expect_exists()
+            .with(eq(root.join("generations.json")))
+            .returning(|_| false);
         let current_link = PathBuf::from("/root/owner/repo/current");
         let package_dir = PathBuf::from("/root/owner/repo");
 
@@ -275,6 +305,11 @@ mod tests {
     fn test_find_prunable_only_current_version() {
         let mut runtime = MockRuntime::new();
         let root = PathBuf::from("/root");
+        // No generations recorded
+        runtime
+            .expect_exists()
+            .with(eq(root.join("generations.json")))
+            .returning(|_| false);
         let current_link = PathBuf::from("/root/owner/repo/current");
         let package_dir = PathBuf::from("/root/owner/repo");
 
@@ -320,6 +355,11 @@ mod tests {
     fn test_find_prunable_multiple_old_versions() {
         let mut runtime = MockRuntime::new();
         let root = PathBuf::from("/root");
+        // No generations recorded
+        runtime
+            .expect_exists()
+            .with(eq(root.join("generations.json")))
+            .returning(|_| false);
         let current_link = PathBuf::from("/root/owner/repo/current");
         let package_dir = PathBuf::from("/root/owner/repo");
 
@@ -426,6 +466,11 @@ mod tests {
 
         let mut runtime = MockRuntime::new();
         let root = PathBuf::from("/root");
+        // No generations recorded
+        runtime
+            .expect_exists()
+            .with(eq(root.join("generations.json")))
+            .returning(|_| false);
         let package_dir = PathBuf::from("/root/owner/repo");
         let current_link = package_dir.join("current");
 
@@ -7,6 +7,7 @@ use crate::provider::PackageSpec;
 use crate::runtime::Runtime;
 
 use super::config::Config;
+use super::generations::record_generation;
 
 /// Remove a package or specific version
 #[tracing::instrument(skip(runtime, config))]
@@ -77,6 +78,11 @@ pub fn remove<R: Runtime>(
         println!("Removed package {}", ctx.display_name);
     }
 
+    record_generation(
+        &runtime,
+        &config.install_root,
+        &format!("remove {}", repo_str),
+    );
     Ok(())
 }
 
@@ -187,6 +193,8 @@ mod tests {
             links: vec![LinkRule {
                 dest: link_dest.clone(),
                 path: None,
+                shim: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -237,6 +245,12 @@ mod tests {
             .with(eq(owner_dir.clone()))
             .returning(|_| Ok(()));
 
+        // Nothing left to record as a generation
+        runtime
+            .expect_exists()
+            .with(eq(root.clone()))
+            .returning(|_| false);
+
         let result = remove(runtime, "owner/repo", false, true, Config::for_test(root));
         assert!(result.is_ok());
     }
@@ -266,6 +280,8 @@ mod tests {
             links: vec![LinkRule {
                 dest: link_dest.clone(),
                 path: None,
+                shim: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -317,6 +333,12 @@ mod tests {
             .with(eq(owner_dir.clone()))
             .returning(|_| Ok(vec![PathBuf::from("repo")]));
 
+        // Nothing left to record as a generation
+        runtime
+            .expect_exists()
+            .with(eq(root.clone()))
+            .returning(|_| false);
+
         let result = remove(
             runtime,
             "owner/repo@v1",
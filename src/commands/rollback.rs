@@ -0,0 +1,28 @@
+use anyhow::Result;
+use log::debug;
+
+use crate::application::SwitchAction;
+use crate::provider::RepoId;
+use crate::runtime::Runtime;
+
+use super::config::Config;
+use super::generations::record_generation;
+use super::print_executable_links;
+
+/// Switch a package back to its previously active version
+#[tracing::instrument(skip(runtime, config))]
+pub fn rollback<R: Runtime>(runtime: R, repo_str: &str, config: Config) -> Result<()> {
+    debug!("Using install root: {:?}", config.install_root);
+    let repo = repo_str.parse::<RepoId>()?;
+
+    let action = SwitchAction::new(&runtime, config.install_root.clone());
+    let result = action.rollback(&repo)?;
+    println!("   rolled back {} {} -> {}", repo, result.from, result.to);
+    print_executable_links(&result.executables);
+    record_generation(
+        &runtime,
+        &config.install_root,
+        &format!("rollback {}", repo),
+    );
+    Ok(())
+}
@@ -5,48 +5,47 @@
 //! configuration values but are not part of the configuration itself.
 
 use anyhow::Result;
-use log::debug;
-use reqwest::{
-    Client,
-    header::{AUTHORIZATION, HeaderMap, HeaderValue},
-};
+use reqwest::Client;
+use std::sync::Arc;
 
 use crate::{
-    archive::ArchiveExtractorImpl, download::HttpDownloader, http::HttpClient,
+    archive::ArchiveExtractorImpl,
+    download::HttpDownloader,
+    http::{Credentials, HttpClient, HttpSettings},
     provider::ProviderFactory,
+    runtime::Runtime,
 };
 
 use super::config::Config;
+use super::credentials::CredentialResolver;
 
-/// Build an HTTP client with optional authentication token
-pub fn build_http_client(token: Option<&str>) -> Result<HttpClient> {
-    let mut headers = HeaderMap::new();
-
-    if let Some(token) = token {
-        let mut auth_value = HeaderValue::from_str(&format!("Bearer {}", token))?;
-        auth_value.set_sensitive(true);
-        headers.insert(AUTHORIZATION, auth_value);
-        debug!("HTTP client configured with authentication");
-    }
+/// Build an HTTP client with per-host credentials and proxy/TLS settings
+pub fn build_http_client(credentials: Credentials, settings: &HttpSettings) -> Result<HttpClient> {
+    let builder = Client::builder().user_agent("ghri-cli");
+    let client = settings.apply(builder)?.build()?;
 
-    let client = Client::builder()
-        .user_agent("ghri-cli")
-        .default_headers(headers)
-        .build()?;
+    Ok(HttpClient::new(client).with_credentials(credentials))
+}
 
-    Ok(HttpClient::new(client))
+/// Build the credentials used for API hosts from configuration
+pub fn build_credentials<R: Runtime + 'static>(runtime: Arc<R>, config: &Config) -> Credentials {
+    Credentials::new(CredentialResolver::new(runtime, config))
 }
 
 /// Build a ProviderFactory from configuration
-pub fn build_provider_factory(config: &Config) -> Result<ProviderFactory> {
-    let http_client = build_http_client(config.token.as_deref())?;
-    Ok(ProviderFactory::new(http_client, &config.api_url))
+pub fn build_provider_factory<R: Runtime + 'static>(
+    runtime: Arc<R>,
+    config: &Config,
+    credentials: Credentials,
+) -> Result<ProviderFactory> {
+    let http_client = build_http_client(credentials, &config.http)?;
+    Ok(ProviderFactory::new(runtime, http_client, &config.api_url))
 }
 
 /// Build a downloader from configuration
-pub fn build_downloader(config: &Config) -> Result<HttpDownloader> {
-    let http_client = build_http_client(config.token.as_deref())?;
-    Ok(HttpDownloader::new(http_client))
+pub fn build_downloader(config: &Config, credentials: Credentials) -> Result<HttpDownloader> {
+    let http_client = build_http_client(credentials, &config.http)?;
+    Ok(HttpDownloader::new(http_client).with_rewrites(config.rewrites.clone()))
 }
 
 /// Build an archive extractor (stateless, no configuration needed)
@@ -62,11 +61,16 @@ pub struct Services {
 }
 
 impl Services {
-    /// Build services from configuration
-    pub fn from_config(config: &Config) -> Result<Self> {
+    /// Build services from configuration.
+    ///
+    /// The provider and the downloader share one HTTP client, so proxy, TLS and
+    /// credential settings apply to API requests and asset downloads alike.
+    pub fn from_config<R: Runtime + 'static>(runtime: Arc<R>, config: &Config) -> Result<Self> {
+        let credentials = build_credentials(Arc::clone(&runtime), config);
+        let http_client = build_http_client(credentials, &config.http)?;
         Ok(Self {
-            provider_factory: build_provider_factory(config)?,
-            downloader: build_downloader(config)?,
+            provider_factory: ProviderFactory::new(runtime, http_client.clone(), &config.api_url),
+            downloader: HttpDownloader::new(http_client).with_rewrites(config.rewrites.clone()),
             extractor: build_extractor(),
         })
     }
@@ -76,6 +80,7 @@ impl Services {
 mod tests {
     use super::*;
     use crate::provider::ProviderKind;
+    use crate::runtime::MockRuntime;
     use mockito::{Matcher, Server};
 
     #[tokio::test]
@@ -90,8 +95,9 @@ mod tests {
             )
             .create();
 
-        let http_client = build_http_client(Some("test_token")).unwrap();
-        let _ = http_client.inner().get(server.url()).send().await;
+        let credentials = Credentials::for_origin(&server.url(), "test_token");
+        let http_client = build_http_client(credentials, &HttpSettings::default()).unwrap();
+        let _ = http_client.get(&server.url()).send().await;
 
         mock.assert();
     }
@@ -105,22 +111,146 @@ mod tests {
             .match_header("Authorization", Matcher::Missing)
             .create();
 
-        let http_client = build_http_client(None).unwrap();
-        let _ = http_client.inner().get(server.url()).send().await;
+        let http_client =
+            build_http_client(Credentials::default(), &HttpSettings::default()).unwrap();
+        let _ = http_client.get(&server.url()).send().await;
 
         mock.assert();
     }
 
     #[test]
     fn test_build_provider_factory() {
-        let config = Config {
-            install_root: std::path::PathBuf::from("/test"),
-            api_url: "https://api.github.com".to_string(),
-            token: None,
-        };
+        let config = Config::for_test("/test");
 
-        let factory = build_provider_factory(&config).unwrap();
+        let factory = build_provider_factory(
+            Arc::new(MockRuntime::new()),
+            &config,
+            Credentials::default(),
+        )
+        .unwrap();
         let provider = factory.default_provider();
         assert_eq!(provider.kind(), ProviderKind::GitHub);
     }
+
+    #[tokio::test]
+    async fn test_build_http_client_with_proxy() {
+        // The proxy receives the request even though the target host is unroutable
+        let mut proxy = Server::new_async().await;
+        let mock = proxy
+            .mock("GET", Matcher::Any)
+            .match_header("User-Agent", "ghri-cli")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let settings = HttpSettings {
+            proxy: Some(proxy.url()),
+            ..Default::default()
+        };
+        let http_client = build_http_client(Credentials::default(), &settings).unwrap();
+        let _ = http_client.get("http://github.invalid/").send().await;
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_services_from_config_invalid_ca() {
+        let mut config = Config::for_test("/test");
+        config.http.ca_certs = vec![std::path::PathBuf::from("/nonexistent/ca.pem")];
+
+        assert!(Services::from_config(Arc::new(MockRuntime::new()), &config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_services_token_only_sent_to_api_host() {
+        let mut server = Server::new_async().await;
+        let api_mock = server
+            .mock("GET", "/api")
+            .match_header("Authorization", "Bearer gh_token")
+            .create_async()
+            .await;
+        let other_mock = server
+            .mock("GET", "/other")
+            .match_header("Authorization", Matcher::Missing)
+            .create_async()
+            .await;
+
+        // The API is configured as 127.0.0.1; the same server reached as
+        // localhost is a different host and must not receive the token.
+        let mut config = Config::for_test("/test");
+        config.api_url = server.url();
+        config.token = Some("gh_token".into());
+
+        let mut runtime = MockRuntime::new();
+        runtime
+            .expect_env_var()
+            .returning(|_| Err(std::env::VarError::NotPresent));
+        runtime.expect_home_dir().returning(|| None);
+        runtime.expect_exists().returning(|_| false);
+
+        let services = Services::from_config(Arc::new(runtime), &config).unwrap();
+        let http_client = services.downloader.http_client();
+
+        let _ = http_client
+            .get(&format!("{}/api", server.url()))
+            .send()
+            .await;
+        let other_url = server.url().replace("127.0.0.1", "localhost");
+        let _ = http_client
+            .get(&format!("{}/other", other_url))
+            .send()
+            .await;
+
+        api_mock.assert_async().await;
+        other_mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_services_token_not_sent_to_downloads_or_plain_http() {
+        // Plain HTTP requests go through the proxy, which records their headers
+        let mut proxy = Server::new_async().await;
+        let mock = proxy
+            .mock("GET", Matcher::Any)
+            .match_header("Authorization", Matcher::Missing)
+            .create_async()
+            .await;
+
+        let mut config = Config::for_test("/test");
+        config.api_url = "https://api.github.com".into();
+        config.token = Some("gh_token".into());
+        config.http.proxy = Some(proxy.url());
+
+        // A per-host token applies to the host on any scheme, so only the
+        // HTTPS check keeps it off the plain HTTP request
+        let mut runtime = MockRuntime::new();
+        runtime.expect_env_var().returning(|key| match key {
+            "GHRI_TOKEN_API_GITHUB_COM" => Ok("host_token".into()),
+            _ => Err(std::env::VarError::NotPresent),
+        });
+        runtime.expect_home_dir().returning(|| None);
+        runtime.expect_exists().returning(|_| false);
+        let runtime = Arc::new(runtime);
+
+        let credentials = build_credentials(Arc::clone(&runtime), &config);
+        assert_eq!(
+            credentials
+                .token_for_url("https://api.github.com/repos/o/r")
+                .as_deref(),
+            Some("host_token")
+        );
+        assert_eq!(
+            credentials.token_for_url("https://github.com/o/r/releases/download/v1/t.tar.gz"),
+            None
+        );
+
+        let services = Services::from_config(runtime, &config).unwrap();
+        let _ = services
+            .downloader
+            .http_client()
+            .get("http://api.github.com/repos/o/r")
+            .send()
+            .await;
+
+        mock.assert_async().await;
+    }
 }
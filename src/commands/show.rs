@@ -2,6 +2,7 @@ use anyhow::Result;
 use log::debug;
 
 use crate::application::ShowAction;
+use crate::domain::model::TagPattern;
 use crate::provider::PackageSpec;
 use crate::runtime::Runtime;
 
@@ -20,20 +21,44 @@ pub fn show<R: Runtime>(runtime: R, repo_str: &str, config: Config) -> Result<()
 
     // Package name
     println!("Package: {}", details.name);
+    if let Some(ref source) = details.source_repo {
+        println!("Alias of: {}", source);
+    }
     println!("Directory: {}", details.package_dir.display());
 
+    // Versions are shown without the channel prefix of the tag pattern
+    let tag_pattern = details
+        .tag_pattern
+        .as_deref()
+        .and_then(|p| TagPattern::parse(p).ok());
+    let display = |tag: &'_ str| -> String {
+        match &tag_pattern {
+            Some(pattern) => pattern.strip(tag).to_string(),
+            None => tag.to_string(),
+        }
+    };
+
     // Current version
     if let Some(ref version) = details.current_version {
-        println!("Current version: {}", version);
+        println!("Current version: {}", display(version));
+    }
+    if let Some(ref pattern) = details.tag_pattern {
+        println!("Tag pattern: {}", pattern);
+    }
+    if let Some(ref pinned) = details.pinned {
+        println!("Pinned: {}", display(pinned));
+    }
+    if let Some(policy) = details.upgrade_policy {
+        println!("Upgrade policy: {}", policy);
     }
 
     // List installed versions
     println!("\nInstalled versions:");
     for version in &details.installed_versions {
         if Some(version) == details.current_version.as_ref() {
-            println!("  {} (current)", version);
+            println!("  {} (current)", display(version));
         } else {
-            println!("  {}", version);
+            println!("  {}", display(version));
         }
     }
 
@@ -71,9 +96,9 @@ pub fn show<R: Runtime>(runtime: R, repo_str: &str, config: Config) -> Result<()
             }
             let installed = details.installed_versions.contains(&release.tag);
             if installed {
-                println!("  {} (installed)", release.tag);
+                println!("  {} (installed)", display(&release.tag));
             } else {
-                println!("  {}", release.tag);
+                println!("  {}", display(&release.tag));
             }
         }
     }
@@ -159,6 +184,8 @@ mod tests {
             links: vec![LinkRule {
                 dest: link_dest.clone(),
                 path: None,
+                shim: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -606,6 +633,8 @@ mod tests {
                 version: "v1.0.0".into(),
                 dest: link_dest.clone(),
                 path: None,
+                shim: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -725,6 +754,8 @@ mod tests {
             links: vec![LinkRule {
                 dest: link_dest.clone(),
                 path: Some("bin/tool".into()),
+                shim: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
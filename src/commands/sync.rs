@@ -0,0 +1,1025 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::application::{
+    InstallAction, InstallOperations, LinkAction, RemoveAction, UpdateAction,
+};
+use crate::cleanup::CleanupContext;
+use crate::domain::model::{
+    LinkRule, LockedPlatform, Lockfile, Manifest, Meta, VersionResolver, VersionedLink,
+};
+use crate::domain::service::PackageRepository;
+use crate::domain::service::release_installer::{DownloadPlan, ReleaseInstaller};
+use crate::download::VerifyingDownloader;
+use crate::platform::Platform;
+use crate::provider::{Release, RepoId};
+use crate::runtime::Runtime;
+
+use super::config::{Config, InstallOptions, SyncOptions};
+use super::generations::record_generation;
+use super::install::{
+    DefaultReleaseInstaller, fetch_url_package_meta, get_download_plan, get_or_fetch_package_meta,
+    run_install, ui,
+};
+use super::services::Services;
+
+/// Change needed to bring an installed package in line with the manifest
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncChange {
+    /// Not installed yet
+    Install,
+    /// Installed at an older version
+    Upgrade { from: String },
+    /// Installed at a newer version
+    Downgrade { from: String },
+    /// Already at the wanted version
+    Unchanged,
+}
+
+/// Planned work for one manifest package
+#[derive(Debug)]
+pub struct PlannedPackage {
+    pub repo: RepoId,
+    /// Repository the releases come from; differs from `repo` for an alias
+    pub source_repo: RepoId,
+    pub release: Release,
+    pub change: SyncChange,
+    /// Filters given in the manifest
+    pub filters: Vec<String>,
+    /// Filters the release files are selected with
+    pub effective_filters: Vec<String>,
+    pub pre: bool,
+    /// Link rules with destinations resolved to absolute paths
+    pub links: Vec<LinkRule>,
+    /// Versioned links with destinations resolved to absolute paths
+    pub versioned_links: Vec<VersionedLink>,
+    /// Files to download, None when the version is already installed
+    pub download: Option<DownloadPlan>,
+    pub(super) meta: Meta,
+    pub(super) is_new: bool,
+}
+
+/// Everything `ghri sync` is going to do
+#[derive(Debug, Default)]
+pub struct SyncPlan {
+    pub packages: Vec<PlannedPackage>,
+    /// Installed packages not listed in the manifest
+    pub removals: Vec<RepoId>,
+}
+
+impl SyncPlan {
+    /// True when no package needs to be installed, switched or removed
+    pub fn is_unchanged(&self) -> bool {
+        self.removals.is_empty()
+            && self
+                .packages
+                .iter()
+                .all(|p| p.change == SyncChange::Unchanged)
+    }
+}
+
+/// Install, upgrade or downgrade packages to match a manifest file
+#[tracing::instrument(skip(runtime, config, options))]
+pub async fn sync<R: Runtime + 'static>(
+    runtime: R,
+    config: Config,
+    options: SyncOptions,
+) -> Result<()> {
+    let runtime = Arc::new(runtime);
+
+    let manifest_path = absolute_manifest_path(runtime.as_ref(), &options.manifest)?;
+    let manifest = Manifest::load(runtime.as_ref(), &manifest_path)?;
+    let manifest_dir = manifest_path.parent().unwrap_or(Path::new("/"));
+
+    let lockfile = if options.locked {
+        let lock_path = Lockfile::path_for(&manifest_path);
+        Some(Lockfile::load(runtime.as_ref(), &lock_path)?)
+    } else {
+        None
+    };
+
+    let services = Services::from_config(Arc::clone(&runtime), &config)?;
+    let pkg_repo = PackageRepository::new(runtime.as_ref(), config.install_root.clone());
+    let installed = installed_packages(&pkg_repo)?;
+    refresh_listed_packages(runtime.as_ref(), &services, &config, &manifest, &installed).await?;
+
+    let action = InstallAction::new(
+        runtime.as_ref(),
+        &services.provider_factory,
+        config.install_root.clone(),
+    );
+
+    let plan = plan_sync(
+        runtime.as_ref(),
+        &action,
+        &manifest,
+        manifest_dir,
+        lockfile.as_ref(),
+        &installed,
+        &config,
+        options.remove_unlisted,
+    )
+    .await?;
+
+    if plan.is_unchanged() {
+        println!("   all packages match {}", manifest_path.display());
+    } else if !options.yes {
+        show_sync_plan(&plan, &action);
+        if !runtime.confirm("Proceed with sync?")? {
+            println!("Sync cancelled.");
+            return Ok(());
+        }
+    }
+
+    let cleanup_ctx = Arc::new(Mutex::new(CleanupContext::new()));
+    let release_installer: Box<dyn ReleaseInstaller> = match &lockfile {
+        Some(lockfile) => Box::new(DefaultReleaseInstaller::new(
+            Arc::clone(&runtime),
+            Arc::new(VerifyingDownloader::new(
+                services.downloader,
+                lockfile.digests(&Platform::detect()),
+            )),
+            Arc::new(services.extractor),
+            cleanup_ctx,
+        )),
+        None => Box::new(DefaultReleaseInstaller::new(
+            Arc::clone(&runtime),
+            Arc::new(services.downloader),
+            Arc::new(services.extractor),
+            cleanup_ctx,
+        )),
+    };
+    let link_action = LinkAction::new(runtime.as_ref(), config.install_root.clone());
+    let mut failed = 0;
+
+    for package in &plan.packages {
+        if package.change != SyncChange::Unchanged {
+            // Locked installs must select exactly the locked files
+            let (filters, default_filters) = if lockfile.is_some() {
+                (package.effective_filters.clone(), vec![])
+            } else {
+                (package.filters.clone(), config.filters.clone())
+            };
+            let options = InstallOptions {
+                filters,
+                default_filters,
+                pre: package.pre,
+                yes: true, // Confirmed above for the whole plan
+                prune: config.prune,
+                tag_pattern: package.meta.tag_pattern.clone(),
+                alias: (package.source_repo != package.repo).then(|| package.repo.repo.clone()),
+                url_source: package.meta.url_source.clone(),
+                bin_dir: None,
+                default_bin_dir: config.bin_dir.clone(),
+                original_args: vec![],
+            };
+            let repo_str = format!("{}@{}", package.source_repo, package.release.tag);
+            if let Err(e) = run_install(
+                &config,
+                Arc::clone(&runtime),
+                &action,
+                release_installer.as_ref(),
+                &repo_str,
+                options,
+            )
+            .await
+            {
+                eprintln!("   failed to sync {}: {}", package.repo, e);
+                failed += 1;
+                continue;
+            }
+        }
+
+        for rule in &package.links {
+            if let Err(e) = ensure_link(&pkg_repo, &link_action, &package.repo, None, rule) {
+                eprintln!(
+                    "   failed to link {} -> {}: {}",
+                    package.repo,
+                    rule.dest.display(),
+                    e
+                );
+                failed += 1;
+            }
+        }
+
+        for link in &package.versioned_links {
+            let result = async {
+                ensure_version(
+                    &pkg_repo,
+                    &action,
+                    release_installer.as_ref(),
+                    package,
+                    &link.version,
+                )
+                .await?;
+                ensure_link(
+                    &pkg_repo,
+                    &link_action,
+                    &package.repo,
+                    Some(&link.version),
+                    &link.to_rule(),
+                )
+            }
+            .await;
+            if let Err(e) = result {
+                eprintln!(
+                    "   failed to link {}@{} -> {}: {}",
+                    package.repo,
+                    link.version,
+                    link.dest.display(),
+                    e
+                );
+                failed += 1;
+            }
+        }
+    }
+
+    if !plan.removals.is_empty() {
+        let remove_action = RemoveAction::new(runtime.as_ref(), &config.install_root);
+        for repo in &plan.removals {
+            let result = remove_action
+                .package_repo()
+                .load_context_any(&repo.owner, &repo.repo, None)
+                .and_then(|ctx| remove_action.remove_package(&ctx));
+            match result {
+                Ok(()) => println!("   removed {}", repo),
+                Err(e) => {
+                    eprintln!("   failed to remove {}: {}", repo, e);
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    // Packages that did sync are part of the new state even if others failed
+    record_generation(
+        runtime.as_ref(),
+        &config.install_root,
+        &format!("sync {}", options.manifest.display()),
+    );
+    if failed > 0 {
+        anyhow::bail!("Sync finished with {} error(s)", failed);
+    }
+    Ok(())
+}
+
+/// Work out what needs to change for every package in the manifest.
+///
+/// With a lockfile, every package resolves to its locked tag and filters.
+#[allow(clippy::too_many_arguments)]
+pub async fn plan_sync<R: Runtime>(
+    runtime: &R,
+    action: &dyn InstallOperations,
+    manifest: &Manifest,
+    manifest_dir: &Path,
+    lockfile: Option<&Lockfile>,
+    installed: &[RepoId],
+    config: &Config,
+    remove_unlisted: bool,
+) -> Result<SyncPlan> {
+    let mut plan = SyncPlan::default();
+
+    for (repo, entry) in manifest.packages()? {
+        let source_repo = match &entry.repo {
+            Some(source_repo) => source_repo
+                .parse::<RepoId>()
+                .with_context(|| format!("Invalid repo '{}' for {}", source_repo, repo))?,
+            None => repo.clone(),
+        };
+        if source_repo.owner != repo.owner {
+            anyhow::bail!(
+                "Alias {} must have the same owner as {} (expected {}/{})",
+                repo,
+                source_repo,
+                source_repo.owner,
+                repo.repo
+            );
+        }
+        let (mut meta, is_new) = match &entry.url {
+            Some(url_source) => fetch_url_package_meta(action, &repo, url_source).await,
+            None => {
+                let source = action.resolve_source_for_new()?;
+                get_or_fetch_package_meta(action, &repo, &source_repo, source.as_ref()).await
+            }
+        }
+        .with_context(|| format!("Failed to resolve {}", repo))?;
+        // The manifest's tag pattern replaces the saved one, as with `install --tag-pattern`
+        if let Some(pattern) = &entry.tag_pattern {
+            meta.tag_pattern = Some(pattern.clone());
+        }
+
+        let pre = entry.pre.unwrap_or(config.pre);
+        let (release, effective_filters) = match lockfile {
+            Some(lockfile) => {
+                let (tag, locked) = locked_package(lockfile, &repo, entry.version.as_deref())?;
+                let release = action.resolve_version(&meta, Some(tag.to_string()), pre)?;
+                (release, locked.filters.clone())
+            }
+            None => {
+                // Pinned packages stay at their pin unless the manifest names a version
+                let version = entry.version.clone().or_else(|| meta.pinned.clone());
+                let release = action.resolve_version(&meta, version, pre)?;
+                let options = InstallOptions {
+                    filters: entry.filters.clone(),
+                    default_filters: config.filters.clone(),
+                    ..Default::default()
+                };
+                (release, action.effective_filters(&options, &meta))
+            }
+        };
+        let download = if action.is_installed(&repo, &release.tag) {
+            None
+        } else {
+            Some(get_download_plan(&release, &effective_filters)?)
+        };
+
+        let current = &meta.current_version;
+        let change = if is_new || current.is_empty() {
+            SyncChange::Install
+        } else if *current == release.tag {
+            SyncChange::Unchanged
+        } else if VersionResolver::is_newer(&meta.releases, &release, current) {
+            SyncChange::Upgrade {
+                from: current.clone(),
+            }
+        } else {
+            SyncChange::Downgrade {
+                from: current.clone(),
+            }
+        };
+
+        let links = entry
+            .links
+            .iter()
+            .map(|rule| LinkRule {
+                dest: resolve_manifest_path(runtime, manifest_dir, &rule.dest),
+                ..rule.clone()
+            })
+            .collect();
+        let versioned_links = entry
+            .versioned_links
+            .iter()
+            .map(|link| VersionedLink {
+                dest: resolve_manifest_path(runtime, manifest_dir, &link.dest),
+                ..link.clone()
+            })
+            .collect();
+
+        plan.packages.push(PlannedPackage {
+            repo,
+            source_repo,
+            release,
+            change,
+            filters: entry.filters.clone(),
+            effective_filters,
+            pre,
+            links,
+            versioned_links,
+            download,
+            meta,
+            is_new,
+        });
+    }
+
+    if remove_unlisted {
+        plan.removals = installed
+            .iter()
+            .filter(|repo| !manifest.contains(repo))
+            .cloned()
+            .collect();
+    }
+
+    Ok(plan)
+}
+
+/// Find the locked tag and files of a package for this platform, and check
+/// the tag still matches the manifest
+fn locked_package<'a>(
+    lockfile: &'a Lockfile,
+    repo: &RepoId,
+    version: Option<&str>,
+) -> Result<(&'a str, &'a LockedPlatform)> {
+    let locked = lockfile
+        .get(repo)
+        .with_context(|| format!("{} is not in the lockfile, run `ghri lock`", repo))?;
+    if let Some(version) = version
+        && !VersionResolver::versions_match(version, &locked.tag)
+        && !VersionResolver::tag_in_range(&locked.tag, version)
+    {
+        anyhow::bail!(
+            "{} is locked to {} but the manifest asks for {}, run `ghri lock`",
+            repo,
+            locked.tag,
+            version
+        );
+    }
+    let platform = Platform::detect();
+    let files = locked.for_platform(&platform).with_context(|| {
+        format!(
+            "{} {} has no files locked for {}-{}, run `ghri lock` on this platform",
+            repo, locked.tag, platform.os, platform.arch
+        )
+    })?;
+    Ok((&locked.tag, files))
+}
+
+/// Manifest path relative to the current directory
+pub(super) fn absolute_manifest_path<R: Runtime>(runtime: &R, path: &Path) -> Result<PathBuf> {
+    if path.is_relative() {
+        Ok(runtime.current_dir()?.join(path))
+    } else {
+        Ok(path.to_path_buf())
+    }
+}
+
+/// Refresh release information of listed packages that are already installed
+pub(super) async fn refresh_listed_packages<R: Runtime>(
+    runtime: &R,
+    services: &Services,
+    config: &Config,
+    manifest: &Manifest,
+    installed: &[RepoId],
+) -> Result<()> {
+    let listed_installed: Vec<String> = installed
+        .iter()
+        .filter(|repo| manifest.contains(repo))
+        .map(|repo| repo.to_string())
+        .collect();
+    if !listed_installed.is_empty() {
+        UpdateAction::new(
+            runtime,
+            &services.provider_factory,
+            config.install_root.clone(),
+        )
+        .update_all(&listed_installed)
+        .await?;
+    }
+    Ok(())
+}
+
+/// Resolve a path from the manifest: `~` is the home directory and relative
+/// paths are relative to the manifest's directory. A trailing slash is kept,
+/// so the link is created inside that directory.
+fn resolve_manifest_path<R: Runtime>(runtime: &R, manifest_dir: &Path, path: &Path) -> PathBuf {
+    let raw = path.to_string_lossy();
+    if let Some(home) = runtime.home_dir() {
+        if raw == "~" {
+            return home;
+        }
+        if let Some(rest) = raw.strip_prefix("~/") {
+            return home.join(rest);
+        }
+    }
+    if path.is_relative() {
+        manifest_dir.join(path)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Packages currently installed under the install root
+pub(super) fn installed_packages<R: Runtime>(
+    pkg_repo: &PackageRepository<'_, R>,
+) -> Result<Vec<RepoId>> {
+    Ok(pkg_repo
+        .find_all_with_meta()?
+        .into_iter()
+        .filter_map(|(_, meta)| meta.name.parse::<RepoId>().ok())
+        .collect())
+}
+
+/// Create (or refresh) a link to a package's current version, or to the
+/// given version for versioned links
+fn ensure_link<R: Runtime>(
+    pkg_repo: &PackageRepository<'_, R>,
+    link_action: &LinkAction<'_, R>,
+    repo: &RepoId,
+    version: Option<&str>,
+    rule: &LinkRule,
+) -> Result<()> {
+    let mut ctx = pkg_repo.load_context(&repo.owner, &repo.repo, version)?;
+    let result = link_action.create_package_link(
+        &mut ctx,
+        rule.dest.clone(),
+        rule.path.clone(),
+        rule.shim.clone(),
+        rule.mode,
+    )?;
+    println!(
+        "   linked {} -> {}",
+        ctx.display_name,
+        result.dest.display()
+    );
+    Ok(())
+}
+
+/// Install a version needed by a versioned link without making it current
+async fn ensure_version<R: Runtime>(
+    pkg_repo: &PackageRepository<'_, R>,
+    action: &dyn InstallOperations,
+    release_installer: &dyn ReleaseInstaller,
+    package: &PlannedPackage,
+    version: &str,
+) -> Result<()> {
+    let repo = &package.repo;
+    let meta = pkg_repo.load_required(&repo.owner, &repo.repo)?;
+    let release = action.resolve_version(&meta, Some(version.to_string()), true)?;
+    if action.is_installed(repo, &release.tag) {
+        return Ok(());
+    }
+    println!("   installing {} {}", repo, release.tag);
+    release_installer
+        .install(
+            repo,
+            &release,
+            &action.version_dir(repo, &release.tag),
+            &package.effective_filters,
+            &[],
+        )
+        .await
+}
+
+fn show_sync_plan(plan: &SyncPlan, action: &dyn InstallOperations) {
+    println!();
+    println!("=== Sync Plan ===");
+    println!();
+    for package in &plan.packages {
+        let tag = &package.release.tag;
+        match &package.change {
+            SyncChange::Install => println!("  install    {} {}", package.repo, tag),
+            SyncChange::Upgrade { from } => {
+                println!("  upgrade    {} {} -> {}", package.repo, from, tag)
+            }
+            SyncChange::Downgrade { from } => {
+                println!("  downgrade  {} {} -> {}", package.repo, from, tag)
+            }
+            SyncChange::Unchanged => println!("  unchanged  {} {}", package.repo, tag),
+        }
+    }
+    for repo in &plan.removals {
+        println!("  remove     {}", repo);
+    }
+
+    for package in &plan.packages {
+        if package.change == SyncChange::Unchanged {
+            continue;
+        }
+        if let Some(download) = &package.download {
+            ui::show_install_plan(
+                &package.repo,
+                &package.release,
+                &action.version_dir(&package.repo, &package.release.tag),
+                &action.meta_path(&package.repo),
+                download,
+                package.is_new,
+                &package.meta,
+            );
+        }
+    }
+
+    let links: Vec<_> = plan
+        .packages
+        .iter()
+        .flat_map(|p| {
+            let current = p
+                .links
+                .iter()
+                .map(move |rule| (p.repo.to_string(), &rule.dest, &rule.path));
+            let versioned = p.versioned_links.iter().map(move |link| {
+                (
+                    format!("{}@{}", p.repo, link.version),
+                    &link.dest,
+                    &link.path,
+                )
+            });
+            current.chain(versioned)
+        })
+        .collect();
+    if !links.is_empty() {
+        println!();
+        println!("Links:");
+        for (spec, dest, path) in links {
+            let source = path.as_ref().map(|p| format!(":{}", p)).unwrap_or_default();
+            println!("  [LINK] {} -> {}{}", dest.display(), spec, source);
+        }
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::MockInstallOperations;
+    use crate::domain::model::{ANY_PLATFORM, LockedPackage};
+    use crate::provider::MockProvider;
+    use crate::runtime::MockRuntime;
+    use crate::test_utils::{configure_mock_runtime_basics, test_home, test_root};
+    use mockall::predicate::*;
+
+    fn release(tag: &str, published_at: &str) -> Release {
+        Release {
+            tag: tag.into(),
+            published_at: Some(published_at.into()),
+            tarball_url: format!("https://example.com/{}.tar.gz", tag),
+            ..Default::default()
+        }
+    }
+
+    fn meta(name: &str, current: &str) -> Meta {
+        Meta {
+            name: name.into(),
+            current_version: current.into(),
+            releases: vec![
+                release("v2.0.0", "2024-02-01T00:00:00Z"),
+                release("v1.0.0", "2024-01-01T00:00:00Z"),
+            ],
+            ..Default::default()
+        }
+    }
+
+    /// Files locked for the platform the tests run on
+    fn locked_here(filters: &[&str]) -> LockedPlatform {
+        let platform = Platform::detect();
+        LockedPlatform {
+            os: platform.os,
+            arch: platform.arch,
+            filters: filters.iter().map(|f| f.to_string()).collect(),
+            files: vec![],
+        }
+    }
+
+    /// Action mock where every package resolves to the given meta and version
+    fn mock_action(metas: Vec<(Meta, bool)>) -> MockInstallOperations {
+        let mut action = MockInstallOperations::new();
+        action
+            .expect_resolve_source_for_new()
+            .returning(|| Ok(Arc::new(MockProvider::new())));
+        action.expect_get_or_fetch_meta().returning(move |repo, _| {
+            let found = metas
+                .iter()
+                .find(|(m, _)| m.name == repo.to_string())
+                .cloned()
+                .unwrap();
+            Box::pin(async move { Ok(found) })
+        });
+        action
+            .expect_resolve_version()
+            .returning(|meta, version, _| {
+                let tag = version.unwrap_or_else(|| meta.releases[0].tag.clone());
+                Ok(meta.releases.iter().find(|r| r.tag == tag).unwrap().clone())
+            });
+        action
+            .expect_effective_filters()
+            .returning(|options, _| options.filters.clone());
+        action
+            .expect_is_installed()
+            .returning(|_, version| version == "v1.0.0");
+        action
+    }
+
+    #[tokio::test]
+    async fn test_plan_sync_changes() {
+        // --- Setup ---
+        let mut runtime = MockRuntime::new();
+        configure_mock_runtime_basics(&mut runtime);
+
+        let manifest = Manifest::parse(
+            r#"
+[packages."new/tool"]
+links = [{ dest = "~/.local/bin/", path = "tool" }]
+
+[packages."old/tool"]
+versioned_links = [{ dest = "bin/tool-1", version = "v1.0.0" }]
+
+[packages."pinned/tool"]
+version = "v1.0.0"
+
+[packages."same/tool"]
+"#,
+        )
+        .unwrap();
+
+        let action = mock_action(vec![
+            (meta("new/tool", ""), true),
+            (meta("old/tool", "v1.0.0"), false),
+            (meta("pinned/tool", "v2.0.0"), false),
+            (meta("same/tool", "v2.0.0"), false),
+        ]);
+        let installed: Vec<RepoId> = ["old/tool", "pinned/tool", "same/tool", "extra/tool"]
+            .iter()
+            .map(|r| r.parse().unwrap())
+            .collect();
+
+        // --- Execute ---
+        let plan = plan_sync(
+            &runtime,
+            &action,
+            &manifest,
+            Path::new("/work"),
+            None,
+            &installed,
+            &Config::for_test(test_root()),
+            true,
+        )
+        .await
+        .unwrap();
+
+        // --- Verify ---
+        let changes: Vec<_> = plan.packages.iter().map(|p| p.change.clone()).collect();
+        assert_eq!(
+            changes,
+            vec![
+                SyncChange::Install,
+                SyncChange::Upgrade {
+                    from: "v1.0.0".into()
+                },
+                SyncChange::Downgrade {
+                    from: "v2.0.0".into()
+                },
+                SyncChange::Unchanged,
+            ]
+        );
+        // v1.0.0 is already on disk, so the downgrade needs no download
+        assert!(plan.packages[0].download.is_some());
+        assert!(plan.packages[2].download.is_none());
+        assert_eq!(
+            plan.packages[0].links[0].dest.to_string_lossy(),
+            format!("{}/", test_home().join(".local/bin").display())
+        );
+        assert_eq!(
+            plan.packages[1].versioned_links[0].dest,
+            PathBuf::from("/work/bin/tool-1")
+        );
+        assert_eq!(plan.removals, vec!["extra/tool".parse().unwrap()]);
+        assert!(!plan.is_unchanged());
+    }
+
+    #[tokio::test]
+    async fn test_plan_sync_keeps_unlisted_by_default() {
+        let runtime = MockRuntime::new();
+        let manifest = Manifest::parse("[packages.\"same/tool\"]\n").unwrap();
+        let action = mock_action(vec![(meta("same/tool", "v2.0.0"), false)]);
+        let installed: Vec<RepoId> = vec!["extra/tool".parse().unwrap()];
+
+        let plan = plan_sync(
+            &runtime,
+            &action,
+            &manifest,
+            Path::new("/work"),
+            None,
+            &installed,
+            &Config::for_test(test_root()),
+            false,
+        )
+        .await
+        .unwrap();
+
+        assert!(plan.removals.is_empty());
+        assert!(plan.is_unchanged());
+    }
+
+    #[tokio::test]
+    async fn test_plan_sync_applies_tag_pattern() {
+        // --- Setup ---
+        let runtime = MockRuntime::new();
+        let manifest =
+            Manifest::parse("[packages.\"mono/repo\"]\ntag_pattern = \"cli-v*\"\n").unwrap();
+        let mut mono = meta("mono/repo", "");
+        mono.releases = vec![
+            release("app-v3.0.0", "2024-03-01T00:00:00Z"),
+            release("cli-v1.2.0", "2024-02-01T00:00:00Z"),
+        ];
+        let mut action = MockInstallOperations::new();
+        action
+            .expect_resolve_source_for_new()
+            .returning(|| Ok(Arc::new(MockProvider::new())));
+        action.expect_get_or_fetch_meta().returning(move |_, _| {
+            let m = mono.clone();
+            Box::pin(async move { Ok((m, true)) })
+        });
+        action
+            .expect_resolve_version()
+            .returning(|meta, _, _| Ok(meta.channel_releases().next().unwrap().clone()));
+        action
+            .expect_effective_filters()
+            .returning(|options, _| options.filters.clone());
+        action.expect_is_installed().returning(|_, _| false);
+
+        // --- Execute ---
+        let plan = plan_sync(
+            &runtime,
+            &action,
+            &manifest,
+            Path::new("/work"),
+            None,
+            &[],
+            &Config::for_test(test_root()),
+            false,
+        )
+        .await
+        .unwrap();
+
+        // --- Verify ---
+        assert_eq!(plan.packages[0].release.tag, "cli-v1.2.0");
+        assert_eq!(plan.packages[0].meta.tag_pattern.as_deref(), Some("cli-v*"));
+    }
+
+    #[tokio::test]
+    async fn test_plan_sync_locked() {
+        // --- Setup ---
+        let runtime = MockRuntime::new();
+        let manifest =
+            Manifest::parse("[packages.\"same/tool\"]\nfilters = [\"*linux*\"]\n").unwrap();
+        let action = mock_action(vec![(meta("same/tool", "v2.0.0"), false)]);
+        let lockfile = Lockfile {
+            packages: vec![LockedPackage {
+                name: "same/tool".into(),
+                tag: "v1.0.0".into(),
+                platforms: vec![locked_here(&["*linux*musl*"])],
+            }],
+            ..Default::default()
+        };
+
+        // --- Execute ---
+        let plan = plan_sync(
+            &runtime,
+            &action,
+            &manifest,
+            Path::new("/work"),
+            Some(&lockfile),
+            &[],
+            &Config::for_test(test_root()),
+            false,
+        )
+        .await
+        .unwrap();
+
+        // --- Verify ---
+        let package = &plan.packages[0];
+        assert_eq!(package.release.tag, "v1.0.0");
+        assert_eq!(package.effective_filters, vec!["*linux*musl*"]);
+        assert_eq!(
+            package.change,
+            SyncChange::Downgrade {
+                from: "v2.0.0".into()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_plan_sync_stale_lockfile() {
+        let runtime = MockRuntime::new();
+        let manifest = Manifest::parse(
+            "[packages.\"same/tool\"]\nversion = \"2.0.0\"\n\n[packages.\"new/tool\"]\n",
+        )
+        .unwrap();
+        let action = mock_action(vec![
+            (meta("same/tool", "v2.0.0"), false),
+            (meta("new/tool", ""), true),
+        ]);
+        let mut lockfile = Lockfile {
+            packages: vec![LockedPackage {
+                name: "same/tool".into(),
+                tag: "v2.0.0".into(),
+                platforms: vec![locked_here(&[])],
+            }],
+            ..Default::default()
+        };
+        let config = Config::for_test(test_root());
+
+        // new/tool is listed in the manifest but not locked
+        let err = plan_sync(
+            &runtime,
+            &action,
+            &manifest,
+            Path::new("/work"),
+            Some(&lockfile),
+            &[],
+            &config,
+            false,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("new/tool is not in the lockfile"));
+
+        // The manifest asks for another version than the locked one
+        lockfile.packages[0].tag = "v1.0.0".into();
+        lockfile.packages.push(LockedPackage {
+            name: "new/tool".into(),
+            tag: "v2.0.0".into(),
+            platforms: vec![locked_here(&[])],
+        });
+        let err = plan_sync(
+            &runtime,
+            &action,
+            &manifest,
+            Path::new("/work"),
+            Some(&lockfile),
+            &[],
+            &config,
+            false,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("same/tool is locked to v1.0.0"));
+    }
+
+    #[tokio::test]
+    async fn test_plan_sync_locked_on_other_platform() {
+        // --- Setup ---
+        let runtime = MockRuntime::new();
+        let manifest = Manifest::parse("[packages.\"same/tool\"]\n").unwrap();
+        let action = mock_action(vec![(meta("same/tool", "v1.0.0"), false)]);
+        let mut lockfile = Lockfile {
+            packages: vec![LockedPackage {
+                name: "same/tool".into(),
+                tag: "v1.0.0".into(),
+                platforms: vec![LockedPlatform {
+                    os: "plan9".into(),
+                    arch: "mips".into(),
+                    filters: vec!["*plan9*".into()],
+                    files: vec![],
+                }],
+            }],
+            ..Default::default()
+        };
+        let config = Config::for_test(test_root());
+
+        // --- Execute ---
+        let err = plan_sync(
+            &runtime,
+            &action,
+            &manifest,
+            Path::new("/work"),
+            Some(&lockfile),
+            &[],
+            &config,
+            false,
+        )
+        .await
+        .unwrap_err();
+
+        // --- Verify ---
+        assert!(err.to_string().contains("has no files locked for"));
+
+        // Files every platform shares are used instead
+        lockfile.packages[0].platforms.push(LockedPlatform {
+            os: ANY_PLATFORM.into(),
+            arch: ANY_PLATFORM.into(),
+            ..Default::default()
+        });
+        let plan = plan_sync(
+            &runtime,
+            &action,
+            &manifest,
+            Path::new("/work"),
+            Some(&lockfile),
+            &[],
+            &config,
+            false,
+        )
+        .await
+        .unwrap();
+        assert!(plan.packages[0].effective_filters.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_manifest_path() {
+        let mut runtime = MockRuntime::new();
+        runtime.expect_home_dir().returning(|| Some(test_home()));
+        let dir = Path::new("/work");
+
+        assert_eq!(
+            resolve_manifest_path(&runtime, dir, Path::new("~/bin/tool")),
+            test_home().join("bin/tool")
+        );
+        assert_eq!(
+            resolve_manifest_path(&runtime, dir, Path::new("bin/tool")),
+            dir.join("bin/tool")
+        );
+        assert_eq!(
+            resolve_manifest_path(&runtime, dir, &test_home().join("abs")),
+            test_home().join("abs")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_missing_manifest() {
+        let mut runtime = MockRuntime::new();
+        configure_mock_runtime_basics(&mut runtime);
+        runtime
+            .expect_read_to_string()
+            .with(eq(test_home().join("ghri.toml")))
+            .returning(|_| Err(anyhow::anyhow!("No such file")));
+
+        let options = SyncOptions {
+            manifest: PathBuf::from("ghri.toml"),
+            ..Default::default()
+        };
+        let err = sync(runtime, Config::for_test(test_root()), options)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Failed to read manifest"));
+    }
+}
@@ -6,6 +6,7 @@ use crate::domain::service::PackageRepository;
 use crate::runtime::Runtime;
 
 use super::config::Config;
+use super::generations::record_generation;
 use super::link_spec::LinkSpec;
 
 /// Remove a link rule and its symlink
@@ -21,7 +22,7 @@ pub fn unlink<R: Runtime>(
     let spec = repo_str.parse::<LinkSpec>()?;
 
     let pkg_repo = PackageRepository::new(&runtime, config.install_root.clone());
-    let action = LinkAction::new(&runtime, config.install_root);
+    let action = LinkAction::new(&runtime, config.install_root.clone());
 
     // Load package context - version is always resolved (user-specified or current)
     let mut ctx =
@@ -47,6 +48,11 @@ pub fn unlink<R: Runtime>(
         eprintln!("Warning: Skipped {:?} - points to external path", path);
     }
 
+    record_generation(
+        &runtime,
+        &config.install_root,
+        &format!("unlink {}", repo_str),
+    );
     Ok(())
 }
 
@@ -119,6 +125,8 @@ mod tests {
             links: vec![LinkRule {
                 dest: test_bin_dir().join("tool"),
                 path: None,
+                shim: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -158,6 +166,8 @@ mod tests {
             links: vec![LinkRule {
                 dest: existing_link.clone(),
                 path: None,
+                shim: None,
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use std::sync::Arc;
 
 use crate::application::UpdateAction;
 use crate::runtime::Runtime;
@@ -12,10 +13,11 @@ pub async fn update<R: Runtime + 'static>(
     config: Config,
     repos: Vec<String>,
 ) -> Result<()> {
-    let services = Services::from_config(&config)?;
+    let runtime = Arc::new(runtime);
+    let services = Services::from_config(Arc::clone(&runtime), &config)?;
 
     let action = UpdateAction::new(
-        &runtime,
+        runtime.as_ref(),
         &services.provider_factory,
         config.install_root.clone(),
     );
@@ -29,8 +31,9 @@ pub async fn update<R: Runtime + 'static>(
 
     for result in &results {
         println!("   updating {}", result.repo);
-        if result.has_update && result.latest_version.is_some() {
-            let latest = result.latest_version.as_ref().unwrap();
+        if result.has_update
+            && let Some(latest) = &result.latest_version
+        {
             print_update_available(&result.repo.to_string(), &result.current_version, latest);
         }
     }
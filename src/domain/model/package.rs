@@ -2,7 +2,10 @@ use anyhow::Result;
 use serde::{Deserialize, Deserializer, Serialize};
 use std::path::{Path, PathBuf};
 
-use crate::domain::model::{LinkRule, Release, VersionedLink};
+use crate::domain::model::{
+    LinkRule, Release, TagPattern, UpgradePolicy, UrlSource, VersionConstraint, VersionResolver,
+    VersionedLink,
+};
 use crate::provider::{RepoId, RepoMetadata};
 use crate::runtime::Runtime;
 
@@ -58,8 +61,43 @@ pub struct Meta {
     /// These patterns are saved and reused when updating the package
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub filters: Vec<String>,
+    /// Directory the package's executables are linked into (`install --bin-dir`)
+    /// Executables of newly installed versions are linked there too
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bin_dir: Option<PathBuf>,
+    /// Version range the package was installed with (e.g., "^1.4")
+    /// Upgrades stay inside this range
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_constraint: Option<String>,
+    /// Version the package is held at; upgrade skips it and install without
+    /// a version keeps it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<String>,
+    /// Default bound for `ghri upgrade` when no `--patch/--minor/--major` is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upgrade_policy: Option<UpgradePolicy>,
+    /// Previously active versions, most recent last; `ghri rollback` returns to the last one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<String>,
+    /// Glob over release tags selecting one component of a monorepo (e.g., "cli-v*")
+    /// Resolution, upgrades and `show` only consider matching releases
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_pattern: Option<String>,
+    /// Repository the releases come from, when the package is installed under
+    /// an alias (`name` is then "owner/alias")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_repo: Option<String>,
+    /// Download URL template and version discovery for packages not hosted on a forge
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_source: Option<UrlSource>,
+    /// Installed from local artifact files; there is no release source to refresh
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub from_file: bool,
 }
 
+/// Number of previously active versions remembered for rollback
+const MAX_HISTORY: usize = 10;
+
 impl Meta {
     pub fn from(
         repo: RepoId,
@@ -88,47 +126,182 @@ impl Meta {
             linked_to: None,
             linked_path: None,
             filters: vec![],
+            bin_dir: None,
+            version_constraint: None,
+            pinned: None,
+            upgrade_policy: None,
+            history: vec![],
+            tag_pattern: None,
+            source_repo: None,
+            url_source: None,
+            from_file: false,
         }
     }
 
+    /// Metadata for a new alias of a repository
+    ///
+    /// Repository info and releases are taken from the source; filters, links
+    /// and the other per-package settings start empty.
+    pub fn for_alias(source: Meta, package: &RepoId) -> Self {
+        Meta {
+            name: package.to_string(),
+            api_url: source.api_url,
+            repo_info_url: source.repo_info_url,
+            releases_url: source.releases_url,
+            description: source.description,
+            homepage: source.homepage,
+            license: source.license,
+            updated_at: source.updated_at,
+            releases: source.releases,
+            source_repo: Some(source.source_repo.unwrap_or(source.name)),
+            url_source: source.url_source,
+            from_file: source.from_file,
+            ..Default::default()
+        }
+    }
+
+    /// Repository to fetch releases from: the alias source, or the package itself
+    pub fn source_repo(&self) -> Result<RepoId> {
+        self.source_repo.as_deref().unwrap_or(&self.name).parse()
+    }
+
+    /// Sort releases newest first, by version (see `VersionResolver::compare_releases`)
     pub(crate) fn sort_releases_internal(releases: &mut [Release]) {
-        releases.sort_by(|a, b| {
-            match (&a.published_at, &b.published_at) {
-                (Some(at_a), Some(at_b)) => at_b.cmp(at_a),  // Descending
-                (Some(_), None) => std::cmp::Ordering::Less, // Published comes before unpublished
-                (None, Some(_)) => std::cmp::Ordering::Greater,
-                (None, None) => b.tag.cmp(&a.tag), // Version descending fallback
-            }
-        });
+        releases.sort_by(|a, b| VersionResolver::compare_releases(b, a));
     }
 
     pub fn sort_releases(&mut self) {
         Self::sort_releases_internal(&mut self.releases);
     }
 
-    pub fn get_latest_stable_release(&self) -> Option<&Release> {
+    /// Parsed tag pattern; an invalid saved pattern is ignored
+    pub fn tag_pattern(&self) -> Option<TagPattern> {
+        self.tag_pattern
+            .as_deref()
+            .and_then(|p| TagPattern::parse(p).ok())
+    }
+
+    /// Releases of the package's channel: those matching the tag pattern, or all
+    pub fn channel_releases(&self) -> impl Iterator<Item = &Release> + Clone {
+        let pattern = self.tag_pattern();
         self.releases
             .iter()
-            .filter(|r| !r.prerelease)
-            .max_by(|a, b| {
-                // Simplified version comparison: tag_name might not be semver-compliant,
-                // but published_at is a good proxy for "latest".
-                // If published_at is missing, fall back to version string comparison.
-                match (&a.published_at, &b.published_at) {
-                    (Some(at_a), Some(at_b)) => at_a.cmp(at_b),
-                    _ => a.tag.cmp(&b.tag),
-                }
+            .filter(move |r| pattern.as_ref().is_none_or(|p| p.matches(&r.tag)))
+    }
+
+    /// Find a release of the channel by tag, or by version without the channel prefix
+    pub fn find_release(&self, version: &str) -> Option<&Release> {
+        VersionResolver::find_exact(self.channel_releases(), version).or_else(|| {
+            let pattern = self.tag_pattern()?;
+            self.channel_releases()
+                .find(|r| VersionResolver::versions_match(pattern.strip(&r.tag), version))
+        })
+    }
+
+    /// Version for display, without the channel prefix of the tag pattern
+    pub fn display_version<'t>(&self, tag: &'t str) -> &'t str {
+        match self.tag_pattern() {
+            Some(pattern) => pattern.strip(tag),
+            None => tag,
+        }
+    }
+
+    /// Get the latest stable release by version
+    pub fn get_latest_stable_release(&self) -> Option<&Release> {
+        VersionResolver::find_latest_stable(self.channel_releases())
+    }
+
+    /// Latest release an upgrade may move to, inside the saved version range if any
+    pub fn upgrade_target(&self, include_prerelease: bool) -> Option<&Release> {
+        let range = self
+            .version_constraint
+            .as_deref()
+            .and_then(|spec| VersionConstraint::parse(spec).ok());
+        match range {
+            Some(VersionConstraint::Range(req)) => {
+                VersionResolver::find_in_range(self.channel_releases(), &req, include_prerelease)
+            }
+            _ if include_prerelease => self.get_latest_release(),
+            _ => self.get_latest_stable_release(),
+        }
+    }
+
+    /// Latest upgrade target that also stays within an upgrade policy
+    ///
+    /// Without a policy this is the same as `upgrade_target`.
+    pub fn upgrade_target_within(
+        &self,
+        include_prerelease: bool,
+        policy: Option<UpgradePolicy>,
+    ) -> Option<&Release> {
+        let Some(policy) = policy else {
+            return self.upgrade_target(include_prerelease);
+        };
+        let range = match self
+            .version_constraint
+            .as_deref()
+            .and_then(|spec| VersionConstraint::parse(spec).ok())
+        {
+            Some(VersionConstraint::Range(req)) => Some(req),
+            _ => None,
+        };
+        self.channel_releases()
+            .filter(|r| include_prerelease || !r.prerelease)
+            .filter(|r| {
+                range.as_ref().is_none_or(|req| {
+                    VersionResolver::parse_tag(&r.tag).is_some_and(|v| req.matches(&v))
+                })
             })
+            .filter(|r| policy.allows(&self.current_version, &r.tag))
+            .max_by(|a, b| VersionResolver::compare_releases(a, b))
+    }
+
+    /// Make a version current, remembering the previously active one for rollback
+    pub fn set_current_version(&mut self, version: &str) {
+        if self.current_version == version {
+            return;
+        }
+        let previous = std::mem::replace(&mut self.current_version, version.to_string());
+        if previous.is_empty() {
+            return;
+        }
+        self.history.retain(|v| *v != previous && v != version);
+        self.history.push(previous);
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+    }
+
+    /// Link rules of the package, including the legacy `linked_to` link
+    pub fn link_rules(&self) -> Vec<LinkRule> {
+        let mut rules = self.links.clone();
+        if let Some(linked_to) = &self.linked_to {
+            rules.push(LinkRule {
+                dest: linked_to.clone(),
+                path: self.linked_path.clone(),
+                ..Default::default()
+            });
+        }
+        rules
+    }
+
+    /// Record the digests of copied and hard linked files, by link destination
+    pub fn record_link_digests(&mut self, digests: &[(PathBuf, String)]) {
+        for (dest, digest) in digests {
+            let links = self.links.iter_mut().filter(|l| l.dest == *dest);
+            for link in links {
+                link.digest = Some(digest.clone());
+            }
+            let versioned = self.versioned_links.iter_mut().filter(|l| l.dest == *dest);
+            for link in versioned {
+                link.digest = Some(digest.clone());
+            }
+        }
     }
 
     /// Get the latest release including pre-releases
     pub fn get_latest_release(&self) -> Option<&Release> {
-        self.releases
-            .iter()
-            .max_by(|a, b| match (&a.published_at, &b.published_at) {
-                (Some(at_a), Some(at_b)) => at_a.cmp(at_b),
-                _ => a.tag.cmp(&b.tag),
-            })
+        VersionResolver::find_latest(self.channel_releases())
     }
 
     /// Check if a string is effectively empty (None, empty, or whitespace-only)
@@ -229,8 +402,13 @@ impl MetaExt for Meta {
             self.releases_url = format!("{}/repos/{}/{}/releases", self.api_url, owner, repo);
         }
 
-        // Default homepage to GitHub repo page (also handle empty string in Some)
-        if Meta::is_option_empty_or_blank(&self.homepage) && !owner.is_empty() && !repo.is_empty() {
+        // Default homepage to GitHub repo page (also handle empty string in Some);
+        // packages installed from local files have none
+        if Meta::is_option_empty_or_blank(&self.homepage)
+            && !self.from_file
+            && !owner.is_empty()
+            && !repo.is_empty()
+        {
             // Convert API URL to web URL
             let web_url = if self.api_url.contains("api.github.com") {
                 "https://github.com".to_string()
@@ -261,6 +439,7 @@ impl MetaExt for Meta {
                 self.links.push(LinkRule {
                     dest: linked_to.clone(),
                     path: self.linked_path.clone(),
+                    ..Default::default()
                 });
             }
             // Clear legacy fields after migration
@@ -442,14 +621,14 @@ mod tests {
     #[test]
     fn test_meta_sorting_fallback() {
         // Test sorting behavior when some releases have no published_at date
-        // Releases with published_at should come first, then fallback to version string comparison
+        // Releases are sorted by version; published_at only breaks ties
 
         // --- Setup ---
 
         let mut releases = vec![
             Release {
                 tag: "v1".into(),
-                published_at: None, // No date - will be sorted by version
+                published_at: None, // No date - still sorted by version
                 name: None,
                 prerelease: false,
                 tarball_url: "".into(),
@@ -457,7 +636,7 @@ mod tests {
             },
             Release {
                 tag: "v2".into(),
-                published_at: None, // No date - will be sorted by version
+                published_at: None, // No date - still sorted by version
                 name: None,
                 prerelease: false,
                 tarball_url: "".into(),
@@ -465,7 +644,7 @@ mod tests {
             },
             Release {
                 tag: "v1.5".into(),
-                published_at: Some("2023".into()), // Has date - comes first
+                published_at: Some("2023".into()), // Has date - does not matter
                 name: None,
                 prerelease: false,
                 tarball_url: "".into(),
@@ -479,10 +658,9 @@ mod tests {
 
         // --- Verify ---
 
-        // v1.5 has published_at, so it comes first
-        assert_eq!(releases[0].tag, "v1.5");
-        // v1 and v2 have no published_at, sorted by version descending
-        assert_eq!(releases[1].tag, "v2");
+        // Version descending, regardless of published_at
+        assert_eq!(releases[0].tag, "v2");
+        assert_eq!(releases[1].tag, "v1.5");
         assert_eq!(releases[2].tag, "v1");
     }
 
@@ -643,6 +821,8 @@ mod tests {
                 name: "app.tar.gz".into(),
                 size: 1024,
                 download_url: "https://example.com/app.tar.gz".into(),
+                api_url: None,
+                local: false,
             }],
         };
 
@@ -1405,4 +1585,59 @@ mod tests {
         // Meta::from() should create meta with empty filters
         assert!(meta.filters.is_empty());
     }
+
+    #[test]
+    fn test_meta_set_current_version_records_history() {
+        let mut meta = Meta::default();
+        meta.set_current_version("v1");
+        assert!(meta.history.is_empty());
+
+        meta.set_current_version("v2");
+        meta.set_current_version("v2");
+        meta.set_current_version("v3");
+        assert_eq!(meta.current_version, "v3");
+        assert_eq!(meta.history, vec!["v1", "v2"]);
+
+        // Returning to an older version moves it to the end of the history
+        meta.set_current_version("v1");
+        assert_eq!(meta.history, vec!["v2", "v3"]);
+
+        for i in 4..20 {
+            meta.set_current_version(&format!("v{}", i));
+        }
+        assert_eq!(meta.history.len(), MAX_HISTORY);
+        assert_eq!(meta.history.last().map(String::as_str), Some("v18"));
+    }
+
+    #[test]
+    fn test_meta_tag_pattern_selects_channel() {
+        // --- Setup ---
+        let tags = ["server-v3.0.1", "cli-v1.2.0", "sdk-v0.4.0", "cli-v1.1.9"];
+        let mut meta = Meta {
+            name: "owner/monorepo".into(),
+            releases: tags
+                .iter()
+                .map(|tag| Release {
+                    tag: tag.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            tag_pattern: Some("cli-v*".into()),
+            ..Default::default()
+        };
+        meta.sort_releases();
+
+        // --- Execute & Verify ---
+        assert_eq!(
+            meta.get_latest_stable_release().map(|r| r.tag.as_str()),
+            Some("cli-v1.2.0")
+        );
+        assert_eq!(meta.channel_releases().count(), 2);
+        assert_eq!(
+            meta.find_release("1.1.9").map(|r| r.tag.as_str()),
+            Some("cli-v1.1.9")
+        );
+        assert!(meta.find_release("server-v3.0.1").is_none());
+        assert_eq!(meta.display_version("cli-v1.2.0"), "1.2.0");
+    }
 }
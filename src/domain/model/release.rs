@@ -8,6 +8,13 @@ pub struct ReleaseAsset {
     pub size: u64,
     #[serde(default)]
     pub download_url: String,
+    /// API endpoint of the asset, used for authenticated downloads from private repositories
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    /// Read from the local filesystem: a file of the file provider or a local
+    /// artifact. Only such assets are downloaded from a `file://` URL.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub local: bool,
 }
 
 /// A release from the provider.
@@ -0,0 +1,65 @@
+use anyhow::{Context, Result};
+
+/// Glob over release tags that selects one component of a monorepo (e.g., `cli-v*`)
+///
+/// The literal text before the first wildcard is the channel prefix, which is
+/// stripped when versions are displayed: `cli-v1.2.0` shows as `1.2.0`.
+#[derive(Debug, Clone)]
+pub struct TagPattern {
+    pattern: glob::Pattern,
+    prefix: String,
+}
+
+impl TagPattern {
+    /// Parse a tag pattern
+    pub fn parse(pattern: &str) -> Result<Self> {
+        let glob = glob::Pattern::new(pattern)
+            .with_context(|| format!("Invalid tag pattern '{}'", pattern))?;
+        let prefix = pattern
+            .split(['*', '?', '['])
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        Ok(Self {
+            pattern: glob,
+            prefix,
+        })
+    }
+
+    /// Check whether a tag belongs to the channel
+    pub fn matches(&self, tag: &str) -> bool {
+        self.pattern.matches(tag)
+    }
+
+    /// Tag without the channel prefix
+    pub fn strip<'t>(&self, tag: &'t str) -> &'t str {
+        match tag.strip_prefix(&self.prefix) {
+            Some(rest) if !rest.is_empty() => rest,
+            _ => tag,
+        }
+    }
+
+    /// The pattern as written
+    pub fn as_str(&self) -> &str {
+        self.pattern.as_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_pattern() {
+        let pattern = TagPattern::parse("cli-v*").unwrap();
+        assert!(pattern.matches("cli-v1.2.0"));
+        assert!(!pattern.matches("server-v3.0.1"));
+        assert_eq!(pattern.strip("cli-v1.2.0"), "1.2.0");
+        assert_eq!(pattern.strip("other"), "other");
+
+        let pattern = TagPattern::parse("sdk/*").unwrap();
+        assert_eq!(pattern.strip("sdk/v0.4.0"), "v0.4.0");
+
+        assert!(TagPattern::parse("cli-[").is_err());
+    }
+}
@@ -0,0 +1,340 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::domain::model::{Release, ReleaseAsset, VersionResolver};
+
+/// Releases served from plain HTTPS: a download URL template plus a way to find versions
+///
+/// ```toml
+/// url = "https://dl.example.com/tool/{version}/tool-{os}-{arch}.tar.gz"
+/// os = { macos = "darwin" }
+/// arch = { x86_64 = "amd64" }
+/// versions = { from = "listing", url = "https://dl.example.com/tool/", regex = 'href="(\d+\.\d+\.\d+)/"' }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct UrlSource {
+    /// Download URL with `{version}`, `{os}` and `{arch}` placeholders
+    pub url: String,
+    /// How versions are discovered
+    pub versions: VersionDiscovery,
+    /// Names used for `{os}` instead of linux/macos/windows
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub os: BTreeMap<String, String>,
+    /// Names used for `{arch}` instead of x86_64/aarch64
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub arch: BTreeMap<String, String>,
+}
+
+/// Where the versions of a `UrlSource` come from
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "from", rename_all = "lowercase", deny_unknown_fields)]
+pub enum VersionDiscovery {
+    /// First capture group of every match of `regex` on a listing page
+    Listing { url: String, regex: String },
+    /// Values selected by a JSONPath (e.g., `$.releases[*].version`) from a JSON document
+    Json { url: String, path: String },
+    /// A fixed list of versions
+    List { versions: Vec<String> },
+}
+
+impl UrlSource {
+    /// Check the template and discovery settings
+    pub fn validate(&self) -> Result<()> {
+        if !self.url.contains("{version}") {
+            anyhow::bail!("URL template '{}' has no {{version}} placeholder", self.url);
+        }
+        match &self.versions {
+            VersionDiscovery::Listing { regex, .. } => {
+                Self::version_regex(regex)?;
+            }
+            VersionDiscovery::Json { path, .. } => {
+                parse_json_path(path)?;
+            }
+            VersionDiscovery::List { versions } if versions.is_empty() => {
+                anyhow::bail!("No versions given for {}", self.url);
+            }
+            VersionDiscovery::List { versions } => {
+                versions.iter().try_for_each(|v| check_version(v))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// URL of the document versions are discovered from, if any
+    pub fn discovery_url(&self) -> Option<&str> {
+        match &self.versions {
+            VersionDiscovery::Listing { url, .. } | VersionDiscovery::Json { url, .. } => Some(url),
+            VersionDiscovery::List { .. } => None,
+        }
+    }
+
+    /// Versions found in a fetched discovery document (or the fixed list), deduplicated
+    ///
+    /// Versions name directories under the install root, so a document with a
+    /// version that is not a plain file name is rejected.
+    pub fn versions_in(&self, document: &str) -> Result<Vec<String>> {
+        let mut versions = match &self.versions {
+            VersionDiscovery::Listing { regex, .. } => Self::version_regex(regex)?
+                .captures_iter(document)
+                .filter_map(|c| c.get(1))
+                .map(|m| m.as_str().to_string())
+                .collect(),
+            VersionDiscovery::Json { path, .. } => {
+                let json: Value =
+                    serde_json::from_str(document).context("Invalid JSON version document")?;
+                select_json_path(&json, &parse_json_path(path)?)
+                    .into_iter()
+                    .filter_map(|v| match v {
+                        Value::String(s) => Some(s.clone()),
+                        Value::Number(n) => Some(n.to_string()),
+                        _ => None,
+                    })
+                    .collect()
+            }
+            VersionDiscovery::List { versions } => versions.clone(),
+        };
+        let mut seen = std::collections::HashSet::new();
+        versions.retain(|v| !v.trim().is_empty() && seen.insert(v.clone()));
+        versions.iter().try_for_each(|v| check_version(v))?;
+        Ok(versions)
+    }
+
+    /// Release for a version, with the download for one platform as its asset
+    pub fn release(&self, version: &str, os: &str, arch: &str) -> Release {
+        let url = self.expand(version, os, arch);
+        let name = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| path.rsplit('/').next())
+            .unwrap_or(&url)
+            .to_string();
+        Release {
+            tag: version.to_string(),
+            prerelease: VersionResolver::parse_tag(version).is_some_and(|v| !v.pre.is_empty()),
+            assets: vec![ReleaseAsset {
+                name,
+                size: 0,
+                download_url: url,
+                api_url: None,
+                local: false,
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// Download URL for a version and platform
+    pub fn expand(&self, version: &str, os: &str, arch: &str) -> String {
+        let os = self.os.get(os).map_or(os, String::as_str);
+        let arch = self.arch.get(arch).map_or(arch, String::as_str);
+        self.url
+            .replace("{version}", version)
+            .replace("{os}", os)
+            .replace("{arch}", arch)
+    }
+
+    fn version_regex(regex: &str) -> Result<Regex> {
+        let re = Regex::new(regex).with_context(|| format!("Invalid version regex '{}'", regex))?;
+        if re.captures_len() < 2 {
+            anyhow::bail!(
+                "Version regex '{}' needs a capture group around the version",
+                regex
+            );
+        }
+        Ok(re)
+    }
+}
+
+/// Check that a discovered version can name a version directory
+fn check_version(version: &str) -> Result<()> {
+    if version.trim().is_empty()
+        || version == "."
+        || version == ".."
+        || version.contains(['/', '\\'])
+        || version.chars().any(char::is_control)
+    {
+        anyhow::bail!(
+            "Invalid version '{}': not usable as a directory name",
+            version
+        );
+    }
+    Ok(())
+}
+
+/// One step of a JSONPath
+#[derive(Debug, Clone, PartialEq)]
+enum JsonPathStep {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+/// Parse the JSONPath subset `$`, `.key`, `['key']`, `[0]`, `[*]` and `.*`
+fn parse_json_path(path: &str) -> Result<Vec<JsonPathStep>> {
+    let invalid = || anyhow::anyhow!("Invalid JSONPath '{}'", path);
+    let mut rest = path.strip_prefix('$').ok_or_else(invalid)?;
+    let mut steps = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            let key = &after[..end];
+            steps.push(match key {
+                "" => return Err(invalid()),
+                "*" => JsonPathStep::Wildcard,
+                _ => JsonPathStep::Key(key.to_string()),
+            });
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(invalid)?;
+            let inner = &after[..end];
+            steps.push(if inner == "*" {
+                JsonPathStep::Wildcard
+            } else if let Some(key) = inner
+                .strip_prefix('\'')
+                .and_then(|k| k.strip_suffix('\''))
+                .or_else(|| inner.strip_prefix('"').and_then(|k| k.strip_suffix('"')))
+            {
+                JsonPathStep::Key(key.to_string())
+            } else {
+                JsonPathStep::Index(inner.parse().map_err(|_| invalid())?)
+            });
+            rest = &after[end + 1..];
+        } else {
+            return Err(invalid());
+        }
+    }
+    Ok(steps)
+}
+
+/// Values a parsed JSONPath selects
+fn select_json_path<'v>(value: &'v Value, steps: &[JsonPathStep]) -> Vec<&'v Value> {
+    let mut current = vec![value];
+    for step in steps {
+        current = current
+            .into_iter()
+            .flat_map(|v| -> Vec<&Value> {
+                match (step, v) {
+                    (JsonPathStep::Key(key), Value::Object(map)) => {
+                        map.get(key).into_iter().collect()
+                    }
+                    (JsonPathStep::Index(i), Value::Array(items)) => {
+                        items.get(*i).into_iter().collect()
+                    }
+                    (JsonPathStep::Wildcard, Value::Array(items)) => items.iter().collect(),
+                    (JsonPathStep::Wildcard, Value::Object(map)) => map.values().collect(),
+                    _ => vec![],
+                }
+            })
+            .collect();
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(versions: VersionDiscovery) -> UrlSource {
+        UrlSource {
+            url: "https://dl.example.com/tool/{version}/tool-{os}-{arch}.tar.gz".into(),
+            versions,
+            os: BTreeMap::from([("macos".into(), "darwin".into())]),
+            arch: BTreeMap::from([("x86_64".into(), "amd64".into())]),
+        }
+    }
+
+    #[test]
+    fn test_versions_from_listing() {
+        let source = source(VersionDiscovery::Listing {
+            url: "https://dl.example.com/tool/".into(),
+            regex: r#"href="(\d+\.\d+\.\d+)/""#.into(),
+        });
+        let page = r#"<a href="1.2.0/">1.2.0/</a> <a href="1.10.1/">1.10.1/</a> <a href="1.2.0/">"#;
+        assert_eq!(source.versions_in(page).unwrap(), vec!["1.2.0", "1.10.1"]);
+    }
+
+    #[test]
+    fn test_versions_from_json_path() {
+        let source = source(VersionDiscovery::Json {
+            url: "https://dl.example.com/tool/index.json".into(),
+            path: "$.releases[*]['version']".into(),
+        });
+        let json = r#"{"releases": [{"version": "2.0.0"}, {"version": "1.9.0"}, {"other": 1}]}"#;
+        assert_eq!(source.versions_in(json).unwrap(), vec!["2.0.0", "1.9.0"]);
+
+        assert!(parse_json_path("releases").is_err());
+        assert!(parse_json_path("$.a[").is_err());
+        assert_eq!(
+            parse_json_path("$.a.*[0]").unwrap(),
+            vec![
+                JsonPathStep::Key("a".into()),
+                JsonPathStep::Wildcard,
+                JsonPathStep::Index(0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_versions_that_escape_the_package_dir_are_rejected() {
+        let json_source = source(VersionDiscovery::Json {
+            url: "https://dl.example.com/tool/index.json".into(),
+            path: "$.versions[*]".into(),
+        });
+        for version in ["../../x", "1.0/../..", "..", "1.0\\..\\x", "1.0\n"] {
+            let json = serde_json::json!({ "versions": ["1.0.0", version] }).to_string();
+            let err = json_source.versions_in(&json).unwrap_err();
+            assert!(err.to_string().contains("Invalid version"), "{}", version);
+        }
+
+        // Empty versions are skipped
+        let json = r#"{"versions": ["1.0.0", "", " "]}"#;
+        assert_eq!(json_source.versions_in(json).unwrap(), vec!["1.0.0"]);
+
+        let list = source(VersionDiscovery::List {
+            versions: vec!["../1.0.0".into()],
+        });
+        assert!(list.validate().is_err());
+    }
+
+    #[test]
+    fn test_release_for_platform() {
+        let source = source(VersionDiscovery::List {
+            versions: vec!["1.2.0".into()],
+        });
+        let release = source.release("1.2.0", "macos", "x86_64");
+        assert_eq!(release.tag, "1.2.0");
+        assert!(!release.prerelease);
+        assert_eq!(release.assets[0].name, "tool-darwin-amd64.tar.gz");
+        assert_eq!(
+            release.assets[0].download_url,
+            "https://dl.example.com/tool/1.2.0/tool-darwin-amd64.tar.gz"
+        );
+        assert!(source.release("2.0.0-rc.1", "linux", "aarch64").prerelease);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut source = source(VersionDiscovery::Listing {
+            url: "https://dl.example.com/".into(),
+            regex: r"\d+\.\d+".into(),
+        });
+        assert!(
+            source
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("capture group")
+        );
+        source.url = "https://dl.example.com/tool.tar.gz".into();
+        assert!(
+            source
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("{version}")
+        );
+    }
+}
@@ -3,6 +3,11 @@
 //! This module provides utilities for resolving version constraints
 //! and selecting appropriate releases from a list.
 
+use anyhow::{Context, Result};
+use semver::{BuildMetadata, Prerelease, Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
 use crate::provider::Release;
 
 /// A resolved, normalized version tag.
@@ -55,6 +60,8 @@ impl AsRef<str> for ResolvedVersion {
 pub enum VersionConstraint {
     /// Match exact version (e.g., "v1.2.3")
     Exact(String),
+    /// Highest stable version inside a semver range (e.g., "^1.4", ">=2,<3", "1.x")
+    Range(VersionReq),
     /// Latest stable (non-prerelease) version
     #[default]
     LatestStable,
@@ -62,6 +69,105 @@ pub enum VersionConstraint {
     Latest,
 }
 
+impl VersionConstraint {
+    /// Parse a user version spec.
+    ///
+    /// Specs starting with an operator (`^`, `~`, `>`, `<`, `=`, `*`), containing
+    /// a comma, or using an `x`/`*` wildcard component are semver ranges; anything
+    /// else is an exact tag.
+    pub fn parse(spec: &str) -> Result<Self> {
+        if !Self::is_range(spec) {
+            return Ok(VersionConstraint::Exact(spec.to_string()));
+        }
+        let req = VersionReq::parse(&strip_v_prefixes(spec))
+            .with_context(|| format!("Invalid version range '{}'", spec))?;
+        Ok(VersionConstraint::Range(req))
+    }
+
+    /// Check whether a version spec is a range rather than an exact tag.
+    pub fn is_range(spec: &str) -> bool {
+        let spec = spec.trim();
+        spec.starts_with(['^', '~', '>', '<', '=', '*'])
+            || spec.contains(',')
+            || (spec.contains('.') && spec.split('.').any(|part| matches!(part, "x" | "X" | "*")))
+    }
+}
+
+/// How far an upgrade may move away from the installed version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpgradePolicy {
+    /// Same major and minor version (`1.4.2` -> `1.4.5`)
+    Patch,
+    /// Same major version (`1.4.2` -> `1.6.0`)
+    Minor,
+    /// Any newer version
+    Major,
+}
+
+impl UpgradePolicy {
+    /// Check whether an upgrade from `current` to `candidate` stays within the policy.
+    ///
+    /// Missing components count as zero. Under `Patch` and `Minor`, tags that
+    /// are not versions never qualify.
+    pub fn allows(self, current: &str, candidate: &str) -> bool {
+        let fixed = match self {
+            UpgradePolicy::Patch => 2,
+            UpgradePolicy::Minor => 1,
+            UpgradePolicy::Major => return true,
+        };
+        let (Some((current, _, _)), Some((candidate, _, _))) =
+            (split_tag(current), split_tag(candidate))
+        else {
+            return false;
+        };
+        let component = |numbers: &[u64], i: usize| numbers.get(i).copied().unwrap_or(0);
+        (0..fixed).all(|i| component(&current, i) == component(&candidate, i))
+    }
+}
+
+impl std::fmt::Display for UpgradePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            UpgradePolicy::Patch => "patch",
+            UpgradePolicy::Minor => "minor",
+            UpgradePolicy::Major => "major",
+        })
+    }
+}
+
+impl std::str::FromStr for UpgradePolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "patch" => Ok(UpgradePolicy::Patch),
+            "minor" => Ok(UpgradePolicy::Minor),
+            "major" => Ok(UpgradePolicy::Major),
+            _ => anyhow::bail!(
+                "Unknown upgrade policy '{}', expected patch, minor or major",
+                s
+            ),
+        }
+    }
+}
+
+/// Remove `v` prefixes from the versions of a range (`^v1.4` -> `^1.4`).
+fn strip_v_prefixes(spec: &str) -> String {
+    let chars: Vec<char> = spec.chars().collect();
+    chars
+        .iter()
+        .enumerate()
+        .filter(|&(i, c)| {
+            let is_prefix = matches!(c, 'v' | 'V')
+                && chars.get(i + 1).is_some_and(char::is_ascii_digit)
+                && (i == 0 || !chars[i - 1].is_ascii_alphanumeric());
+            !is_prefix
+        })
+        .map(|(_, c)| *c)
+        .collect()
+}
+
 /// Version resolver - pure functions for version resolution.
 ///
 /// All methods are stateless and operate on slices of releases.
@@ -77,6 +183,7 @@ impl VersionResolver {
     ) -> Option<&'a Release> {
         match constraint {
             VersionConstraint::Exact(version) => Self::find_exact(releases, version),
+            VersionConstraint::Range(req) => Self::find_in_range(releases, req, false),
             VersionConstraint::LatestStable => Self::find_latest_stable(releases),
             VersionConstraint::Latest => Self::find_latest(releases),
         }
@@ -85,12 +192,105 @@ impl VersionResolver {
     /// Find a release with exact version match.
     ///
     /// Supports matching with or without 'v' prefix (e.g., "1.0.0" matches "v1.0.0").
-    pub fn find_exact<'a>(releases: &'a [Release], version: &str) -> Option<&'a Release> {
+    pub fn find_exact<'a>(
+        releases: impl IntoIterator<Item = &'a Release>,
+        version: &str,
+    ) -> Option<&'a Release> {
         releases
-            .iter()
+            .into_iter()
             .find(|r| Self::versions_match(&r.tag, version))
     }
 
+    /// Find the highest release whose tag satisfies a semver range.
+    ///
+    /// Tags that cannot be parsed as versions never match. Releases marked as
+    /// prereleases are skipped unless `include_prerelease` is set.
+    pub fn find_in_range<'a>(
+        releases: impl IntoIterator<Item = &'a Release>,
+        req: &VersionReq,
+        include_prerelease: bool,
+    ) -> Option<&'a Release> {
+        releases
+            .into_iter()
+            .filter(|r| include_prerelease || !r.prerelease)
+            .filter(|r| Self::parse_tag(&r.tag).is_some_and(|v| req.matches(&v)))
+            .max_by(|a, b| Self::compare_releases(a, b))
+    }
+
+    /// Check whether a tag satisfies a range spec.
+    ///
+    /// Returns false for exact specs and for tags that are not versions.
+    pub fn tag_in_range(tag: &str, spec: &str) -> bool {
+        match (VersionConstraint::parse(spec), Self::parse_tag(tag)) {
+            (Ok(VersionConstraint::Range(req)), Some(version)) => req.matches(&version),
+            _ => false,
+        }
+    }
+
+    /// Parse a release tag as a semantic version.
+    ///
+    /// Accepts common tag styles: `v1.2.3`, `1.2`, `release-1.2.3`,
+    /// `tool-v1.2.3`, `1.2.3-rc.1` and `1.2.3rc1`. Missing minor and patch
+    /// numbers are zero, and numbers beyond the patch are ignored.
+    pub fn parse_tag(tag: &str) -> Option<Version> {
+        let (numbers, pre, build) = split_tag(tag)?;
+        let mut version = Version::new(
+            numbers[0],
+            numbers.get(1).copied().unwrap_or(0),
+            numbers.get(2).copied().unwrap_or(0),
+        );
+        if !pre.is_empty() {
+            version.pre = Prerelease::new(&sanitize_identifier(pre)).ok()?;
+        }
+        if !build.is_empty() {
+            version.build = BuildMetadata::new(&sanitize_identifier(build)).ok()?;
+        }
+        Some(version)
+    }
+
+    /// Compare two tags by version.
+    ///
+    /// All numeric components count, so CalVer tags like `2024.01.15.2` order
+    /// correctly, and a prerelease sorts before its release. Returns None when
+    /// either tag is not a version.
+    pub fn compare_tags(a: &str, b: &str) -> Option<Ordering> {
+        let (numbers_a, pre_a, _) = split_tag(a)?;
+        let (numbers_b, pre_b, _) = split_tag(b)?;
+
+        let len = numbers_a.len().max(numbers_b.len());
+        let component = |numbers: &[u64], i: usize| numbers.get(i).copied().unwrap_or(0);
+        let numbers = (0..len)
+            .map(|i| component(&numbers_a, i).cmp(&component(&numbers_b, i)))
+            .find(|o| o.is_ne())
+            .unwrap_or(Ordering::Equal);
+
+        let pre = match (pre_a.is_empty(), pre_b.is_empty()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => match (
+                Prerelease::new(&sanitize_identifier(pre_a)),
+                Prerelease::new(&sanitize_identifier(pre_b)),
+            ) {
+                (Ok(pa), Ok(pb)) => pa.cmp(&pb),
+                _ => pre_a.cmp(pre_b),
+            },
+        };
+        Some(numbers.then(pre))
+    }
+
+    /// Check whether a release is newer than the installed version.
+    pub fn is_newer(releases: &[Release], candidate: &Release, current_version: &str) -> bool {
+        if Self::versions_match(&candidate.tag, current_version) {
+            return false;
+        }
+        match Self::find_exact(releases, current_version) {
+            Some(current) => Self::compare_releases(candidate, current) == Ordering::Greater,
+            None => Self::compare_tags(&candidate.tag, current_version)
+                .is_none_or(|o| o == Ordering::Greater),
+        }
+    }
+
     /// Resolve a user-input version to an official version from releases.
     ///
     /// Returns a `ResolvedVersion` containing the official tag if found,
@@ -110,19 +310,23 @@ impl VersionResolver {
 
     /// Find the latest stable (non-prerelease) release.
     ///
-    /// Uses `published_at` for comparison when available, falls back to version string.
-    pub fn find_latest_stable(releases: &[Release]) -> Option<&Release> {
+    /// Releases are ordered by version, see `compare_releases`.
+    pub fn find_latest_stable<'a>(
+        releases: impl IntoIterator<Item = &'a Release>,
+    ) -> Option<&'a Release> {
         releases
-            .iter()
+            .into_iter()
             .filter(|r| !r.prerelease)
             .max_by(|a, b| Self::compare_releases(a, b))
     }
 
     /// Find the latest release including prereleases.
     ///
-    /// Uses `published_at` for comparison when available, falls back to version string.
-    pub fn find_latest(releases: &[Release]) -> Option<&Release> {
-        releases.iter().max_by(|a, b| Self::compare_releases(a, b))
+    /// Releases are ordered by version, see `compare_releases`.
+    pub fn find_latest<'a>(releases: impl IntoIterator<Item = &'a Release>) -> Option<&'a Release> {
+        releases
+            .into_iter()
+            .max_by(|a, b| Self::compare_releases(a, b))
     }
 
     /// Check if there's a newer version available.
@@ -139,7 +343,7 @@ impl VersionResolver {
             Self::find_latest_stable(releases)
         };
 
-        latest.filter(|r| !Self::versions_match(&r.tag, current_version))
+        latest.filter(|r| Self::is_newer(releases, r, current_version))
     }
 
     /// Check if two version strings match.
@@ -153,17 +357,73 @@ impl VersionResolver {
 
     /// Compare two releases for ordering.
     ///
-    /// Primary sort by `published_at` (descending), fallback to version string.
-    fn compare_releases(a: &Release, b: &Release) -> std::cmp::Ordering {
-        match (&a.published_at, &b.published_at) {
+    /// Releases whose tags parse as versions rank above those that don't and
+    /// are ordered by version (see `compare_tags`). `published_at` breaks ties
+    /// and orders the rest, with the tag string as the last resort.
+    pub fn compare_releases(a: &Release, b: &Release) -> Ordering {
+        let by_version = match (Self::parse_tag(&a.tag), Self::parse_tag(&b.tag)) {
+            (Some(_), Some(_)) => Self::compare_tags(&a.tag, &b.tag).unwrap_or(Ordering::Equal),
+            (Some(_), None) => Ordering::Greater,
+            (None, Some(_)) => Ordering::Less,
+            (None, None) => Ordering::Equal,
+        };
+        let by_date = match (&a.published_at, &b.published_at) {
             (Some(at_a), Some(at_b)) => at_a.cmp(at_b),
-            (Some(_), None) => std::cmp::Ordering::Greater,
-            (None, Some(_)) => std::cmp::Ordering::Less,
-            (None, None) => a.tag.cmp(&b.tag),
-        }
+            (Some(_), None) => Ordering::Greater,
+            (None, Some(_)) => Ordering::Less,
+            (None, None) => Ordering::Equal,
+        };
+        by_version.then(by_date).then_with(|| a.tag.cmp(&b.tag))
     }
 }
 
+/// Split a tag into its numeric components, prerelease and build metadata.
+fn split_tag(tag: &str) -> Option<(Vec<u64>, &str, &str)> {
+    let bytes = tag.as_bytes();
+    let is_boundary = |i: usize| i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+    // Prefer a number at a word boundary or after a `v` prefix, else the first number
+    let start = (0..bytes.len())
+        .find(|&i| {
+            bytes[i].is_ascii_digit()
+                && (is_boundary(i) || (matches!(bytes[i - 1], b'v' | b'V') && is_boundary(i - 1)))
+        })
+        .or_else(|| bytes.iter().position(u8::is_ascii_digit))?;
+
+    let rest = &tag[start..];
+    let core_len = rest
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(rest.len());
+    let (core, suffix) = rest.split_at(core_len);
+    let numbers: Vec<u64> = core
+        .trim_end_matches('.')
+        .split('.')
+        .map(|n| n.parse().ok())
+        .collect::<Option<_>>()?;
+
+    let (pre, build) = match suffix.split_once('+') {
+        Some((pre, build)) => (pre, build),
+        None => (suffix, ""),
+    };
+    Some((numbers, pre.trim_start_matches(['-', '.', '_']), build))
+}
+
+/// Replace characters that are not allowed in semver identifiers.
+fn sanitize_identifier(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .split('.')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -397,14 +657,79 @@ mod tests {
     }
 
     #[test]
-    fn test_compare_releases_by_published_at() {
-        let older = make_release("v1.0.0", Some("2024-01-01T00:00:00Z"), false);
-        let newer = make_release("v0.9.0", Some("2024-02-01T00:00:00Z"), false);
+    fn test_compare_releases_by_version_not_published_at() {
+        let higher = make_release("v2.1.0", Some("2024-01-01T00:00:00Z"), false);
+        let backport = make_release("v1.9.8", Some("2024-02-01T00:00:00Z"), false);
 
-        // v0.9.0 is "newer" by published_at despite lower version number
-        let releases = vec![older, newer];
+        // A backport published later is not the latest release
+        let releases = vec![higher, backport];
         let latest = VersionResolver::find_latest(&releases);
-        assert_eq!(latest.unwrap().tag, "v0.9.0");
+        assert_eq!(latest.unwrap().tag, "v2.1.0");
+        assert!(VersionResolver::check_update(&releases, "v2.1.0", false).is_none());
+    }
+
+    #[test]
+    fn test_compare_releases_published_at_breaks_ties() {
+        let older = make_release("nightly-a", Some("2024-01-01T00:00:00Z"), false);
+        let newer = make_release("nightly-b", Some("2024-02-01T00:00:00Z"), false);
+        let version = make_release("v0.1.0", None, false);
+
+        // Tags that are not versions are ordered by date, below versioned tags
+        let releases = vec![newer.clone(), older.clone()];
+        assert_eq!(
+            VersionResolver::find_latest(&releases).unwrap().tag,
+            "nightly-b"
+        );
+        let releases = vec![newer, older, version];
+        assert_eq!(
+            VersionResolver::find_latest(&releases).unwrap().tag,
+            "v0.1.0"
+        );
+
+        // Same version in two spellings: the later one wins
+        let a = make_release("1.0", Some("2024-01-01T00:00:00Z"), false);
+        let b = make_release("v1.0.0", Some("2024-03-01T00:00:00Z"), false);
+        assert_eq!(
+            VersionResolver::compare_releases(&a, &b),
+            std::cmp::Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_compare_tags() {
+        use std::cmp::Ordering::*;
+        let cmp = |a, b| VersionResolver::compare_tags(a, b);
+
+        assert_eq!(cmp("v1.10.0", "v1.9.0"), Some(Greater));
+        assert_eq!(cmp("v2.0.0-rc.1", "v2.0.0"), Some(Less));
+        assert_eq!(cmp("v2.0.0-rc.2", "v2.0.0-rc.10"), Some(Less));
+        assert_eq!(cmp("2024.01.15.2", "2024.01.15"), Some(Greater));
+        assert_eq!(cmp("24.04", "23.10"), Some(Greater));
+        assert_eq!(cmp("tool-v1.2", "1.2.0"), Some(Equal));
+        assert_eq!(cmp("nightly", "v1.0.0"), None);
+    }
+
+    #[test]
+    fn test_is_newer() {
+        let releases = vec![
+            make_release("v2.1.0", Some("2024-01-01T00:00:00Z"), false),
+            make_release("v1.9.8", Some("2024-02-01T00:00:00Z"), false),
+        ];
+
+        assert!(VersionResolver::is_newer(&releases, &releases[0], "v1.9.8"));
+        assert!(!VersionResolver::is_newer(
+            &releases,
+            &releases[1],
+            "v2.1.0"
+        ));
+        assert!(!VersionResolver::is_newer(&releases, &releases[0], "2.1.0"));
+        // Installed version no longer listed: compare tags
+        assert!(VersionResolver::is_newer(&releases, &releases[0], "v2.0.0"));
+        assert!(!VersionResolver::is_newer(
+            &releases,
+            &releases[0],
+            "v3.0.0"
+        ));
     }
 
     #[test]
@@ -417,4 +742,92 @@ mod tests {
         let latest = VersionResolver::find_latest(&releases);
         assert_eq!(latest.unwrap().tag, "v2.0.0");
     }
+
+    #[test]
+    fn test_parse_tag_styles() {
+        let parse = |tag: &str| VersionResolver::parse_tag(tag).map(|v| v.to_string());
+
+        assert_eq!(parse("v1.2.3").as_deref(), Some("1.2.3"));
+        assert_eq!(parse("1.2").as_deref(), Some("1.2.0"));
+        assert_eq!(parse("release-1.2.3").as_deref(), Some("1.2.3"));
+        assert_eq!(parse("bat-v0.24.0").as_deref(), Some("0.24.0"));
+        assert_eq!(parse("v1.2.3-rc.1").as_deref(), Some("1.2.3-rc.1"));
+        assert_eq!(parse("1.2.3rc1").as_deref(), Some("1.2.3-rc1"));
+        assert_eq!(parse("v1.2.3+build.5").as_deref(), Some("1.2.3+build.5"));
+        assert_eq!(parse("jq-1.7.1").as_deref(), Some("1.7.1"));
+        assert_eq!(parse("2024.01.15").as_deref(), Some("2024.1.15"));
+        assert_eq!(parse("nightly"), None);
+    }
+
+    #[test]
+    fn test_version_constraint_parse() {
+        assert_eq!(
+            VersionConstraint::parse("v1.2.3").unwrap(),
+            VersionConstraint::Exact("v1.2.3".into())
+        );
+        assert_eq!(
+            VersionConstraint::parse("nightly").unwrap(),
+            VersionConstraint::Exact("nightly".into())
+        );
+        for spec in ["^1.4", "~0.9.2", ">=2,<3", "1.x", "^v1.4", "*"] {
+            assert!(
+                matches!(
+                    VersionConstraint::parse(spec).unwrap(),
+                    VersionConstraint::Range(_)
+                ),
+                "{} should be a range",
+                spec
+            );
+        }
+        assert!(VersionConstraint::parse("^one").is_err());
+    }
+
+    #[test]
+    fn test_find_in_range() {
+        let releases = vec![
+            make_release("v2.1.0", Some("2024-03-01T00:00:00Z"), false),
+            make_release("v1.9.8", Some("2024-04-01T00:00:00Z"), false),
+            make_release("v1.5.0-rc.1", Some("2024-02-01T00:00:00Z"), true),
+            make_release("v1.4.2", Some("2024-01-15T00:00:00Z"), false),
+            make_release("v1.4.0", Some("2024-01-01T00:00:00Z"), false),
+            make_release("v0.9.3", Some("2023-06-01T00:00:00Z"), false),
+        ];
+        let find = |spec: &str| match VersionConstraint::parse(spec).unwrap() {
+            VersionConstraint::Range(req) => {
+                VersionResolver::resolve(&releases, &VersionConstraint::Range(req))
+                    .map(|r| r.tag.as_str())
+            }
+            _ => panic!("not a range"),
+        };
+
+        assert_eq!(find("^1.4"), Some("v1.9.8"));
+        assert_eq!(find("~1.4.0"), Some("v1.4.2"));
+        assert_eq!(find(">=2,<3"), Some("v2.1.0"));
+        assert_eq!(find("1.x"), Some("v1.9.8"));
+        assert_eq!(find("~0.9.2"), Some("v0.9.3"));
+        assert_eq!(find("^3"), None);
+
+        assert!(VersionResolver::tag_in_range("v1.9.8", "^1.4"));
+        assert!(!VersionResolver::tag_in_range("v2.1.0", "^1.4"));
+        assert!(!VersionResolver::tag_in_range("v1.4.0", "v1.4.0"));
+    }
+
+    #[test]
+    fn test_upgrade_policy_allows() {
+        assert!(UpgradePolicy::Patch.allows("v1.4.2", "v1.4.9"));
+        assert!(!UpgradePolicy::Patch.allows("v1.4.2", "v1.5.0"));
+        assert!(UpgradePolicy::Minor.allows("v1.4.2", "v1.9.0"));
+        assert!(!UpgradePolicy::Minor.allows("v1.4.2", "v2.0.0"));
+        assert!(UpgradePolicy::Major.allows("v1.4.2", "v3.0.0"));
+        // Missing components count as zero
+        assert!(UpgradePolicy::Patch.allows("v2", "v2.0.1"));
+        // Non-version tags only pass the major policy
+        assert!(!UpgradePolicy::Minor.allows("nightly", "v1.0.0"));
+        assert!(UpgradePolicy::Major.allows("nightly", "v1.0.0"));
+        assert_eq!(
+            "minor".parse::<UpgradePolicy>().unwrap(),
+            UpgradePolicy::Minor
+        );
+        assert!("newest".parse::<UpgradePolicy>().is_err());
+    }
 }
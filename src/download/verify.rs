@@ -0,0 +1,184 @@
+//! SHA-256 verification of downloaded files.
+
+use anyhow::{Context, Result};
+use log::debug;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use crate::provider::ReleaseAsset;
+use crate::runtime::Runtime;
+
+use super::Downloader;
+
+/// Compute the hex-encoded SHA-256 digest of a file.
+pub fn sha256_file<R: Runtime>(runtime: &R, path: &Path) -> Result<String> {
+    let mut reader = runtime
+        .open(path)
+        .with_context(|| format!("Failed to open {:?} for hashing", path))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .with_context(|| format!("Failed to read {:?}", path))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Downloader that only fetches known URLs and checks their SHA-256 digests.
+///
+/// Used for locked installs: every URL must have an expected digest, and a
+/// download whose content differs is deleted and reported as an error.
+pub struct VerifyingDownloader<D: Downloader> {
+    inner: D,
+    /// Expected digests keyed by download URL
+    digests: HashMap<String, String>,
+}
+
+impl<D: Downloader> VerifyingDownloader<D> {
+    pub fn new(inner: D, digests: HashMap<String, String>) -> Self {
+        Self { inner, digests }
+    }
+
+    fn expected(&self, url: &str) -> Result<&str> {
+        self.digests
+            .get(url)
+            .map(String::as_str)
+            .with_context(|| format!("{} is not in the lockfile", url))
+    }
+
+    fn verify<R: Runtime>(runtime: &R, url: &str, dest: &Path, expected: &str) -> Result<()> {
+        let actual = sha256_file(runtime, dest)?;
+        if !actual.eq_ignore_ascii_case(expected) {
+            let _ = runtime.remove_file(dest);
+            anyhow::bail!(
+                "SHA-256 mismatch for {}: expected {}, got {}",
+                url,
+                expected,
+                actual
+            );
+        }
+        debug!("Verified SHA-256 of {}", url);
+        Ok(())
+    }
+}
+
+impl<D: Downloader> Downloader for VerifyingDownloader<D> {
+    fn download<'a, R: Runtime + 'a>(
+        &'a self,
+        runtime: &'a R,
+        url: &'a str,
+        dest: &'a Path,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let expected = self.expected(url)?;
+            self.inner.download(runtime, url, dest).await?;
+            Self::verify(runtime, url, dest, expected)
+        })
+    }
+
+    fn download_asset<'a, R: Runtime + 'a>(
+        &'a self,
+        runtime: &'a R,
+        asset: &'a ReleaseAsset,
+        dest: &'a Path,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            let expected = self.expected(&asset.download_url)?;
+            self.inner.download_asset(runtime, asset, dest).await?;
+            Self::verify(runtime, &asset.download_url, dest, expected)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::download::mock::MockDownloader;
+    use crate::runtime::MockRuntime;
+    use mockall::predicate::*;
+
+    // SHA-256 of "data"
+    const DATA_SHA256: &str = "3a6eb0790f39ac87c94f3856b2dd2c5d110e6811602261a9a923d3bb23adc8b7";
+
+    fn runtime_with_file(content: &'static str) -> MockRuntime {
+        let mut runtime = MockRuntime::new();
+        runtime
+            .expect_open()
+            .returning(move |_| Ok(Box::new(std::io::Cursor::new(content.as_bytes()))));
+        runtime
+    }
+
+    #[test]
+    fn test_sha256_file() {
+        let runtime = runtime_with_file("data");
+        assert_eq!(
+            sha256_file(&runtime, Path::new("/tmp/file")).unwrap(),
+            DATA_SHA256
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verifying_downloader_accepts_matching_digest() {
+        let runtime = runtime_with_file("data");
+        let downloader = VerifyingDownloader::new(
+            MockDownloader::new(),
+            HashMap::from([("https://example.com/a".into(), DATA_SHA256.into())]),
+        );
+
+        downloader
+            .download(&runtime, "https://example.com/a", Path::new("/tmp/a"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_verifying_downloader_rejects_other_content() {
+        // --- Setup ---
+        let mut runtime = runtime_with_file("tampered");
+        runtime
+            .expect_remove_file()
+            .with(eq(Path::new("/tmp/a").to_path_buf()))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let downloader = VerifyingDownloader::new(
+            MockDownloader::new(),
+            HashMap::from([("https://example.com/a".into(), DATA_SHA256.into())]),
+        );
+        let asset = ReleaseAsset {
+            name: "a".into(),
+            size: 4,
+            download_url: "https://example.com/a".into(),
+            api_url: None,
+            local: false,
+        };
+
+        // --- Execute ---
+        let err = downloader
+            .download_asset(&runtime, &asset, Path::new("/tmp/a"))
+            .await
+            .unwrap_err();
+
+        // --- Verify ---
+        assert!(err.to_string().contains("SHA-256 mismatch"));
+    }
+
+    #[tokio::test]
+    async fn test_verifying_downloader_rejects_unknown_url() {
+        let runtime = MockRuntime::new();
+        let downloader = VerifyingDownloader::new(MockDownloader::new(), HashMap::new());
+
+        let err = downloader
+            .download(&runtime, "https://example.com/b", Path::new("/tmp/b"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not in the lockfile"));
+    }
+}
@@ -0,0 +1,266 @@
+//! Per-host authentication for HTTP requests.
+//!
+//! Tokens are attached per request based on the request URL's origin (scheme,
+//! host and port), never as default headers, so a token only reaches the
+//! origin it belongs to. Tokens are never sent over plain HTTP, except to
+//! loopback addresses. Redirects to a different origin (e.g., release assets
+//! served from a CDN) are followed without the `Authorization` header, which
+//! reqwest strips when the host or port changes.
+
+use log::debug;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+/// Source of access tokens, looked up by request origin.
+pub trait TokenSource: Send + Sync {
+    /// Return the token for an origin, if one is available.
+    fn token(&self, origin: &Origin) -> Option<String>;
+}
+
+/// A fixed origin → token map, matched on the full origin.
+impl TokenSource for HashMap<Origin, String> {
+    fn token(&self, origin: &Origin) -> Option<String> {
+        self.get(origin).cloned()
+    }
+}
+
+/// Scheme, normalized host and port of a URL.
+///
+/// The port is `None` when it is the scheme's default, so `https://h` and
+/// `https://h:443` are the same origin.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Origin {
+    pub scheme: String,
+    pub host: String,
+    pub port: Option<u16>,
+}
+
+impl Origin {
+    /// Parse the origin of a URL. Returns `None` for invalid or host-less URLs.
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = reqwest::Url::parse(url).ok()?;
+        Some(Self {
+            scheme: url.scheme().to_string(),
+            host: normalize_host(url.host_str()?),
+            port: url.port(),
+        })
+    }
+
+    /// Host and non-default port, the key for per-host credentials
+    /// (e.g., `github.example.com` or `github.example.com:8443`).
+    pub fn authority(&self) -> String {
+        match self.port {
+            Some(port) => format!("{}:{}", self.host, port),
+            None => self.host.clone(),
+        }
+    }
+
+    /// Whether a token may be sent to this origin: HTTPS, or a loopback address.
+    pub fn is_secure(&self) -> bool {
+        self.scheme == "https"
+            || self.host == "localhost"
+            || self
+                .host
+                .trim_matches(['[', ']'])
+                .parse::<IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback())
+    }
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}://{}", self.scheme, self.host)?;
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+        Ok(())
+    }
+}
+
+/// Normalize a host name for credential lookup.
+///
+/// Host names are case-insensitive. `api.github.com` and `github.com` are
+/// different hosts: a token for the API is not sent to release downloads.
+pub fn normalize_host(host: &str) -> String {
+    host.to_lowercase()
+}
+
+/// Credentials attached to outgoing requests, resolved per origin.
+///
+/// Tokens are resolved lazily on the first request to an origin and cached,
+/// so expensive sources (like credential helpers) run at most once per origin.
+#[derive(Clone, Default)]
+pub struct Credentials {
+    source: Option<Arc<dyn TokenSource>>,
+    cache: Arc<Mutex<HashMap<Origin, Option<String>>>>,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("has_source", &self.source.is_some())
+            .finish()
+    }
+}
+
+impl Credentials {
+    /// Credentials backed by a token source.
+    pub fn new(source: impl TokenSource + 'static) -> Self {
+        Self {
+            source: Some(Arc::new(source)),
+            cache: Arc::default(),
+        }
+    }
+
+    /// Credentials with a single token for the origin of a URL.
+    pub fn for_origin(url: &str, token: impl Into<String>) -> Self {
+        let origins = Origin::parse(url)
+            .map(|origin| HashMap::from([(origin, token.into())]))
+            .unwrap_or_default();
+        Self::new(origins)
+    }
+
+    /// Find the token to send with a request to the given URL.
+    pub fn token_for_url(&self, url: &str) -> Option<String> {
+        let source = self.source.as_ref()?;
+        let origin = Origin::parse(url)?;
+        if !origin.is_secure() {
+            return None;
+        }
+
+        let mut cache = self.cache.lock().unwrap();
+        cache
+            .entry(origin)
+            .or_insert_with_key(|origin| {
+                let token = source.token(origin);
+                if token.is_some() {
+                    debug!("Using token for {}", origin);
+                }
+                token
+            })
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_normalize_host() {
+        assert_eq!(normalize_host("API.GitHub.com"), "api.github.com");
+        assert_eq!(normalize_host("gitlab.example.com"), "gitlab.example.com");
+    }
+
+    #[test]
+    fn test_origin_parse() {
+        let origin = Origin::parse("https://API.github.com:443/repos/o/r").unwrap();
+        assert_eq!(origin.host, "api.github.com");
+        assert_eq!(origin.port, None);
+        assert_eq!(origin.authority(), "api.github.com");
+        assert_eq!(origin.to_string(), "https://api.github.com");
+
+        let origin = Origin::parse("http://127.0.0.1:8080/x").unwrap();
+        assert_eq!(origin.port, Some(8080));
+        assert_eq!(origin.authority(), "127.0.0.1:8080");
+        assert!(Origin::parse("not a url").is_none());
+    }
+
+    #[test]
+    fn test_origin_is_secure() {
+        let secure = |url: &str| Origin::parse(url).unwrap().is_secure();
+        assert!(secure("https://github.com/"));
+        assert!(secure("http://127.0.0.1:8080/"));
+        assert!(secure("http://localhost/"));
+        assert!(secure("http://[::1]:8080/"));
+        assert!(!secure("http://github.com/"));
+        assert!(!secure("http://10.0.0.1/"));
+    }
+
+    #[test]
+    fn test_token_for_url_matches_full_origin() {
+        let credentials = Credentials::for_origin("https://api.github.com", "secret");
+
+        assert_eq!(
+            credentials
+                .token_for_url("https://api.github.com/repos/o/r")
+                .as_deref(),
+            Some("secret")
+        );
+        // Release downloads live on github.com, a different host
+        assert_eq!(
+            credentials.token_for_url("https://github.com/o/r/releases/download/v1/t.tar.gz"),
+            None
+        );
+        assert_eq!(
+            credentials.token_for_url("https://api.github.com:8443/repos/o/r"),
+            None
+        );
+        assert_eq!(
+            credentials.token_for_url("https://gitlab.com/api/v4/projects"),
+            None
+        );
+        assert_eq!(credentials.token_for_url("not a url"), None);
+    }
+
+    #[test]
+    fn test_token_never_sent_over_plain_http() {
+        struct Always;
+        impl TokenSource for Always {
+            fn token(&self, _origin: &Origin) -> Option<String> {
+                Some("t".into())
+            }
+        }
+        let credentials = Credentials::new(Always);
+
+        assert_eq!(
+            credentials.token_for_url("http://github.example.com/"),
+            None
+        );
+        assert_eq!(
+            credentials
+                .token_for_url("https://github.example.com/")
+                .as_deref(),
+            Some("t")
+        );
+        assert_eq!(
+            credentials
+                .token_for_url("http://127.0.0.1:8080/")
+                .as_deref(),
+            Some("t")
+        );
+    }
+
+    #[test]
+    fn test_no_credentials() {
+        let credentials = Credentials::default();
+        assert_eq!(credentials.token_for_url("https://api.github.com/"), None);
+    }
+
+    #[test]
+    fn test_token_source_is_cached_per_origin() {
+        struct Counting(Arc<AtomicUsize>);
+        impl TokenSource for Counting {
+            fn token(&self, _origin: &Origin) -> Option<String> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Some("t".into())
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let credentials = Credentials::new(Counting(Arc::clone(&calls)));
+
+        credentials.token_for_url("https://api.github.com/a");
+        credentials
+            .clone()
+            .token_for_url("https://API.github.com:443/b");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Another host, or the same host on another port, is a different origin
+        credentials.token_for_url("https://github.com/c");
+        credentials.token_for_url("https://api.github.com:8443/d");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+}
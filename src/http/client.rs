@@ -2,22 +2,47 @@
 
 use anyhow::{Context, Result};
 use log::{debug, warn};
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 use serde::de::DeserializeOwned;
 use std::io::Write;
 
+use super::auth::Credentials;
 use super::retry::{MAX_RETRIES, NonRetryableError, RETRY_DELAY_MS, check_retryable};
 
 /// HTTP client with built-in retry logic for network operations.
 #[derive(Clone)]
 pub struct HttpClient {
     client: Client,
+    credentials: Credentials,
 }
 
 impl HttpClient {
     /// Creates a new HTTP client wrapping the given reqwest Client.
     pub fn new(client: Client) -> Self {
-        Self { client }
+        Self {
+            client,
+            credentials: Credentials::default(),
+        }
+    }
+
+    /// Attach per-host credentials to requests made by this client.
+    pub fn with_credentials(mut self, credentials: Credentials) -> Self {
+        self.credentials = credentials;
+        self
+    }
+
+    /// Starts a GET request, authenticated if a token is known for the URL's host.
+    pub fn get(&self, url: &str) -> RequestBuilder {
+        let request = self.client.get(url);
+        match self.credentials.token_for_url(url) {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Returns true if requests to the URL would carry a token.
+    pub fn has_token_for(&self, url: &str) -> bool {
+        self.credentials.token_for_url(url).is_some()
     }
 
     /// Returns a reference to the underlying reqwest Client.
@@ -33,7 +58,6 @@ impl HttpClient {
 
         self.with_retry("GET JSON", || async {
             let response = self
-                .client
                 .get(url)
                 .send()
                 .await
@@ -51,6 +75,29 @@ impl HttpClient {
         .await
     }
 
+    /// Performs a GET request and returns the response body as text.
+    /// Automatically retries on transient errors.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_text(&self, url: &str) -> Result<String> {
+        debug!("GET text from {}...", url);
+
+        self.with_retry("GET text", || async {
+            let response = self
+                .get(url)
+                .send()
+                .await
+                .context("Failed to send request")?;
+
+            let response = response.error_for_status().map_err(check_retryable)?;
+
+            response
+                .text()
+                .await
+                .context("Failed to read response body")
+        })
+        .await
+    }
+
     /// Performs a GET request with query parameters and deserializes the JSON response.
     /// Automatically retries on transient errors.
     #[tracing::instrument(skip(self, query))]
@@ -63,7 +110,6 @@ impl HttpClient {
 
         self.with_retry("GET JSON with query", || async {
             let response = self
-                .client
                 .get(url)
                 .query(query)
                 .send()
@@ -87,6 +133,22 @@ impl HttpClient {
     /// Uses a writer function to allow for custom file creation (e.g., via Runtime).
     #[tracing::instrument(skip(self, create_writer))]
     pub async fn download_file<W, F>(&self, url: &str, create_writer: F) -> Result<u64>
+    where
+        W: Write,
+        F: Fn() -> Result<W>,
+    {
+        self.download_file_as(url, None, create_writer).await
+    }
+
+    /// Downloads a file like [`download_file`](Self::download_file), sending the given
+    /// `Accept` header (e.g., `application/octet-stream` for GitHub API asset endpoints).
+    #[tracing::instrument(skip(self, create_writer))]
+    pub async fn download_file_as<W, F>(
+        &self,
+        url: &str,
+        accept: Option<&str>,
+        create_writer: F,
+    ) -> Result<u64>
     where
         W: Write,
         F: Fn() -> Result<W>,
@@ -96,7 +158,7 @@ impl HttpClient {
         let mut last_error = None;
 
         for attempt in 1..=MAX_RETRIES {
-            match self.download_file_once(url, &create_writer).await {
+            match self.download_file_once(url, accept, &create_writer).await {
                 Ok(bytes) => return Ok(bytes),
                 Err(e) => {
                     // Check if this is a non-retryable error
@@ -123,14 +185,21 @@ impl HttpClient {
     }
 
     /// Single download attempt without retry.
-    async fn download_file_once<W, F>(&self, url: &str, create_writer: &F) -> Result<u64>
+    async fn download_file_once<W, F>(
+        &self,
+        url: &str,
+        accept: Option<&str>,
+        create_writer: &F,
+    ) -> Result<u64>
     where
         W: Write,
         F: Fn() -> Result<W>,
     {
-        let response = self
-            .client
-            .get(url)
+        let mut request = self.get(url);
+        if let Some(accept) = accept {
+            request = request.header(reqwest::header::ACCEPT, accept);
+        }
+        let response = request
             .send()
             .await
             .context("Failed to start download request")?;
@@ -440,4 +509,112 @@ mod tests {
             MAX_RETRIES
         );
     }
+
+    #[tokio::test]
+    async fn test_get_json_sends_token_for_matching_host() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let mock = server
+            .mock("GET", "/test")
+            .match_header("authorization", "Bearer host_token")
+            .with_status(200)
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let client = HttpClient::new(Client::new())
+            .with_credentials(Credentials::for_origin(&url, "host_token"));
+        let _: serde_json::Value = client.get_json(&format!("{}/test", url)).await.unwrap();
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_download_omits_token_for_other_hosts() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let mock = server
+            .mock("GET", "/file")
+            .match_header("authorization", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_body("data")
+            .create_async()
+            .await;
+
+        let client = HttpClient::new(Client::new()).with_credentials(Credentials::for_origin(
+            "https://api.github.com",
+            "github_token",
+        ));
+        let bytes = client
+            .download_file(&format!("{}/file", url), || Ok(Vec::new()))
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(bytes, 4);
+    }
+
+    #[tokio::test]
+    async fn test_download_redirect_to_other_origin_drops_token() {
+        // API server redirects the asset to a separate "CDN" server
+        let mut api = mockito::Server::new_async().await;
+        let mut cdn = mockito::Server::new_async().await;
+
+        let redirect = api
+            .mock("GET", "/asset")
+            .match_header("authorization", "Bearer host_token")
+            .with_status(302)
+            .with_header("location", &format!("{}/blob", cdn.url()))
+            .create_async()
+            .await;
+        let blob = cdn
+            .mock("GET", "/blob")
+            .match_header("authorization", mockito::Matcher::Missing)
+            .with_status(200)
+            .with_body("data")
+            .create_async()
+            .await;
+
+        let client = HttpClient::new(Client::new())
+            .with_credentials(Credentials::for_origin(&api.url(), "host_token"));
+        let bytes = client
+            .download_file(&format!("{}/asset", api.url()), || Ok(Vec::new()))
+            .await
+            .unwrap();
+
+        redirect.assert_async().await;
+        blob.assert_async().await;
+        assert_eq!(bytes, 4);
+    }
+
+    #[tokio::test]
+    async fn test_download_redirect_within_origin_keeps_token() {
+        let mut server = mockito::Server::new_async().await;
+
+        let redirect = server
+            .mock("GET", "/asset")
+            .with_status(302)
+            .with_header("location", "/blob")
+            .create_async()
+            .await;
+        let blob = server
+            .mock("GET", "/blob")
+            .match_header("authorization", "Bearer host_token")
+            .with_status(200)
+            .with_body("data")
+            .create_async()
+            .await;
+
+        let client = HttpClient::new(Client::new())
+            .with_credentials(Credentials::for_origin(&server.url(), "host_token"));
+        client
+            .download_file(&format!("{}/asset", server.url()), || Ok(Vec::new()))
+            .await
+            .unwrap();
+
+        redirect.assert_async().await;
+        blob.assert_async().await;
+    }
 }
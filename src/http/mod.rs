@@ -1,7 +1,13 @@
-//! HTTP client module with retry logic and error handling.
+//! HTTP client module with retry logic and error handling, and a minimal
+//! server for release mirrors.
 
+mod auth;
 mod client;
 mod retry;
+pub mod server;
+mod settings;
 
+pub use auth::{Credentials, Origin, TokenSource, normalize_host};
 pub use client::HttpClient;
 pub use retry::{MAX_RETRIES, NonRetryableError, RETRY_DELAY_MS, check_retryable, classify_error};
+pub use settings::HttpSettings;
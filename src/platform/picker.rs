@@ -113,7 +113,7 @@ impl AssetPicker for DefaultAssetPicker {
         }
 
         // Sort by score (descending)
-        candidates.sort_by(|a, b| self.score_asset(&b.name).cmp(&self.score_asset(&a.name)));
+        candidates.sort_by_key(|a| std::cmp::Reverse(self.score_asset(&a.name)));
 
         candidates.into_iter().next()
     }
@@ -199,6 +199,8 @@ mod tests {
                 name: name.to_string(),
                 size: 1000,
                 download_url: format!("https://example.com/{}", name),
+                api_url: None,
+                local: false,
             })
             .collect()
     }
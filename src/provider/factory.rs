@@ -8,9 +8,10 @@ use std::sync::Arc;
 
 use anyhow::{Result, anyhow};
 
-use super::{Provider, ProviderKind, RepoId, create_provider};
-use crate::domain::model::Meta;
+use super::{Provider, ProviderKind, RepoId, UrlProvider, create_provider};
+use crate::domain::model::{Meta, UrlSource};
 use crate::http::HttpClient;
+use crate::runtime::Runtime;
 
 /// Package specification for identifying a package and its provider.
 /// Format: "owner/repo" or "owner/repo@version"
@@ -103,15 +104,22 @@ impl PackageSpec {
 /// - PackageSpec (for new installations)
 /// - Meta (for updates/upgrades of existing packages)
 pub struct ProviderFactory {
+    /// Filesystem access for `file://` release trees
+    runtime: Arc<dyn Runtime>,
     http_client: HttpClient,
     /// Default API URL for GitHub (can be overridden for GitHub Enterprise)
     default_github_api_url: String,
 }
 
 impl ProviderFactory {
-    /// Create a new factory with the given HTTP client and default GitHub API URL.
-    pub fn new(http_client: HttpClient, default_github_api_url: &str) -> Self {
+    /// Create a new factory with the given runtime, HTTP client and default GitHub API URL.
+    pub fn new(
+        runtime: Arc<dyn Runtime>,
+        http_client: HttpClient,
+        default_github_api_url: &str,
+    ) -> Self {
         Self {
+            runtime,
             http_client,
             default_github_api_url: default_github_api_url.to_string(),
         }
@@ -123,12 +131,18 @@ impl ProviderFactory {
             ProviderKind::GitHub => &self.default_github_api_url,
             ProviderKind::GitLab => "https://gitlab.com/api/v4",
             ProviderKind::Gitee => "https://gitee.com/api/v5",
+            ProviderKind::Url | ProviderKind::File => "",
         }
     }
 
     /// Create a provider for the given kind and API URL.
     pub fn create(&self, kind: ProviderKind, api_url: &str) -> Arc<dyn Provider> {
-        create_provider(self.http_client.clone(), kind, api_url)
+        create_provider(
+            Arc::clone(&self.runtime),
+            self.http_client.clone(),
+            kind,
+            api_url,
+        )
     }
 
     /// Create a provider from installed package metadata.
@@ -136,26 +150,40 @@ impl ProviderFactory {
     /// The provider kind is inferred from the stored API URL, and the
     /// exact API URL from meta is used (for GitHub Enterprise, etc.).
     pub fn provider_for_meta(&self, meta: &Meta) -> Arc<dyn Provider> {
+        if let Some(source) = &meta.url_source {
+            return self.url_provider(source);
+        }
         let kind = Self::infer_provider_kind(&meta.api_url);
         self.create(kind, &meta.api_url)
     }
 
+    /// Create a provider for releases served from plain HTTPS.
+    pub fn url_provider(&self, source: &UrlSource) -> Arc<dyn Provider> {
+        Arc::new(UrlProvider::new(self.http_client.clone(), source.clone()))
+    }
+
     /// Create the default provider (GitHub with configured API URL).
+    ///
+    /// A `file://` API URL selects the local filesystem provider instead.
     pub fn default_provider(&self) -> Arc<dyn Provider> {
-        self.create(
-            ProviderKind::GitHub,
-            self.default_api_url(ProviderKind::GitHub),
-        )
+        let api_url = self.default_api_url(ProviderKind::GitHub);
+        match Self::infer_provider_kind(api_url) {
+            ProviderKind::File => self.create(ProviderKind::File, api_url),
+            _ => self.create(ProviderKind::GitHub, api_url),
+        }
     }
 
     /// Infer provider kind from an API URL.
     ///
+    /// - `file://` URLs -> File
     /// - URLs containing "gitlab" -> GitLab
     /// - URLs containing "gitee" -> Gitee
     /// - Otherwise -> GitHub (default, includes github.com and GitHub Enterprise)
     pub fn infer_provider_kind(api_url: &str) -> ProviderKind {
         let url_lower = api_url.to_lowercase();
-        if url_lower.contains("gitlab") {
+        if url_lower.starts_with("file://") {
+            ProviderKind::File
+        } else if url_lower.contains("gitlab") {
             ProviderKind::GitLab
         } else if url_lower.contains("gitee") {
             ProviderKind::Gitee
@@ -168,10 +196,15 @@ impl ProviderFactory {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runtime::MockRuntime;
 
     fn make_test_factory() -> ProviderFactory {
         let http_client = HttpClient::new(reqwest::Client::new());
-        ProviderFactory::new(http_client, "https://api.github.com")
+        ProviderFactory::new(
+            Arc::new(MockRuntime::new()),
+            http_client,
+            "https://api.github.com",
+        )
     }
 
     #[test]
@@ -182,6 +215,19 @@ mod tests {
         assert_eq!(provider.api_url(), "https://api.github.com");
     }
 
+    #[test]
+    fn test_default_provider_for_file_url() {
+        let http_client = HttpClient::new(reqwest::Client::new());
+        let factory = ProviderFactory::new(
+            Arc::new(MockRuntime::new()),
+            http_client,
+            "file:///srv/releases",
+        );
+        let provider = factory.default_provider();
+        assert_eq!(provider.kind(), ProviderKind::File);
+        assert_eq!(provider.api_url(), "file:///srv/releases");
+    }
+
     #[test]
     fn test_from_meta() {
         let factory = make_test_factory();
@@ -224,6 +270,10 @@ mod tests {
             ProviderFactory::infer_provider_kind("https://gitee.com/api/v5"),
             ProviderKind::Gitee
         );
+        assert_eq!(
+            ProviderFactory::infer_provider_kind("file:///srv/releases"),
+            ProviderKind::File
+        );
         assert_eq!(
             ProviderFactory::infer_provider_kind("https://unknown.com/api"),
             ProviderKind::GitHub
@@ -0,0 +1,318 @@
+//! Local filesystem provider for air-gapped installs.
+//!
+//! Releases are read from a directory tree `<root>/<owner>/<repo>/<tag>/<assets>`
+//! addressed by a `file://` URL. A repository with a mirror index (written by
+//! `ghri mirror`) is served from the index instead of directory listings.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use log::debug;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::domain::model::{MIRROR_INDEX, MirrorIndex, ReleaseAsset, VersionResolver};
+use crate::download::local_file_path;
+use crate::runtime::Runtime;
+
+use super::{Provider, ProviderKind, Release, RepoId, RepoMetadata};
+
+/// Provider that synthesises releases from directory listings.
+pub struct FileProvider {
+    runtime: Arc<dyn Runtime>,
+    api_url: String,
+}
+
+impl FileProvider {
+    /// Create a provider for a `file://` release tree.
+    pub fn new(runtime: Arc<dyn Runtime>, api_url: &str) -> Self {
+        Self {
+            runtime,
+            api_url: api_url.to_string(),
+        }
+    }
+
+    /// Directory holding the releases of a repository.
+    fn repo_dir(&self, repo: &RepoId, api_url: &str) -> Result<PathBuf> {
+        let root = local_file_path(api_url)?
+            .with_context(|| format!("{} is not a file:// URL", api_url))?;
+        let dir = root.join(&repo.owner).join(&repo.repo);
+        if !self.runtime.is_dir(&dir) {
+            anyhow::bail!("{} not found in {}", repo, root.display());
+        }
+        Ok(dir)
+    }
+
+    /// Visible entries of a directory, sorted by name.
+    fn entries(&self, dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+        let mut entries = Vec::new();
+        for path in self
+            .runtime
+            .read_dir(dir)
+            .with_context(|| format!("Failed to read {:?}", dir))?
+        {
+            let Some(name) = path.file_name().map(|n| n.to_string_lossy().into_owned()) else {
+                continue;
+            };
+            if !name.starts_with('.') {
+                entries.push((name, path));
+            }
+        }
+        entries.sort();
+        Ok(entries)
+    }
+
+    /// Mirror index of a repository directory, if there is one
+    fn mirror_index(&self, dir: &Path) -> Result<Option<MirrorIndex>> {
+        let path = dir.join(MIRROR_INDEX);
+        if !self.runtime.exists(&path) {
+            return Ok(None);
+        }
+        let content = self
+            .runtime
+            .read_to_string(&path)
+            .with_context(|| format!("Failed to read {:?}", path))?;
+        Ok(Some(MirrorIndex::parse(&content)?))
+    }
+
+    fn file_url(path: &Path) -> Result<String> {
+        Ok(reqwest::Url::from_file_path(path)
+            .map_err(|_| anyhow::anyhow!("Cannot build a file URL for {:?}", path))?
+            .to_string())
+    }
+
+    /// Releases recorded in a mirror index, with their files in `dir`
+    fn mirrored_releases(index: MirrorIndex, dir: &Path) -> Result<Vec<Release>> {
+        let mut releases = Vec::new();
+        for mirrored in index.releases {
+            let tag_dir = dir.join(&mirrored.tag);
+            let mut assets = Vec::new();
+            for file in mirrored.files {
+                assets.push(ReleaseAsset {
+                    download_url: Self::file_url(&tag_dir.join(&file.name))?,
+                    name: file.name,
+                    size: file.size,
+                    api_url: None,
+                    local: true,
+                });
+            }
+            releases.push(Release {
+                tag: mirrored.tag,
+                name: mirrored.name,
+                published_at: mirrored.published_at,
+                prerelease: mirrored.prerelease,
+                assets,
+                ..Default::default()
+            });
+        }
+        Ok(releases)
+    }
+
+    fn release(&self, tag: String, dir: &Path) -> Result<Release> {
+        let mut assets = Vec::new();
+        for (name, path) in self.entries(dir)? {
+            if self.runtime.is_dir(&path) {
+                continue;
+            }
+            assets.push(ReleaseAsset {
+                name,
+                size: self.runtime.file_size(&path)?,
+                download_url: Self::file_url(&path)?,
+                api_url: None,
+                local: true,
+            });
+        }
+        Ok(Release {
+            prerelease: VersionResolver::parse_tag(&tag).is_some_and(|v| !v.pre.is_empty()),
+            tag,
+            assets,
+            ..Default::default()
+        })
+    }
+}
+
+#[async_trait]
+impl Provider for FileProvider {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::File
+    }
+
+    fn api_url(&self) -> &str {
+        &self.api_url
+    }
+
+    async fn get_repo_metadata(&self, repo: &RepoId) -> Result<RepoMetadata> {
+        self.get_repo_metadata_at(repo, &self.api_url).await
+    }
+
+    async fn get_releases(&self, repo: &RepoId) -> Result<Vec<Release>> {
+        self.get_releases_at(repo, &self.api_url).await
+    }
+
+    /// Repository info comes from a mirror index; a plain directory has none.
+    async fn get_repo_metadata_at(&self, repo: &RepoId, api_url: &str) -> Result<RepoMetadata> {
+        let dir = self.repo_dir(repo, api_url)?;
+        Ok(match self.mirror_index(&dir)? {
+            Some(index) => RepoMetadata {
+                description: index.description,
+                homepage: index.homepage,
+                license: index.license,
+                updated_at: index.updated_at,
+            },
+            None => RepoMetadata::default(),
+        })
+    }
+
+    async fn get_releases_at(&self, repo: &RepoId, api_url: &str) -> Result<Vec<Release>> {
+        let dir = self.repo_dir(repo, api_url)?;
+        if let Some(index) = self.mirror_index(&dir)? {
+            debug!("Reading releases of {} from the mirror index...", repo);
+            return Self::mirrored_releases(index, &dir);
+        }
+        debug!("Listing releases of {} in {:?}...", repo, dir);
+        let mut releases = Vec::new();
+        for (tag, path) in self.entries(&dir)? {
+            if self.runtime.is_dir(&path) {
+                releases.push(self.release(tag, &path)?);
+            }
+        }
+        Ok(releases)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::MockRuntime;
+    use mockall::predicate::eq;
+
+    const ROOT: &str = "/srv/releases";
+    const API_URL: &str = "file:///srv/releases/";
+
+    fn repo_dir() -> PathBuf {
+        PathBuf::from(ROOT).join("owner/tool")
+    }
+
+    /// Runtime where `dirs` are directories and `files` are files with their sizes
+    fn runtime_with_tree(dirs: &[PathBuf], files: &[(PathBuf, u64)]) -> MockRuntime {
+        let mut runtime = MockRuntime::new();
+        let is_dir: Vec<PathBuf> = dirs.to_vec();
+        runtime
+            .expect_is_dir()
+            .returning(move |path| is_dir.iter().any(|d| d == path));
+        let entries: Vec<PathBuf> = dirs
+            .iter()
+            .cloned()
+            .chain(files.iter().map(|(path, _)| path.clone()))
+            .collect();
+        runtime.expect_read_dir().returning(move |dir| {
+            Ok(entries
+                .iter()
+                .filter(|path| path.parent() == Some(dir))
+                .cloned()
+                .collect())
+        });
+        let sizes = files.to_vec();
+        runtime
+            .expect_file_size()
+            .returning(move |path| Ok(sizes.iter().find(|(p, _)| p == path).unwrap().1));
+        runtime
+    }
+
+    #[tokio::test]
+    async fn test_file_provider_lists_releases() {
+        // --- Setup ---
+        let dir = repo_dir();
+        let mut runtime = runtime_with_tree(
+            &[dir.clone(), dir.join("v1.0.0"), dir.join("v1.1.0-rc.1")],
+            &[
+                (dir.join("v1.0.0/tool-linux.tar.gz"), 4),
+                (dir.join("v1.0.0/.DS_Store"), 0),
+                (dir.join("README"), 13),
+            ],
+        );
+        runtime
+            .expect_exists()
+            .with(eq(dir.join(MIRROR_INDEX)))
+            .returning(|_| false);
+        let provider = FileProvider::new(Arc::new(runtime), API_URL);
+
+        // --- Execute ---
+        let releases = provider
+            .get_releases(&"owner/tool".parse().unwrap())
+            .await
+            .unwrap();
+
+        // --- Verify ---
+        assert_eq!(provider.kind(), ProviderKind::File);
+        assert_eq!(releases.len(), 2);
+        assert_eq!(releases[0].tag, "v1.0.0");
+        assert!(!releases[0].prerelease);
+        assert_eq!(releases[0].assets.len(), 1);
+        assert_eq!(releases[0].assets[0].name, "tool-linux.tar.gz");
+        assert_eq!(releases[0].assets[0].size, 4);
+        assert!(releases[0].assets[0].local);
+        assert_eq!(
+            local_file_path(&releases[0].assets[0].download_url).unwrap(),
+            Some(dir.join("v1.0.0/tool-linux.tar.gz"))
+        );
+        assert!(releases[1].prerelease);
+        assert!(releases[1].assets.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_file_provider_reads_mirror_index() {
+        // --- Setup ---
+        let dir = repo_dir();
+        let mut runtime = runtime_with_tree(std::slice::from_ref(&dir), &[]);
+        let index = r#"{
+            "name": "owner/tool",
+            "source": "https://api.github.com",
+            "description": "A tool",
+            "releases": [{
+                "tag": "v1.0.0",
+                "published_at": "2024-01-01T00:00:00Z",
+                "files": [{"name": "tool.tar.gz", "size": 4, "sha256": "abc"}]
+            }]
+        }"#;
+        runtime
+            .expect_exists()
+            .with(eq(dir.join(MIRROR_INDEX)))
+            .returning(|_| true);
+        runtime
+            .expect_read_to_string()
+            .with(eq(dir.join(MIRROR_INDEX)))
+            .returning(move |_| Ok(index.to_string()));
+        let provider = FileProvider::new(Arc::new(runtime), API_URL);
+        let repo: RepoId = "owner/tool".parse().unwrap();
+
+        // --- Execute ---
+        let info = provider.get_repo_metadata(&repo).await.unwrap();
+        let releases = provider.get_releases(&repo).await.unwrap();
+
+        // --- Verify ---
+        assert_eq!(info.description.as_deref(), Some("A tool"));
+        assert_eq!(releases.len(), 1);
+        assert_eq!(
+            releases[0].published_at.as_deref(),
+            Some("2024-01-01T00:00:00Z")
+        );
+        // Only indexed files are assets, whatever else the directory holds
+        assert_eq!(releases[0].assets.len(), 1);
+        assert_eq!(
+            local_file_path(&releases[0].assets[0].download_url).unwrap(),
+            Some(dir.join("v1.0.0/tool.tar.gz"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_provider_missing_repo() {
+        let runtime = runtime_with_tree(&[], &[]);
+        let provider = FileProvider::new(Arc::new(runtime), API_URL);
+
+        let err = provider
+            .get_repo_metadata(&"owner/missing".parse().unwrap())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("owner/missing not found"));
+    }
+}
@@ -42,6 +42,9 @@ mod api {
         pub name: String,
         pub size: u64,
         pub browser_download_url: String,
+        /// API endpoint (`/repos/{owner}/{repo}/releases/assets/{id}`)
+        #[serde(default)]
+        pub url: Option<String>,
     }
 }
 
@@ -162,6 +165,8 @@ impl From<api::Asset> for ReleaseAsset {
             name: a.name,
             size: a.size,
             download_url: a.browser_download_url,
+            api_url: a.url,
+            local: false,
         }
     }
 }
@@ -199,6 +204,7 @@ mod tests {
                 name: "tool-linux-amd64".into(),
                 size: 1024,
                 browser_download_url: "https://example.com/asset".into(),
+                url: Some("https://api.github.com/repos/o/r/releases/assets/1".into()),
             }],
         };
 
@@ -208,5 +214,9 @@ mod tests {
         assert!(!release.prerelease);
         assert_eq!(release.assets.len(), 1);
         assert_eq!(release.assets[0].name, "tool-linux-amd64");
+        assert_eq!(
+            release.assets[0].api_url.as_deref(),
+            Some("https://api.github.com/repos/o/r/releases/assets/1")
+        );
     }
 }
@@ -4,7 +4,9 @@
 //! (GitHub, GitLab, Gitee, etc.), enabling multi-platform support.
 
 mod factory;
+mod file;
 mod github;
+mod url;
 
 use anyhow::Result;
 use async_trait::async_trait;
@@ -14,14 +16,18 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 pub use factory::{PackageSpec, ProviderFactory};
+pub use file::FileProvider;
+pub use url::UrlProvider;
 
 // Re-export domain models
 pub use crate::domain::model::{Release, ReleaseAsset};
 
 use crate::http::HttpClient;
+use crate::runtime::Runtime;
 
 /// Create a provider instance for the given kind and API URL.
 fn create_provider(
+    runtime: Arc<dyn Runtime>,
     http_client: HttpClient,
     kind: ProviderKind,
     api_url: &str,
@@ -31,6 +37,7 @@ fn create_provider(
             http_client,
             api_url,
         )),
+        ProviderKind::File => Arc::new(file::FileProvider::new(runtime, api_url)),
         _ => {
             unimplemented!("Provider kind {:?} is not yet implemented", kind)
         }
@@ -66,6 +73,22 @@ impl FromStr for RepoId {
     }
 }
 
+impl RepoId {
+    /// Package id of an alias of this repository: `owner/alias`
+    pub fn alias(&self, alias: &str) -> Result<RepoId> {
+        if alias.is_empty() || alias.contains(['/', '\\', '@']) || alias == "." || alias == ".." {
+            anyhow::bail!(
+                "Invalid alias '{}'. Expected a name without '/' or '@'.",
+                alias
+            );
+        }
+        Ok(RepoId {
+            owner: self.owner.clone(),
+            repo: alias.to_string(),
+        })
+    }
+}
+
 /// Repository metadata from the source.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RepoMetadata {
@@ -83,6 +106,10 @@ pub enum ProviderKind {
     GitHub,
     GitLab,
     Gitee,
+    /// Plain HTTPS downloads described by a `UrlSource`
+    Url,
+    /// A `file://` directory tree `<owner>/<repo>/<tag>/<assets>`
+    File,
 }
 
 impl fmt::Display for ProviderKind {
@@ -91,6 +118,8 @@ impl fmt::Display for ProviderKind {
             ProviderKind::GitHub => write!(f, "github"),
             ProviderKind::GitLab => write!(f, "gitlab"),
             ProviderKind::Gitee => write!(f, "gitee"),
+            ProviderKind::Url => write!(f, "url"),
+            ProviderKind::File => write!(f, "file"),
         }
     }
 }
@@ -103,8 +132,10 @@ impl FromStr for ProviderKind {
             "github" => Ok(ProviderKind::GitHub),
             "gitlab" => Ok(ProviderKind::GitLab),
             "gitee" => Ok(ProviderKind::Gitee),
+            "url" => Ok(ProviderKind::Url),
+            "file" => Ok(ProviderKind::File),
             _ => anyhow::bail!(
-                "Unknown provider kind: {}. Expected github, gitlab, or gitee.",
+                "Unknown provider kind: {}. Expected github, gitlab, gitee, url, or file.",
                 s
             ),
         }
@@ -165,6 +196,18 @@ mod tests {
         assert!("owner/".parse::<RepoId>().is_err());
     }
 
+    #[test]
+    fn test_repo_id_alias() {
+        let repo: RepoId = "BurntSushi/ripgrep".parse().unwrap();
+        assert_eq!(
+            repo.alias("rg-musl").unwrap().to_string(),
+            "BurntSushi/rg-musl"
+        );
+        assert!(repo.alias("").is_err());
+        assert!(repo.alias("a/b").is_err());
+        assert!(repo.alias("..").is_err());
+    }
+
     #[test]
     fn test_provider_kind_parse() {
         assert_eq!(
@@ -191,5 +234,6 @@ mod tests {
         assert_eq!(ProviderKind::GitHub.to_string(), "github");
         assert_eq!(ProviderKind::GitLab.to_string(), "gitlab");
         assert_eq!(ProviderKind::Gitee.to_string(), "gitee");
+        assert_eq!(ProviderKind::File.to_string(), "file");
     }
 }
@@ -0,0 +1,163 @@
+//! Direct-URL provider for tools published on plain HTTPS.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use log::debug;
+
+use crate::domain::model::UrlSource;
+use crate::http::HttpClient;
+use crate::platform::Platform;
+
+use super::{Provider, ProviderKind, Release, RepoId, RepoMetadata};
+
+/// Provider that synthesises releases from a URL template and discovered versions.
+pub struct UrlProvider {
+    http_client: HttpClient,
+    source: UrlSource,
+}
+
+impl UrlProvider {
+    /// Create a provider for a URL source.
+    pub fn new(http_client: HttpClient, source: UrlSource) -> Self {
+        Self {
+            http_client,
+            source,
+        }
+    }
+
+    async fn fetch_versions(&self) -> Result<Vec<String>> {
+        let document = match self.source.discovery_url() {
+            Some(url) => {
+                debug!("Fetching versions from {}...", url);
+                self.http_client
+                    .get_text(url)
+                    .await
+                    .with_context(|| format!("Failed to fetch versions from {}", url))?
+            }
+            None => String::new(),
+        };
+        self.source.versions_in(&document)
+    }
+}
+
+#[async_trait]
+impl Provider for UrlProvider {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::Url
+    }
+
+    fn api_url(&self) -> &str {
+        &self.source.url
+    }
+
+    async fn get_repo_metadata(&self, repo: &RepoId) -> Result<RepoMetadata> {
+        self.get_repo_metadata_at(repo, "").await
+    }
+
+    async fn get_releases(&self, repo: &RepoId) -> Result<Vec<Release>> {
+        self.get_releases_at(repo, "").await
+    }
+
+    /// There is no repository to describe; the download host serves as homepage.
+    async fn get_repo_metadata_at(&self, _repo: &RepoId, _api_url: &str) -> Result<RepoMetadata> {
+        let homepage = reqwest::Url::parse(&self.source.url)
+            .ok()
+            .and_then(|url| url.host_str().map(|host| format!("https://{}", host)));
+        Ok(RepoMetadata {
+            homepage,
+            ..Default::default()
+        })
+    }
+
+    async fn get_releases_at(&self, repo: &RepoId, _api_url: &str) -> Result<Vec<Release>> {
+        let versions = self.fetch_versions().await?;
+        if versions.is_empty() {
+            anyhow::bail!("No versions of {} found", repo);
+        }
+        let platform = Platform::detect();
+        Ok(versions
+            .iter()
+            .map(|v| self.source.release(v, &platform.os, &platform.arch))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::VersionDiscovery;
+    use reqwest::Client;
+    use std::collections::BTreeMap;
+
+    #[tokio::test]
+    async fn test_url_provider_discovers_releases() {
+        // --- Setup ---
+        let mut server = mockito::Server::new_async().await;
+        let listing = server
+            .mock("GET", "/tool/")
+            .with_status(200)
+            .with_body(r#"<a href="1.0.0/">1.0.0/</a><a href="1.1.0/">1.1.0/</a>"#)
+            .create_async()
+            .await;
+        let source = UrlSource {
+            url: format!(
+                "{}/tool/{{version}}/tool-{{os}}-{{arch}}.tar.gz",
+                server.url()
+            ),
+            versions: VersionDiscovery::Listing {
+                url: format!("{}/tool/", server.url()),
+                regex: r#"href="([\d.]+)/""#.into(),
+            },
+            os: BTreeMap::new(),
+            arch: BTreeMap::new(),
+        };
+        let provider = UrlProvider::new(HttpClient::new(Client::new()), source);
+        let repo: RepoId = "vendor/tool".parse().unwrap();
+
+        // --- Execute ---
+        let releases = provider.get_releases(&repo).await.unwrap();
+
+        // --- Verify ---
+        listing.assert_async().await;
+        assert_eq!(provider.kind(), ProviderKind::Url);
+        assert_eq!(releases.len(), 2);
+        assert_eq!(releases[1].tag, "1.1.0");
+        let platform = Platform::detect();
+        assert_eq!(
+            releases[1].assets[0].download_url,
+            format!(
+                "{}/tool/1.1.0/tool-{}-{}.tar.gz",
+                server.url(),
+                platform.os,
+                platform.arch
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_url_provider_no_versions() {
+        let mut server = mockito::Server::new_async().await;
+        let _index = server
+            .mock("GET", "/index.json")
+            .with_status(200)
+            .with_body(r#"{"versions": []}"#)
+            .create_async()
+            .await;
+        let source = UrlSource {
+            url: "https://dl.example.com/{version}/tool".into(),
+            versions: VersionDiscovery::Json {
+                url: format!("{}/index.json", server.url()),
+                path: "$.versions[*]".into(),
+            },
+            os: BTreeMap::new(),
+            arch: BTreeMap::new(),
+        };
+        let provider = UrlProvider::new(HttpClient::new(Client::new()), source);
+
+        let err = provider
+            .get_releases(&"vendor/tool".parse().unwrap())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No versions of vendor/tool"));
+    }
+}
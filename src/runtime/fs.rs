@@ -29,6 +29,11 @@ impl RealRuntime {
         fs::copy(from, to).context("Failed to copy file")
     }
 
+    #[tracing::instrument(skip(self))]
+    pub(crate) fn hard_link_impl(&self, original: &Path, link: &Path) -> Result<()> {
+        fs::hard_link(original, link).context("Failed to create hard link")
+    }
+
     #[tracing::instrument(skip(self))]
     pub(crate) fn create_dir_all_impl(&self, path: &Path) -> Result<()> {
         fs::create_dir_all(path).context("Failed to create directory")?;
@@ -80,6 +85,13 @@ impl RealRuntime {
         path.is_dir()
     }
 
+    #[tracing::instrument(skip(self))]
+    pub(crate) fn file_size_impl(&self, path: &Path) -> Result<u64> {
+        Ok(fs::metadata(path)
+            .context("Failed to read file metadata")?
+            .len())
+    }
+
     #[tracing::instrument(skip(self))]
     pub(crate) fn set_permissions_impl(&self, path: &Path, mode: u32) -> Result<()> {
         #[cfg(unix)]
@@ -94,6 +106,20 @@ impl RealRuntime {
         }
         Ok(())
     }
+
+    #[tracing::instrument(skip(self))]
+    pub(crate) fn is_executable_impl(&self, path: &Path) -> bool {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = path;
+            false
+        }
+    }
 }
 
 #[cfg(test)]
@@ -183,6 +209,32 @@ mod tests {
         }
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_real_runtime_is_executable() {
+        let runtime = RealRuntime;
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("tool");
+        runtime.write(&file_path, b"#!/bin/sh\n").unwrap();
+
+        runtime.set_permissions(&file_path, 0o644).unwrap();
+        assert!(!runtime.is_executable(&file_path));
+        runtime.set_permissions(&file_path, 0o744).unwrap();
+        assert!(runtime.is_executable(&file_path));
+        assert!(!runtime.is_executable(dir.path()));
+    }
+
+    #[test]
+    fn test_real_runtime_file_size() {
+        let runtime = RealRuntime;
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("tool.tar.gz");
+        runtime.write(&file_path, b"data").unwrap();
+
+        assert_eq!(runtime.file_size(&file_path).unwrap(), 4);
+        assert!(runtime.file_size(&dir.path().join("missing")).is_err());
+    }
+
     #[test]
     fn test_real_runtime_errors() {
         let runtime = RealRuntime;
@@ -10,10 +10,12 @@
 //! - `fs` - File system operations (read, write, directory)
 //! - `symlink` - Symlink operations (create, read, resolve, remove)
 //! - `user` - User interaction (confirmation prompts)
+//! - `process` - External command execution
 
 mod env;
 mod fs;
 pub mod path;
+mod process;
 mod symlink;
 mod user;
 
@@ -37,6 +39,7 @@ pub trait Runtime: Send + Sync {
     fn read_to_string(&self, path: &Path) -> Result<String>;
     fn rename(&self, from: &Path, to: &Path) -> Result<()>;
     fn copy(&self, from: &Path, to: &Path) -> Result<u64>;
+    fn hard_link(&self, original: &Path, link: &Path) -> Result<()>;
     fn create_dir_all(&self, path: &Path) -> Result<()>;
     fn remove_file(&self, path: &Path) -> Result<()>;
     fn remove_dir(&self, path: &Path) -> Result<()>;
@@ -61,9 +64,16 @@ pub trait Runtime: Send + Sync {
     fn remove_dir_all(&self, path: &Path) -> Result<()>;
     fn is_dir(&self, path: &Path) -> bool;
 
+    /// Size of a file in bytes, following symlinks.
+    fn file_size(&self, path: &Path) -> Result<u64>;
+
     /// Set file permissions (mode) on Unix systems. No-op on Windows.
     fn set_permissions(&self, path: &Path, mode: u32) -> Result<()>;
 
+    /// Whether a file has any execute permission bit set on Unix systems.
+    /// Always false on Windows.
+    fn is_executable(&self, path: &Path) -> bool;
+
     /// Remove a symlink if its target is under the given prefix directory.
     /// The prefix is checked by directory components, not string prefix.
     /// Returns Ok(true) if removed, Ok(false) if skipped, Err if operation failed.
@@ -86,6 +96,11 @@ pub trait Runtime: Send + Sync {
     // User interaction
     /// Prompt user for confirmation. Returns true if user confirms (y/yes), false otherwise.
     fn confirm(&self, prompt: &str) -> Result<bool>;
+
+    // Processes
+    /// Run an external command and return its standard output.
+    /// Fails if the command cannot be started or exits with a non-zero status.
+    fn run_command(&self, program: &str, args: &[String]) -> Result<String>;
 }
 
 // Blanket implementation for &R where R: Runtime
@@ -107,6 +122,9 @@ impl<R: Runtime + ?Sized> Runtime for &R {
     fn copy(&self, from: &Path, to: &Path) -> Result<u64> {
         (*self).copy(from, to)
     }
+    fn hard_link(&self, original: &Path, link: &Path) -> Result<()> {
+        (*self).hard_link(original, link)
+    }
     fn create_dir_all(&self, path: &Path) -> Result<()> {
         (*self).create_dir_all(path)
     }
@@ -152,9 +170,15 @@ impl<R: Runtime + ?Sized> Runtime for &R {
     fn is_dir(&self, path: &Path) -> bool {
         (*self).is_dir(path)
     }
+    fn file_size(&self, path: &Path) -> Result<u64> {
+        (*self).file_size(path)
+    }
     fn set_permissions(&self, path: &Path, mode: u32) -> Result<()> {
         (*self).set_permissions(path, mode)
     }
+    fn is_executable(&self, path: &Path) -> bool {
+        (*self).is_executable(path)
+    }
     fn remove_symlink_if_target_under(
         &self,
         link_path: &Path,
@@ -181,6 +205,9 @@ impl<R: Runtime + ?Sized> Runtime for &R {
     fn confirm(&self, prompt: &str) -> Result<bool> {
         (*self).confirm(prompt)
     }
+    fn run_command(&self, program: &str, args: &[String]) -> Result<String> {
+        (*self).run_command(program, args)
+    }
 }
 
 pub struct RealRuntime;
@@ -207,6 +234,10 @@ impl Runtime for RealRuntime {
         self.copy_impl(from, to)
     }
 
+    fn hard_link(&self, original: &Path, link: &Path) -> Result<()> {
+        self.hard_link_impl(original, link)
+    }
+
     fn create_dir_all(&self, path: &Path) -> Result<()> {
         self.create_dir_all_impl(path)
     }
@@ -267,10 +298,18 @@ impl Runtime for RealRuntime {
         self.is_dir_impl(path)
     }
 
+    fn file_size(&self, path: &Path) -> Result<u64> {
+        self.file_size_impl(path)
+    }
+
     fn set_permissions(&self, path: &Path, mode: u32) -> Result<()> {
         self.set_permissions_impl(path, mode)
     }
 
+    fn is_executable(&self, path: &Path) -> bool {
+        self.is_executable_impl(path)
+    }
+
     fn remove_symlink_if_target_under(
         &self,
         link_path: &Path,
@@ -303,4 +342,8 @@ impl Runtime for RealRuntime {
     fn confirm(&self, prompt: &str) -> Result<bool> {
         self.confirm_impl(prompt)
     }
+
+    fn run_command(&self, program: &str, args: &[String]) -> Result<String> {
+        self.run_command_impl(program, args)
+    }
 }
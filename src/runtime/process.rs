@@ -0,0 +1,48 @@
+//! External command execution.
+
+use anyhow::{Context, Result};
+use std::process::{Command, Stdio};
+
+use super::RealRuntime;
+
+impl RealRuntime {
+    #[tracing::instrument(skip(self, args))]
+    pub(crate) fn run_command_impl(&self, program: &str, args: &[String]) -> Result<String> {
+        let output = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::inherit())
+            .output()
+            .with_context(|| format!("Failed to run {}", program))?;
+
+        if !output.status.success() {
+            anyhow::bail!("{} exited with {}", program, output.status);
+        }
+
+        String::from_utf8(output.stdout)
+            .with_context(|| format!("{} printed invalid UTF-8", program))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::{RealRuntime, Runtime};
+
+    #[test]
+    #[cfg(unix)]
+    fn test_run_command() {
+        let runtime = RealRuntime;
+
+        let output = runtime
+            .run_command("sh", &["-c".into(), "echo hello".into()])
+            .unwrap();
+        assert_eq!(output, "hello\n");
+
+        assert!(
+            runtime
+                .run_command("sh", &["-c".into(), "exit 3".into()])
+                .is_err()
+        );
+        assert!(runtime.run_command("ghri-no-such-command", &[]).is_err());
+    }
+}
@@ -3,6 +3,7 @@ use assert_cmd::cargo;
 use flate2::Compression;
 use flate2::write::GzEncoder;
 use mockito::Server;
+use predicates::prelude::PredicateBooleanExt;
 use std::io::prelude::*;
 use tar::Builder;
 use tempfile::tempdir;
@@ -1289,3 +1290,610 @@ fn test_remove_nonexistent_package_fails() {
         .failure()
         .stderr(predicates::str::contains("not installed"));
 }
+
+#[cfg_attr(
+    ghri_skip_cross_windows_tests,
+    ignore = "cross windows tests disabled; set GHRI_RUN_CROSS_WINDOWS_TESTS=1 to enable"
+)]
+#[test]
+fn test_install_from_file_provider() {
+    // Releases laid out as <owner>/<repo>/<tag>/<assets>, no network involved
+    let releases_dir = tempdir().unwrap();
+    for tag in ["v1.0.0", "v1.1.0"] {
+        let tag_dir = releases_dir.path().join("owner/tool").join(tag);
+        std::fs::create_dir_all(&tag_dir).unwrap();
+        std::fs::write(
+            tag_dir.join("tool.tar.gz"),
+            create_tar_gz(&[("tool/VERSION", tag)]),
+        )
+        .unwrap();
+    }
+    let api_url = reqwest::Url::from_directory_path(releases_dir.path())
+        .unwrap()
+        .to_string();
+
+    let root_dir = tempdir().unwrap();
+    let install_root = root_dir.path();
+
+    Command::new(cargo::cargo_bin!("ghri"))
+        .arg("install")
+        .arg("-y")
+        .arg("owner/tool")
+        .arg("--root")
+        .arg(install_root)
+        .arg("--api-url")
+        .arg(&api_url)
+        .assert()
+        .success();
+
+    let version_file = install_root.join("owner/tool/v1.1.0/VERSION");
+    assert_eq!(std::fs::read_to_string(version_file).unwrap(), "v1.1.0");
+    let meta_content = std::fs::read_to_string(install_root.join("owner/tool/meta.json")).unwrap();
+    assert!(meta_content.contains(&api_url));
+}
+
+#[test]
+fn test_install_prune_overrides_generations() {
+    let releases_dir = tempdir().unwrap();
+    for tag in ["v1.0.0", "v1.1.0"] {
+        let tag_dir = releases_dir.path().join("owner/tool").join(tag);
+        std::fs::create_dir_all(&tag_dir).unwrap();
+        std::fs::write(
+            tag_dir.join("tool.tar.gz"),
+            create_tar_gz(&[("tool/VERSION", tag)]),
+        )
+        .unwrap();
+    }
+    let api_url = reqwest::Url::from_directory_path(releases_dir.path())
+        .unwrap()
+        .to_string();
+    let root_dir = tempdir().unwrap();
+    let install_root = root_dir.path();
+    let ghri = |args: &[&str]| {
+        Command::new(cargo::cargo_bin!("ghri"))
+            .args(args)
+            .arg("--root")
+            .arg(install_root)
+            .assert()
+    };
+
+    ghri(&["install", "-y", "owner/tool@v1.0.0", "--api-url", &api_url]).success();
+    // Generation 1 still references v1.0.0, but --prune was asked for explicitly
+    ghri(&["install", "-y", "--prune", "owner/tool@v1.1.0"])
+        .success()
+        .stdout(predicates::str::contains(
+            "Deleted generation(s) 1 that used v1.0.0",
+        ));
+
+    assert!(!install_root.join("owner/tool/v1.0.0").exists());
+    assert!(install_root.join("owner/tool/v1.1.0").exists());
+    ghri(&["generations", "list"])
+        .success()
+        .stdout(predicates::str::contains("install owner/tool@v1.0.0").not())
+        .stdout(predicates::str::contains("install owner/tool@v1.1.0"));
+}
+
+#[cfg_attr(
+    ghri_skip_cross_windows_tests,
+    ignore = "cross windows tests disabled; set GHRI_RUN_CROSS_WINDOWS_TESTS=1 to enable"
+)]
+#[test]
+fn test_install_local_artifact() {
+    let build_dir = tempdir().unwrap();
+    let artifact = build_dir.path().join("tool-1.2.3-linux-x86_64.tar.gz");
+    std::fs::write(&artifact, create_tar_gz(&[("tool/VERSION", "1.2.3")])).unwrap();
+
+    let root_dir = tempdir().unwrap();
+    let install_root = root_dir.path();
+
+    Command::new(cargo::cargo_bin!("ghri"))
+        .arg("install")
+        .arg("-y")
+        .arg(&artifact)
+        .arg("--as")
+        .arg("owner/tool@1.2.3")
+        .arg("--root")
+        .arg(install_root)
+        .assert()
+        .success();
+
+    let version_file = install_root.join("owner/tool/1.2.3/VERSION");
+    assert_eq!(std::fs::read_to_string(version_file).unwrap(), "1.2.3");
+
+    // Nothing to refresh for a package installed from files
+    Command::new(cargo::cargo_bin!("ghri"))
+        .arg("update")
+        .arg("--root")
+        .arg(install_root)
+        .assert()
+        .success();
+
+    // --as must name the version
+    Command::new(cargo::cargo_bin!("ghri"))
+        .arg("install")
+        .arg("-y")
+        .arg(&artifact)
+        .arg("--as")
+        .arg("owner/tool")
+        .arg("--root")
+        .arg(install_root)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--as OWNER/REPO@VERSION"));
+}
+
+#[cfg_attr(
+    ghri_skip_cross_windows_tests,
+    ignore = "cross windows tests disabled; set GHRI_RUN_CROSS_WINDOWS_TESTS=1 to enable"
+)]
+#[test]
+fn test_mirror_create_update_and_install_from_mirror() {
+    let mut server = Server::new();
+    let url = server.url();
+    let release_json = |tag: &str| {
+        format!(
+            r#"{{
+                "tag_name": "{tag}",
+                "tarball_url": "{url}/tarball/{tag}",
+                "prerelease": false,
+                "assets": [
+                    {{"name": "tool-linux.tar.gz", "size": 1, "browser_download_url": "{url}/download/{tag}/tool-linux.tar.gz"}},
+                    {{"name": "tool-macos.tar.gz", "size": 1, "browser_download_url": "{url}/download/{tag}/tool-macos.tar.gz"}}
+                ]
+            }}"#
+        )
+    };
+    let _repo = server
+        .mock("GET", "/repos/owner/tool")
+        .with_status(200)
+        .with_body(
+            r#"{
+                "description": "A tool",
+                "homepage": null,
+                "license": null,
+                "updated_at": "2024-01-01T00:00:00Z"
+            }"#,
+        )
+        .create();
+    let (releases_v1, _) = mock_releases(
+        &mut server,
+        "owner",
+        "tool",
+        &format!("[{}]", release_json("v1.0.0")),
+    );
+    for tag in ["v1.0.0", "v1.1.0"] {
+        server
+            .mock("GET", &*format!("/download/{}/tool-linux.tar.gz", tag))
+            .with_status(200)
+            .with_body(create_tar_gz(&[("tool/VERSION", tag)]))
+            .create();
+    }
+
+    let mirror_dir = tempdir().unwrap();
+    let mirror = mirror_dir.path();
+    Command::new(cargo::cargo_bin!("ghri"))
+        .args(["mirror", "create"])
+        .arg(mirror)
+        .args(["owner/tool@^1", "-f", "*linux*", "--api-url", &url])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("mirrored owner/tool v1.0.0"));
+    assert!(mirror.join("owner/tool/v1.0.0/tool-linux.tar.gz").exists());
+    assert!(!mirror.join("owner/tool/v1.0.0/tool-macos.tar.gz").exists());
+    assert!(mirror.join("owner/tool/v1.0.0/SHA256SUMS").exists());
+
+    // A new upstream release is added incrementally
+    releases_v1.remove();
+    let (_releases_v2, _) = mock_releases(
+        &mut server,
+        "owner",
+        "tool",
+        &format!("[{}, {}]", release_json("v1.1.0"), release_json("v1.0.0")),
+    );
+    Command::new(cargo::cargo_bin!("ghri"))
+        .args(["mirror", "update"])
+        .arg(mirror)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("mirrored owner/tool v1.1.0"))
+        .stdout(predicates::str::contains("mirrored owner/tool v1.0.0").not());
+    let index = std::fs::read_to_string(mirror.join("owner/tool/mirror.json")).unwrap();
+    assert!(index.contains("v1.1.0") && index.contains("v1.0.0"));
+
+    // The mirror serves as the release source
+    let root_dir = tempdir().unwrap();
+    let install_root = root_dir.path();
+    let mirror_url = reqwest::Url::from_directory_path(mirror)
+        .unwrap()
+        .to_string();
+    Command::new(cargo::cargo_bin!("ghri"))
+        .args([
+            "install",
+            "-y",
+            "owner/tool@1.0.0",
+            "--api-url",
+            &mirror_url,
+        ])
+        .arg("--root")
+        .arg(install_root)
+        .assert()
+        .success();
+    Command::new(cargo::cargo_bin!("ghri"))
+        .args(["upgrade", "-y"])
+        .arg("--root")
+        .arg(install_root)
+        .assert()
+        .success();
+    let version_file = install_root.join("owner/tool/v1.1.0/VERSION");
+    assert_eq!(std::fs::read_to_string(version_file).unwrap(), "v1.1.0");
+}
+
+#[test]
+fn test_install_from_mirror_server() {
+    let mirror_dir = tempdir().unwrap();
+    let release_dir = mirror_dir.path().join("owner/tool/v1.0.0");
+    std::fs::create_dir_all(&release_dir).unwrap();
+    std::fs::write(
+        release_dir.join("tool-linux.tar.gz"),
+        create_tar_gz(&[("tool/VERSION", "v1.0.0")]),
+    )
+    .unwrap();
+
+    let mut server = std::process::Command::new(cargo::cargo_bin!("ghri"))
+        .args(["mirror", "serve"])
+        .arg(mirror_dir.path())
+        .args(["--listen", "127.0.0.1:0"])
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    std::io::BufReader::new(server.stdout.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    let addr = line.trim().rsplit(' ').next().unwrap().to_string();
+    assert!(addr.starts_with("http://127.0.0.1:"), "{}", line);
+
+    let root_dir = tempdir().unwrap();
+    let install_root = root_dir.path();
+    let result = Command::new(cargo::cargo_bin!("ghri"))
+        .args(["install", "-y", "owner/tool", "--api-url", &addr])
+        .arg("--root")
+        .arg(install_root)
+        .ok();
+    server.kill().unwrap();
+    server.wait().unwrap();
+
+    result.unwrap();
+    let version_file = install_root.join("owner/tool/v1.0.0/VERSION");
+    assert_eq!(std::fs::read_to_string(version_file).unwrap(), "v1.0.0");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_link_shim_follows_upgrade_and_use() {
+    let releases_dir = tempdir().unwrap();
+    for tag in ["v1.0.0", "v1.1.0"] {
+        let tag_dir = releases_dir.path().join("owner/tool").join(tag);
+        std::fs::create_dir_all(&tag_dir).unwrap();
+        let script = format!("#!/bin/sh\necho \"$GREETING {} $*\"\n", tag);
+        std::fs::write(
+            tag_dir.join("tool.tar.gz"),
+            create_tar_gz_with_executable(&[
+                ("tool/run", &script, 0o755),
+                ("tool/README", "", 0o644),
+            ]),
+        )
+        .unwrap();
+    }
+    let api_url = reqwest::Url::from_directory_path(releases_dir.path())
+        .unwrap()
+        .to_string();
+    let root_dir = tempdir().unwrap();
+    let install_root = root_dir.path();
+    let shim = install_root.join("bin/tool");
+    let ghri = |args: &[&str]| {
+        Command::new(cargo::cargo_bin!("ghri"))
+            .args(args)
+            .arg("--root")
+            .arg(install_root)
+            .assert()
+            .success();
+    };
+    let run_shim = || {
+        let output = std::process::Command::new(&shim).arg("x").output().unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    ghri(&["install", "-y", "owner/tool@v1.0.0", "--api-url", &api_url]);
+    ghri(&[
+        "link",
+        "owner/tool:run",
+        shim.to_str().unwrap(),
+        "--shim",
+        "--env",
+        "GREETING=hello",
+        "--arg",
+        "--flag",
+    ]);
+    assert!(!shim.is_symlink());
+    assert_eq!(run_shim(), "hello v1.0.0 --flag x\n");
+
+    // The shim is rewritten for the new current version
+    ghri(&["upgrade", "-y"]);
+    assert_eq!(run_shim(), "hello v1.1.0 --flag x\n");
+    ghri(&["use", "owner/tool@v1.0.0"]);
+    assert_eq!(run_shim(), "hello v1.0.0 --flag x\n");
+
+    Command::new(cargo::cargo_bin!("ghri"))
+        .args(["links", "owner/tool", "--root"])
+        .arg(install_root)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("(shim)"))
+        .stdout(predicates::str::contains("[").not());
+
+    ghri(&["unlink", "owner/tool", shim.to_str().unwrap()]);
+    assert!(!shim.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_alias_upgrades_and_keeps_its_name() {
+    let releases_dir = tempdir().unwrap();
+    for tag in ["v1.0.0", "v1.1.0"] {
+        let tag_dir = releases_dir.path().join("owner/tool").join(tag);
+        std::fs::create_dir_all(&tag_dir).unwrap();
+        std::fs::write(
+            tag_dir.join("tool.tar.gz"),
+            create_tar_gz(&[("tool/VERSION", tag)]),
+        )
+        .unwrap();
+    }
+    let api_url = reqwest::Url::from_directory_path(releases_dir.path())
+        .unwrap()
+        .to_string();
+    let root_dir = tempdir().unwrap();
+    let install_root = root_dir.path();
+    let ghri = |args: &[&str]| {
+        Command::new(cargo::cargo_bin!("ghri"))
+            .args(args)
+            .arg("--root")
+            .arg(install_root)
+            .assert()
+    };
+
+    ghri(&[
+        "install",
+        "-y",
+        "owner/tool@v1.0.0",
+        "--as",
+        "tool-alias",
+        "--api-url",
+        &api_url,
+    ])
+    .success();
+
+    // The alias name is not a repository of its own
+    ghri(&["install", "-y", "owner/tool-alias"])
+        .failure()
+        .stderr(predicates::str::contains(
+            "owner/tool-alias is an alias installed from owner/tool",
+        ));
+
+    ghri(&["upgrade", "-y"]).success();
+    let version_file = install_root.join("owner/tool-alias/v1.1.0/VERSION");
+    assert_eq!(std::fs::read_to_string(version_file).unwrap(), "v1.1.0");
+    assert!(!install_root.join("owner/tool").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_link_copy_and_hardlink_follow_upgrade() {
+    let releases_dir = tempdir().unwrap();
+    for tag in ["v1.0.0", "v1.1.0"] {
+        let tag_dir = releases_dir.path().join("owner/tool").join(tag);
+        std::fs::create_dir_all(&tag_dir).unwrap();
+        std::fs::write(
+            tag_dir.join("tool.tar.gz"),
+            create_tar_gz_with_executable(&[("tool/run", tag, 0o755), ("tool/README", "", 0o644)]),
+        )
+        .unwrap();
+    }
+    let api_url = reqwest::Url::from_directory_path(releases_dir.path())
+        .unwrap()
+        .to_string();
+    let root_dir = tempdir().unwrap();
+    let install_root = root_dir.path();
+    let copy = install_root.join("bin/tool-copy");
+    let hard = install_root.join("bin/tool-hard");
+    let ghri = |args: &[&str]| {
+        Command::new(cargo::cargo_bin!("ghri"))
+            .args(args)
+            .arg("--root")
+            .arg(install_root)
+            .assert()
+            .success()
+    };
+
+    ghri(&["install", "-y", "owner/tool@v1.0.0", "--api-url", &api_url]);
+    ghri(&[
+        "link",
+        "owner/tool:run",
+        copy.to_str().unwrap(),
+        "--mode",
+        "copy",
+    ]);
+    ghri(&[
+        "link",
+        "owner/tool:run",
+        hard.to_str().unwrap(),
+        "--mode",
+        "hardlink",
+    ]);
+    assert!(!copy.is_symlink() && !hard.is_symlink());
+    assert_eq!(std::fs::read_to_string(&copy).unwrap(), "v1.0.0");
+
+    // Both are placed again for the new current version
+    ghri(&["upgrade", "-y"]);
+    assert_eq!(std::fs::read_to_string(&copy).unwrap(), "v1.1.0");
+    assert_eq!(std::fs::read_to_string(&hard).unwrap(), "v1.1.0");
+    ghri(&["links", "owner/tool"])
+        .stdout(predicates::str::contains("(copy)"))
+        .stdout(predicates::str::contains("(hardlink)"))
+        .stdout(predicates::str::contains("[").not());
+
+    // A copy edited by the user is no longer replaced or removed
+    std::fs::write(&copy, "edited").unwrap();
+    ghri(&["links", "owner/tool"]).stdout(predicates::str::contains("[modified]"));
+    ghri(&["use", "owner/tool@v1.0.0"]);
+    assert_eq!(std::fs::read_to_string(&copy).unwrap(), "edited");
+    assert_eq!(std::fs::read_to_string(&hard).unwrap(), "v1.0.0");
+    ghri(&["unlink", "owner/tool", "--all"]);
+    assert_eq!(std::fs::read_to_string(&copy).unwrap(), "edited");
+    assert!(!hard.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_install_bin_dir_links_executables() {
+    let releases_dir = tempdir().unwrap();
+    let releases: [(&str, &[&str]); 2] = [
+        ("v1.0.0", &["tool", "tool-legacy"]),
+        ("v1.1.0", &["tool", "tool-helper"]),
+    ];
+    for (tag, executables) in releases {
+        let tag_dir = releases_dir.path().join("owner/tool").join(tag);
+        std::fs::create_dir_all(&tag_dir).unwrap();
+        let script = format!("#!/bin/sh\necho {}\n", tag);
+        let mut files: Vec<(String, &str, u32)> = executables
+            .iter()
+            .map(|name| (format!("tool/bin/{}", name), script.as_str(), 0o755))
+            .collect();
+        files.push(("tool/share/doc/README".into(), "docs", 0o644));
+        files.push(("tool/install.sh".into(), "#!/bin/sh\n", 0o644));
+        files.push(("tool/tool-run".into(), "#!/bin/sh\n", 0o755));
+        let files: Vec<(&str, &str, u32)> = files
+            .iter()
+            .map(|(path, content, mode)| (path.as_str(), *content, *mode))
+            .collect();
+        std::fs::write(
+            tag_dir.join("tool.tar.gz"),
+            create_tar_gz_with_executable(&files),
+        )
+        .unwrap();
+    }
+    let api_url = reqwest::Url::from_directory_path(releases_dir.path())
+        .unwrap()
+        .to_string();
+    let root_dir = tempdir().unwrap();
+    let install_root = root_dir.path().join("root");
+    let bin_dir = root_dir.path().join("bin");
+
+    Command::new(cargo::cargo_bin!("ghri"))
+        .args(["install", "-y", "owner/tool@v1.0.0", "--api-url", &api_url])
+        .arg("--bin-dir")
+        .arg(&bin_dir)
+        .arg("--root")
+        .arg(&install_root)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("linked"));
+    assert!(bin_dir.join("tool").is_symlink());
+    assert!(bin_dir.join("tool-legacy").is_symlink());
+    assert!(!bin_dir.join("README").exists());
+    // Outside `bin/` only scripts with an execute bit are linked
+    assert!(!bin_dir.join("install.sh").exists());
+    assert!(bin_dir.join("tool-run").is_symlink());
+
+    // The saved bin directory picks up new executables and drops removed ones
+    Command::new(cargo::cargo_bin!("ghri"))
+        .args(["upgrade", "-y", "--root"])
+        .arg(&install_root)
+        .assert()
+        .success();
+    let output = std::process::Command::new(bin_dir.join("tool"))
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "v1.1.0\n");
+    assert!(bin_dir.join("tool-helper").is_symlink());
+    assert!(!bin_dir.join("tool-legacy").is_symlink());
+
+    // Switching versions relinks the executables of the version switched to
+    Command::new(cargo::cargo_bin!("ghri"))
+        .args(["rollback", "owner/tool", "--root"])
+        .arg(&install_root)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("unlinked"));
+    let output = std::process::Command::new(bin_dir.join("tool"))
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "v1.0.0\n");
+    assert!(!bin_dir.join("tool-helper").is_symlink());
+    assert!(bin_dir.join("tool-legacy").is_symlink());
+
+    Command::new(cargo::cargo_bin!("ghri"))
+        .args(["use", "owner/tool@v1.1.0", "--root"])
+        .arg(&install_root)
+        .assert()
+        .success();
+    assert!(bin_dir.join("tool-helper").is_symlink());
+    assert!(!bin_dir.join("tool-legacy").is_symlink());
+
+    Command::new(cargo::cargo_bin!("ghri"))
+        .args(["remove", "-y", "owner/tool", "--root"])
+        .arg(&install_root)
+        .assert()
+        .success();
+    assert!(!bin_dir.join("tool").is_symlink());
+    assert!(!bin_dir.join("tool-helper").is_symlink());
+}
+
+#[test]
+fn test_generations_recorded_by_mutating_commands() {
+    let releases_dir = tempdir().unwrap();
+    let tag_dir = releases_dir.path().join("owner/tool/v1.0.0");
+    std::fs::create_dir_all(&tag_dir).unwrap();
+    std::fs::write(
+        tag_dir.join("tool.tar.gz"),
+        create_tar_gz(&[("tool/VERSION", "v1.0.0")]),
+    )
+    .unwrap();
+    let api_url = reqwest::Url::from_directory_path(releases_dir.path())
+        .unwrap()
+        .to_string();
+    let root_dir = tempdir().unwrap();
+    let install_root = root_dir.path().join("root");
+    let dest = root_dir.path().join("bin/tool");
+    let ghri = |args: &[&str]| {
+        Command::new(cargo::cargo_bin!("ghri"))
+            .args(args)
+            .arg("--root")
+            .arg(&install_root)
+            .assert()
+    };
+
+    ghri(&["install", "-y", "owner/tool@v1.0.0", "--api-url", &api_url]).success();
+    // Listing versions changes nothing
+    ghri(&["use", "owner/tool"]).success();
+    ghri(&["link", "owner/tool", dest.to_str().unwrap()]).success();
+    ghri(&["unlink", "owner/tool", "--all"]).success();
+
+    let output = ghri(&["generations", "list"])
+        .success()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let commands: Vec<&str> = stdout
+        .lines()
+        .map(|line| line.split("UTC  ").nth(1).unwrap())
+        .collect();
+    assert_eq!(
+        commands,
+        vec![
+            "install owner/tool@v1.0.0 (1 package(s))",
+            "link owner/tool (1 package(s))",
+            "unlink owner/tool (1 package(s))",
+        ]
+    );
+}